@@ -1,5 +1,5 @@
 // SQLite compile flags tuned for WASM: no threads/dlopen, keep common extensions.
-const FULL_FEATURED: [&str; 23] = [
+const FULL_FEATURED: [&str; 24] = [
     "-DSQLITE_OS_OTHER",
     "-DSQLITE_USE_URI",
     // SQLite is configured for a single-threaded environment, as WebAssembly is single-threaded by default.
@@ -26,6 +26,7 @@ const FULL_FEATURED: [&str; 23] = [
     "-DSQLITE_ENABLE_STMTVTAB",
     "-DSQLITE_ENABLE_UNKNOWN_SQL_FUNCTION",
     "-DSQLITE_ENABLE_COLUMN_METADATA",
+    "-DSQLITE_ENABLE_STMT_SCANSTATUS",
 ];
 
 #[cfg(feature = "sqlite3mc")]
@@ -45,6 +46,11 @@ fn main() {
 
     compile();
 
+    // Not set when a prebuilt library overrides this build script, see
+    // `src/shim.rs`.
+    println!("cargo::rustc-check-cfg=cfg(bundled_sqlite)");
+    println!("cargo::rustc-cfg=bundled_sqlite");
+
     #[cfg(feature = "bindgen")]
     {
         let update_bindgen = std::env::var(UPDATE_BINDGEN_ENV).is_ok();
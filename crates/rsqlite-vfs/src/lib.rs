@@ -16,6 +16,7 @@ use alloc::string::String;
 use alloc::vec::Vec;
 use alloc::{boxed::Box, ffi::CString};
 use alloc::{format, vec};
+use core::marker::PhantomData;
 use core::time::Duration;
 use core::{cell::RefCell, ffi::CStr, ops::Deref};
 use ffi::*;
@@ -69,6 +70,20 @@ macro_rules! check_result {
     };
 }
 
+/// A macro to evaluate an expression returning a SQLite result code, returning
+/// the code early unless it is SQLITE_OK.
+///
+/// Expands to a block, so it can be used as an expression of type `()`.
+#[macro_export]
+macro_rules! try_sqlite {
+    ($ex:expr) => {{
+        let code = $ex;
+        if code != $crate::ffi::SQLITE_OK {
+            return code;
+        }
+    }};
+}
+
 /// A macro to explicitly mark a parameter as unused, suppressing compiler warnings.
 #[macro_export]
 macro_rules! unused {
@@ -82,11 +97,16 @@ pub const SQLITE3_HEADER: &str = "SQLite format 3";
 
 /// Generates a random, temporary filename, typically used when SQLite requests a file with a NULL name.
 pub fn random_name(randomness: fn(&mut [u8])) -> String {
+    random_name_with_len(randomness, 32)
+}
+
+/// Generates a random alphanumeric name of exactly `len` characters.
+pub fn random_name_with_len(randomness: fn(&mut [u8]), len: usize) -> String {
     const GEN_ASCII_STR_CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ\
                 abcdefghijklmnopqrstuvwxyz\
                 0123456789";
     const GEN_LEN: u8 = GEN_ASCII_STR_CHARSET.len() as u8;
-    let mut random_buffer = [0; 32];
+    let mut random_buffer = vec![0; len];
     randomness(&mut random_buffer);
     random_buffer
         .into_iter()
@@ -345,6 +365,54 @@ pub fn register_vfs<IO: SQLiteIoMethods, V: SQLiteVfs<IO>>(
     Ok(vfs as *mut sqlite3_vfs)
 }
 
+/// A VFS registered for the lifetime of the guard.
+///
+/// Registers like [`register_vfs`] on creation, and unregisters the VFS and
+/// frees its name and app data on drop, so tests can register custom VFSes
+/// without leaking them into the global registry for later tests.
+///
+/// All connections using the VFS must be closed before the guard is dropped.
+pub struct ScopedVfs<IO: SQLiteIoMethods> {
+    vfs: *mut sqlite3_vfs,
+    _marker: PhantomData<IO>,
+}
+
+impl<IO: SQLiteIoMethods> ScopedVfs<IO> {
+    /// Register the VFS `V` as `vfs_name`, see [`register_vfs`].
+    pub fn register<V: SQLiteVfs<IO>>(
+        vfs_name: &str,
+        app_data: IO::AppData,
+        default_vfs: bool,
+    ) -> Result<Self, RegisterVfsError> {
+        let vfs = register_vfs::<IO, V>(vfs_name, app_data, default_vfs)?;
+        Ok(Self {
+            vfs,
+            _marker: PhantomData,
+        })
+    }
+
+    /// The registered `sqlite3_vfs`.
+    pub fn as_ptr(&self) -> *mut sqlite3_vfs {
+        self.vfs
+    }
+
+    /// The app data of the VFS.
+    pub fn app_data(&self) -> &VfsAppData<IO::AppData> {
+        unsafe { IO::Store::app_data(self.vfs) }
+    }
+}
+
+impl<IO: SQLiteIoMethods> Drop for ScopedVfs<IO> {
+    fn drop(&mut self) {
+        unsafe {
+            sqlite3_vfs_unregister(self.vfs);
+            let vfs = Box::from_raw(self.vfs);
+            drop(CString::from_raw(vfs.zName.cast_mut()));
+            drop(VfsAppData::<IO::AppData>::from_raw(vfs.pAppData.cast()));
+        }
+    }
+}
+
 /// A container for VFS-specific errors, holding both an error code and a descriptive message.
 #[derive(Debug)]
 pub struct VfsError {
@@ -1056,7 +1124,7 @@ pub mod test_suite {
 
 #[cfg(test)]
 mod tests {
-    use crate::random_name;
+    use crate::{random_name, random_name_with_len};
     use crate::{MemChunksFile, VfsFile};
 
     #[test]
@@ -1118,5 +1186,7 @@ mod tests {
         assert!(name_1.is_ascii(), "Expected an ascii-name: `{name_1}`");
         assert!(name_2.is_ascii(), "Expected an ascii-name: `{name_2}`");
         assert_ne!(name_1, name_2);
+        assert_eq!(32, name_1.len());
+        assert_eq!(12, random_name_with_len(random, 12).len());
     }
 }
@@ -3,6 +3,9 @@
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 
+/// localStorage VFS implementation for tiny databases used from the main thread.
+pub mod localstorage;
+
 /// IndexedDB VFS implementation with relaxed durability guarantees.
 pub mod relaxed_idb;
 
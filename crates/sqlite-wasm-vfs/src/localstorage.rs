@@ -0,0 +1,807 @@
+//! localstorage vfs implementation
+//!
+//! ```rust
+//! use sqlite_wasm_rs as ffi;
+//! use sqlite_wasm_vfs::localstorage::{
+//!     install as install_localstorage_vfs,
+//!     LocalStorageCfg
+//! };
+//!
+//! fn open_db() {
+//!     // install localstorage persistent vfs and set as default vfs
+//!     install_localstorage_vfs::<ffi::WasmOsCallback>(&LocalStorageCfg::default(), true)
+//!         .unwrap();
+//!
+//!     // open with localstorage vfs
+//!     let mut db = std::ptr::null_mut();
+//!     let ret = unsafe {
+//!         ffi::sqlite3_open_v2(
+//!             c"settings.db".as_ptr().cast(),
+//!             &mut db as *mut _,
+//!             ffi::SQLITE_OPEN_READWRITE | ffi::SQLITE_OPEN_CREATE,
+//!             std::ptr::null()
+//!         )
+//!     };
+//!     assert_eq!(ffi::SQLITE_OK, ret);
+//! }
+//! ```
+//!
+//! A VFS for very small databases, such as settings, that must be usable from the
+//! main thread without OPFS and without waiting for IndexedDB.
+//!
+//! Every page of a database is stored base64-encoded in `localStorage`, under the key
+//! `{vfs_name}:{offset}:{filename}`. All pages are loaded into memory on install, and
+//! the pages changed by a transaction are written back synchronously when it commits.
+//! Journals stay in memory, so closing the page in the middle of a commit may leave a
+//! partially written database.
+//!
+//! `localStorage` is limited to about 5MB per origin, and base64 adds a third on top
+//! of the page size. The VFS enforces `LocalStorageCfg::quota` itself, a commit that
+//! would exceed it fails with `SQLITE_FULL` and leaves the stored database unchanged.
+//! If `localStorage` runs out of space first, e.g. because other data of the origin
+//! uses it, the blocks already written by the commit are restored to their previous
+//! values before it fails with `SQLITE_FULL`.
+//!
+//! `localStorage` is shared by all pages of an origin, but each page keeps its own copy
+//! in memory, so using it on multiple pages at once may cause DB corruption.
+
+use rsqlite_vfs::{
+    bail, check_option, check_result,
+    ffi::{
+        sqlite3_file, sqlite3_vfs, SQLITE_ERROR, SQLITE_FCNTL_COMMIT_PHASETWO, SQLITE_FCNTL_PRAGMA,
+        SQLITE_FCNTL_SYNC, SQLITE_FULL, SQLITE_IOERR, SQLITE_IOERR_DELETE, SQLITE_NOTFOUND,
+        SQLITE_OK, SQLITE_OPEN_MAIN_DB,
+    },
+    register_vfs, registered_vfs, MemChunksFile, OsCallback, RegisterVfsError, SQLiteIoMethods,
+    SQLiteVfs, SQLiteVfsFile, VfsAppData, VfsError, VfsFile, VfsResult, VfsStore,
+};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::ffi::{c_char, CStr};
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use js_sys::Reflect;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::Storage;
+
+use crate::relaxed_idb::page_read;
+
+type Result<T> = std::result::Result<T, LocalStorageError>;
+
+/// Bytes taken up in `localStorage`, which stores keys and values as UTF-16.
+fn entry_size(key: &str, value: &str) -> usize {
+    (key.len() + value.len()) * 2
+}
+
+enum LsFile {
+    Main(LsPageFile),
+    Temp(MemChunksFile),
+}
+
+impl LsFile {
+    fn new(flags: i32) -> Self {
+        if flags & SQLITE_OPEN_MAIN_DB == 0 {
+            Self::Temp(MemChunksFile::default())
+        } else {
+            Self::Main(LsPageFile::default())
+        }
+    }
+}
+
+#[derive(Default)]
+struct LsPageFile {
+    file_size: usize,
+    block_size: usize,
+    blocks: HashMap<usize, Vec<u8>>,
+    tx_blocks: HashSet<usize>,
+    /// Size of the `localStorage` entry of each stored block.
+    stored: HashMap<usize, usize>,
+}
+
+impl VfsFile for LsPageFile {
+    fn read(&self, buf: &mut [u8], offset: usize) -> VfsResult<bool> {
+        Ok(page_read(
+            buf,
+            self.block_size,
+            self.file_size,
+            offset,
+            |addr| self.blocks.get(&addr),
+            |page, buf, (start, end)| buf.copy_from_slice(&page[start..end]),
+        ))
+    }
+
+    fn write(&mut self, buf: &[u8], offset: usize) -> VfsResult<()> {
+        let page_size = buf.len();
+
+        for fill in (self.file_size..offset).step_by(page_size) {
+            self.blocks.insert(fill, vec![0; page_size]);
+            self.tx_blocks.insert(fill);
+        }
+
+        self.blocks.insert(offset, buf.to_vec());
+        self.tx_blocks.insert(offset);
+        self.block_size = page_size;
+        self.file_size = self.file_size.max(offset + page_size);
+        Ok(())
+    }
+
+    fn truncate(&mut self, size: usize) -> VfsResult<()> {
+        self.file_size = size;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> VfsResult<()> {
+        Ok(())
+    }
+
+    fn size(&self) -> VfsResult<usize> {
+        Ok(self.file_size)
+    }
+}
+
+impl VfsFile for LsFile {
+    fn read(&self, buf: &mut [u8], offset: usize) -> VfsResult<bool> {
+        match self {
+            LsFile::Main(page_file) => page_file.read(buf, offset),
+            LsFile::Temp(mem_chunks_file) => mem_chunks_file.read(buf, offset),
+        }
+    }
+
+    fn write(&mut self, buf: &[u8], offset: usize) -> VfsResult<()> {
+        match self {
+            LsFile::Main(page_file) => page_file.write(buf, offset),
+            LsFile::Temp(mem_chunks_file) => mem_chunks_file.write(buf, offset),
+        }
+    }
+
+    fn truncate(&mut self, size: usize) -> VfsResult<()> {
+        match self {
+            LsFile::Main(page_file) => page_file.truncate(size),
+            LsFile::Temp(mem_chunks_file) => mem_chunks_file.truncate(size),
+        }
+    }
+
+    fn flush(&mut self) -> VfsResult<()> {
+        match self {
+            LsFile::Main(page_file) => page_file.flush(),
+            LsFile::Temp(mem_chunks_file) => mem_chunks_file.flush(),
+        }
+    }
+
+    fn size(&self) -> VfsResult<usize> {
+        match self {
+            LsFile::Main(page_file) => page_file.size(),
+            LsFile::Temp(mem_chunks_file) => mem_chunks_file.size(),
+        }
+    }
+}
+
+struct LocalStorage {
+    storage: Storage,
+    vfs_name: String,
+    quota: usize,
+    used: Cell<usize>,
+    name2file: RefCell<HashMap<String, LsFile>>,
+}
+
+impl LocalStorage {
+    fn new(options: &LocalStorageCfg) -> Result<Self> {
+        let storage = Reflect::get(&js_sys::global(), &JsValue::from("localStorage"))
+            .ok()
+            .filter(|storage| !storage.is_undefined() && !storage.is_null())
+            .ok_or_else(|| {
+                LocalStorageError::Generic("localStorage is not available in this context".into())
+            })?
+            .unchecked_into::<Storage>();
+
+        let pool = LocalStorage {
+            storage,
+            vfs_name: options.vfs_name.clone(),
+            quota: options.quota,
+            used: Cell::new(0),
+            name2file: RefCell::new(HashMap::new()),
+        };
+        pool.load()?;
+        Ok(pool)
+    }
+
+    fn key(&self, file: &str, offset: usize) -> String {
+        format!("{}:{offset}:{file}", self.vfs_name)
+    }
+
+    fn stored_keys(&self) -> Result<Vec<String>> {
+        let prefix = format!("{}:", self.vfs_name);
+        let len = self.storage.length().map_err(LocalStorageError::from_js)?;
+        let mut keys = Vec::new();
+        for idx in 0..len {
+            if let Some(key) = self.storage.key(idx).map_err(LocalStorageError::from_js)? {
+                if key.starts_with(&prefix) {
+                    keys.push(key);
+                }
+            }
+        }
+        Ok(keys)
+    }
+
+    /// Read every block of this VFS from `localStorage` into memory.
+    fn load(&self) -> Result<()> {
+        let mut name2file = HashMap::new();
+        let mut used = 0;
+
+        for key in self.stored_keys()? {
+            let corrupt = || LocalStorageError::Generic(format!("invalid entry {key}"));
+            let (offset, file) = key[self.vfs_name.len() + 1..]
+                .split_once(':')
+                .ok_or_else(corrupt)?;
+            let offset = offset.parse::<usize>().map_err(|_| corrupt())?;
+            let value = self
+                .storage
+                .get_item(&key)
+                .map_err(LocalStorageError::from_js)?
+                .ok_or_else(corrupt)?;
+            let data = STANDARD.decode(&value).map_err(|_| corrupt())?;
+
+            let LsFile::Main(page_file) = name2file
+                .entry(file.to_string())
+                .or_insert_with(|| LsFile::Main(LsPageFile::default()))
+            else {
+                unreachable!();
+            };
+            page_file.block_size = data.len();
+            page_file.file_size = page_file.file_size.max(offset + data.len());
+            page_file.stored.insert(offset, entry_size(&key, &value));
+            page_file.blocks.insert(offset, data);
+            used += entry_size(&key, &value);
+        }
+
+        self.used.set(used);
+        *self.name2file.borrow_mut() = name2file;
+        Ok(())
+    }
+
+    /// Write the blocks changed since the last sync to `localStorage`.
+    fn sync_file(&self, file: &str, page_file: &mut LsPageFile) -> VfsResult<()> {
+        let truncated = page_file
+            .stored
+            .keys()
+            .copied()
+            .filter(|&offset| offset >= page_file.file_size)
+            .collect::<Vec<_>>();
+        let mut entries = Vec::new();
+        for &offset in &page_file.tx_blocks {
+            if offset >= page_file.file_size {
+                continue;
+            }
+            if let Some(block) = page_file.blocks.get(&offset) {
+                let key = self.key(file, offset);
+                let value = STANDARD.encode(block);
+                entries.push((offset, key, value));
+            }
+        }
+
+        // Check the quota before writing anything, so that a commit that does
+        // not fit leaves the stored database untouched.
+        let freed = truncated
+            .iter()
+            .chain(entries.iter().map(|(offset, _, _)| offset))
+            .filter_map(|offset| page_file.stored.get(offset))
+            .sum::<usize>();
+        let added = entries
+            .iter()
+            .map(|(_, key, value)| entry_size(key, value))
+            .sum::<usize>();
+        let used = self.used.get() - freed + added;
+        if used > self.quota {
+            return Err(VfsError::new(
+                SQLITE_FULL,
+                format!(
+                    "localstorage quota exceeded: {file} needs {used} bytes, the quota is {} bytes",
+                    self.quota
+                ),
+            ));
+        }
+
+        // The origin may run out of space before the quota is reached, keep the
+        // previous values so a commit that fails partway can be rolled back.
+        let mut written = Vec::new();
+        for (_, key, value) in &entries {
+            let ret = self.storage.get_item(key).and_then(|old| {
+                self.storage.set_item(key, value)?;
+                Ok(old)
+            });
+            match ret {
+                Ok(old) => written.push((key, old)),
+                Err(err) => {
+                    for (key, old) in written.into_iter().rev() {
+                        let _ = match old {
+                            Some(old) => self.storage.set_item(key, &old),
+                            None => self.storage.remove_item(key),
+                        };
+                    }
+                    return Err(VfsError::new(
+                        SQLITE_FULL,
+                        format!("failed to write {key} to localStorage: {err:?}"),
+                    ));
+                }
+            }
+        }
+
+        for (offset, key, value) in entries {
+            let size = entry_size(&key, &value);
+            let old = page_file.stored.insert(offset, size).unwrap_or(0);
+            self.used.set(self.used.get() - old + size);
+            page_file.tx_blocks.remove(&offset);
+        }
+        for offset in truncated {
+            let _ = self.storage.remove_item(&self.key(file, offset));
+            let old = page_file.stored.remove(&offset).unwrap_or(0);
+            self.used.set(self.used.get() - old);
+        }
+        let file_size = page_file.file_size;
+        page_file.blocks.retain(|&offset, _| offset < file_size);
+        page_file.tx_blocks.clear();
+
+        Ok(())
+    }
+
+    fn remove_file(&self, file: &str, page_file: &LsPageFile) {
+        for (offset, size) in &page_file.stored {
+            let _ = self.storage.remove_item(&self.key(file, *offset));
+            self.used.set(self.used.get() - size);
+        }
+    }
+
+    fn export_db(&self, name: &str) -> Result<Vec<u8>> {
+        let name2file = self.name2file.borrow();
+
+        match name2file.get(name) {
+            Some(LsFile::Main(file)) => {
+                let mut ret = vec![0; file.file_size];
+                file.read(&mut ret, 0).unwrap();
+                Ok(ret)
+            }
+            Some(LsFile::Temp(_)) => Err(LocalStorageError::Generic(
+                "Does not support dumping temporary files".into(),
+            )),
+            None => Err(LocalStorageError::Generic(
+                "The file to be exported does not exist".into(),
+            )),
+        }
+    }
+
+    fn delete_db(&self, name: &str) {
+        if let Some(LsFile::Main(file)) = self.name2file.borrow_mut().remove(name) {
+            self.remove_file(name, &file);
+        }
+    }
+
+    fn clear_all(&self) -> Result<()> {
+        std::mem::take(&mut *self.name2file.borrow_mut());
+        for key in self.stored_keys()? {
+            self.storage
+                .remove_item(&key)
+                .map_err(LocalStorageError::from_js)?;
+        }
+        self.used.set(0);
+        Ok(())
+    }
+}
+
+struct LocalStorageStore;
+
+impl VfsStore<LsFile, LocalStorage> for LocalStorageStore {
+    fn add_file(vfs: *mut sqlite3_vfs, file: &str, flags: i32) -> VfsResult<()> {
+        let pool = unsafe { Self::app_data(vfs) };
+        pool.name2file
+            .borrow_mut()
+            .insert(file.into(), LsFile::new(flags));
+        Ok(())
+    }
+
+    fn contains_file(vfs: *mut sqlite3_vfs, file: &str) -> VfsResult<bool> {
+        let pool = unsafe { Self::app_data(vfs) };
+        Ok(pool.name2file.borrow().contains_key(file))
+    }
+
+    fn delete_file(vfs: *mut sqlite3_vfs, file: &str) -> VfsResult<()> {
+        let pool = unsafe { Self::app_data(vfs) };
+        let ls_file = match pool.name2file.borrow_mut().remove(file) {
+            Some(file) => file,
+            None => {
+                return Err(VfsError::new(
+                    SQLITE_IOERR_DELETE,
+                    format!("{file} not found"),
+                ))
+            }
+        };
+        // temp files are never written to localStorage
+        if let LsFile::Main(page_file) = &ls_file {
+            pool.remove_file(file, page_file);
+        }
+        Ok(())
+    }
+
+    fn with_file<F: Fn(&LsFile) -> VfsResult<i32>>(
+        vfs_file: &SQLiteVfsFile,
+        f: F,
+    ) -> VfsResult<i32> {
+        let name = unsafe { vfs_file.name() };
+        let pool = unsafe { Self::app_data(vfs_file.vfs) };
+        match pool.name2file.borrow().get(name) {
+            Some(file) => f(file),
+            None => Err(VfsError::new(SQLITE_IOERR, format!("{name} not found"))),
+        }
+    }
+
+    fn with_file_mut<F: Fn(&mut LsFile) -> VfsResult<i32>>(
+        vfs_file: &SQLiteVfsFile,
+        f: F,
+    ) -> VfsResult<i32> {
+        let name = unsafe { vfs_file.name() };
+        let pool = unsafe { Self::app_data(vfs_file.vfs) };
+        match pool.name2file.borrow_mut().get_mut(name) {
+            Some(file) => f(file),
+            None => Err(VfsError::new(SQLITE_IOERR, format!("{name} not found"))),
+        }
+    }
+}
+
+struct LocalStorageIoMethods;
+
+impl SQLiteIoMethods for LocalStorageIoMethods {
+    type File = LsFile;
+    type AppData = LocalStorage;
+    type Store = LocalStorageStore;
+
+    const VERSION: ::std::os::raw::c_int = 1;
+
+    unsafe extern "C" fn xFileControl(
+        pFile: *mut sqlite3_file,
+        op: ::std::os::raw::c_int,
+        pArg: *mut ::std::os::raw::c_void,
+    ) -> ::std::os::raw::c_int {
+        let vfs_file = SQLiteVfsFile::from_file(pFile);
+        let pool = Self::Store::app_data(vfs_file.vfs);
+        let name = vfs_file.name();
+
+        let mut name2file = pool.name2file.borrow_mut();
+        let file = check_option!(name2file.get_mut(name));
+
+        let LsFile::Main(file) = file else {
+            return SQLITE_NOTFOUND;
+        };
+
+        match op {
+            SQLITE_FCNTL_PRAGMA => {
+                let pArg = pArg as *mut *mut c_char;
+                let name = *pArg.add(1);
+                let value = *pArg.add(2);
+
+                bail!(name.is_null());
+                bail!(value.is_null(), SQLITE_NOTFOUND);
+
+                let key = check_result!(CStr::from_ptr(name).to_str());
+                let value = check_result!(CStr::from_ptr(value).to_str());
+
+                if key.eq_ignore_ascii_case("page_size") {
+                    let page_size = check_result!(value.parse::<usize>());
+                    if page_size == file.block_size {
+                        return SQLITE_OK;
+                    } else if file.block_size == 0 {
+                        file.block_size = page_size;
+                    } else {
+                        return pool.store_err(VfsError::new(
+                            SQLITE_ERROR,
+                            "page_size cannot be changed".into(),
+                        ));
+                    }
+                }
+            }
+            // `SQLITE_FCNTL_SYNC` is sent on every commit, also with `synchronous=off`,
+            // and `SQLITE_FCNTL_COMMIT_PHASETWO` after the file was truncated.
+            SQLITE_FCNTL_SYNC | SQLITE_FCNTL_COMMIT_PHASETWO => {
+                if let Err(err) = pool.sync_file(name, file) {
+                    return pool.store_err(err);
+                }
+            }
+            _ => (),
+        }
+
+        SQLITE_NOTFOUND
+    }
+}
+
+struct LocalStorageVfs<C>(PhantomData<C>);
+
+impl<C> SQLiteVfs<LocalStorageIoMethods> for LocalStorageVfs<C>
+where
+    C: OsCallback,
+{
+    const VERSION: ::std::os::raw::c_int = 1;
+
+    fn sleep(dur: Duration) {
+        C::sleep(dur);
+    }
+
+    fn random(buf: &mut [u8]) {
+        C::random(buf);
+    }
+
+    fn epoch_timestamp_in_ms() -> i64 {
+        C::epoch_timestamp_in_ms()
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum LocalStorageError {
+    #[error(transparent)]
+    Vfs(#[from] RegisterVfsError),
+    #[error("localStorage error: {0}")]
+    Js(String),
+    #[error("Generic error: {0}")]
+    Generic(String),
+}
+
+impl LocalStorageError {
+    fn from_js(err: JsValue) -> Self {
+        Self::Js(format!("{err:?}"))
+    }
+}
+
+/// Build `LocalStorageCfg`
+pub struct LocalStorageCfgBuilder(LocalStorageCfg);
+
+impl LocalStorageCfgBuilder {
+    pub fn new() -> Self {
+        Self(LocalStorageCfg::default())
+    }
+
+    /// The SQLite VFS name under which this VFS is registered, also used as
+    /// the prefix of its `localStorage` keys.
+    pub fn vfs_name(mut self, name: &str) -> Self {
+        self.0.vfs_name = name.into();
+        self
+    }
+
+    /// The number of bytes this VFS may use in `localStorage`.
+    pub fn quota(mut self, quota: usize) -> Self {
+        self.0.quota = quota;
+        self
+    }
+
+    /// Build `LocalStorageCfg`.
+    pub fn build(self) -> LocalStorageCfg {
+        self.0
+    }
+}
+
+impl Default for LocalStorageCfgBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `LocalStorage` options
+pub struct LocalStorageCfg {
+    /// The SQLite VFS name under which this VFS is registered, also used as
+    /// the prefix of its `localStorage` keys.
+    pub vfs_name: String,
+    /// The number of bytes this VFS may use in `localStorage`, counting keys and
+    /// values as UTF-16 like browsers do. Defaults to 5MB.
+    pub quota: usize,
+}
+
+impl Default for LocalStorageCfg {
+    fn default() -> Self {
+        Self {
+            vfs_name: "localstorage".into(),
+            quota: 5 * 1024 * 1024,
+        }
+    }
+}
+
+/// LocalStorageVfs management tool.
+pub struct LocalStorageUtil {
+    pool: &'static VfsAppData<LocalStorage>,
+}
+
+impl LocalStorageUtil {
+    /// Export the database.
+    pub fn export_db(&self, filename: &str) -> Result<Vec<u8>> {
+        self.pool.export_db(filename)
+    }
+
+    /// Delete the specified database, make sure that the database is closed.
+    pub fn delete_db(&self, filename: &str) {
+        self.pool.delete_db(filename)
+    }
+
+    /// Delete all database, make sure that all database is closed.
+    pub fn clear_all(&self) -> Result<()> {
+        self.pool.clear_all()
+    }
+
+    /// Does the database exists.
+    pub fn exists(&self, filename: &str) -> bool {
+        self.pool.name2file.borrow().contains_key(filename)
+    }
+
+    /// List all files.
+    pub fn list(&self) -> Vec<String> {
+        self.pool.name2file.borrow().keys().cloned().collect()
+    }
+
+    /// Number of files.
+    pub fn count(&self) -> usize {
+        self.pool.name2file.borrow().len()
+    }
+
+    /// Bytes currently used in `localStorage`, counted like `LocalStorageCfg::quota`.
+    pub fn usage(&self) -> usize {
+        self.pool.used.get()
+    }
+}
+
+/// Register `localstorage` vfs and return a management tool which can be used
+/// to perform basic administration of the stored databases.
+///
+/// All databases stored under `options.vfs_name` are loaded into memory.
+/// Fails if `localStorage` is not available, e.g. in a worker.
+///
+/// If the vfs corresponding to `options.vfs_name` has been registered,
+/// only return a management tool without register.
+pub fn install<C: OsCallback>(
+    options: &LocalStorageCfg,
+    default_vfs: bool,
+) -> Result<LocalStorageUtil> {
+    let pool = if let Some(vfs) = registered_vfs(&options.vfs_name)? {
+        unsafe { LocalStorageStore::app_data(vfs) }
+    } else {
+        let pool = LocalStorage::new(options)?;
+        let vfs = register_vfs::<LocalStorageIoMethods, LocalStorageVfs<C>>(
+            &options.vfs_name,
+            pool,
+            default_vfs,
+        )?;
+        unsafe { LocalStorageStore::app_data(vfs) }
+    };
+
+    Ok(LocalStorageUtil { pool })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LocalStorage, LocalStorageCfgBuilder, LocalStorageStore, LsFile, LsPageFile};
+    use rsqlite_vfs::{test_suite::test_vfs_store, VfsAppData, VfsFile};
+    use std::cell::{Cell, RefCell};
+    use std::collections::HashMap;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    /// Workers and node have no `localStorage`, install a `Map` backed one.
+    fn polyfill_local_storage() {
+        js_sys::Function::new_no_args(
+            "if (globalThis.localStorage === undefined) {
+                const map = new Map();
+                globalThis.localStorage = {
+                    getItem: (key) => (map.has(key) ? map.get(key) : null),
+                    setItem: (key, value) => void map.set(key, String(value)),
+                    removeItem: (key) => void map.delete(key),
+                    key: (idx) => [...map.keys()][idx] ?? null,
+                    get length() { return map.size; },
+                };
+            }",
+        )
+        .call0(&wasm_bindgen::JsValue::UNDEFINED)
+        .unwrap();
+    }
+
+    #[wasm_bindgen_test]
+    fn test_localstorage_vfs_store() {
+        polyfill_local_storage();
+        test_vfs_store::<LocalStorage, LsFile, LocalStorageStore>(VfsAppData::new(
+            LocalStorage::new(
+                &LocalStorageCfgBuilder::new()
+                    .vfs_name("test_localstorage_suite")
+                    .build(),
+            )
+            .unwrap(),
+        ))
+        .unwrap();
+    }
+
+    #[wasm_bindgen_test]
+    fn test_localstorage_sync_and_reload() {
+        polyfill_local_storage();
+        let cfg = LocalStorageCfgBuilder::new()
+            .vfs_name("test_localstorage_reload")
+            .quota(8 * 1024)
+            .build();
+        let pool = LocalStorage::new(&cfg).unwrap();
+        pool.clear_all().unwrap();
+
+        let mut file = LsPageFile::default();
+        file.write(&[1; 1024], 0).unwrap();
+        file.write(&[2; 1024], 1024).unwrap();
+        pool.sync_file("reload.db", &mut file).unwrap();
+        let usage = pool.used.get();
+        assert!(usage > 2 * 2 * 1024);
+
+        // Shrinking the file removes the truncated block from storage.
+        file.write(&[3; 1024], 0).unwrap();
+        file.truncate(1024).unwrap();
+        pool.sync_file("reload.db", &mut file).unwrap();
+        assert!(pool.used.get() < usage);
+
+        // A commit over the quota fails and leaves the stored blocks alone.
+        file.write(&[4; 1024], 0).unwrap();
+        file.write(&[4; 1024], 4096).unwrap();
+        assert!(pool.sync_file("reload.db", &mut file).is_err());
+
+        let reloaded = LocalStorage::new(&cfg).unwrap();
+        assert_eq!(reloaded.used.get(), pool.used.get());
+        assert_eq!(reloaded.export_db("reload.db").unwrap(), vec![3; 1024]);
+
+        pool.clear_all().unwrap();
+        assert!(LocalStorage::new(&cfg)
+            .unwrap()
+            .name2file
+            .borrow()
+            .is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_localstorage_partial_write_rollback() {
+        // A `localStorage` whose next `setItem` calls succeed `failIn` times, then throw once.
+        let storage = js_sys::Function::new_no_args(
+            "const map = new Map();
+            return {
+                failIn: -1,
+                getItem: (key) => (map.has(key) ? map.get(key) : null),
+                setItem(key, value) {
+                    if (this.failIn === 0) {
+                        this.failIn = -1;
+                        throw new Error('QuotaExceededError');
+                    }
+                    if (this.failIn > 0) this.failIn--;
+                    map.set(key, String(value));
+                },
+                removeItem: (key) => void map.delete(key),
+                key: (idx) => [...map.keys()][idx] ?? null,
+                get length() { return map.size; },
+            };",
+        )
+        .call0(&wasm_bindgen::JsValue::UNDEFINED)
+        .unwrap();
+        let pool = LocalStorage {
+            storage: storage.clone().unchecked_into(),
+            vfs_name: "test_localstorage_rollback".into(),
+            quota: usize::MAX,
+            used: Cell::new(0),
+            name2file: RefCell::new(HashMap::new()),
+        };
+
+        let mut file = LsPageFile::default();
+        file.write(&[1; 1024], 0).unwrap();
+        file.write(&[2; 1024], 1024).unwrap();
+        pool.sync_file("rollback.db", &mut file).unwrap();
+        let usage = pool.used.get();
+
+        // The first block is overwritten, then writing the second one fails.
+        file.write(&[3; 1024], 0).unwrap();
+        file.write(&[3; 1024], 1024).unwrap();
+        file.write(&[3; 1024], 2048).unwrap();
+        js_sys::Reflect::set(&storage, &"failIn".into(), &1.into()).unwrap();
+        assert!(pool.sync_file("rollback.db", &mut file).is_err());
+        assert_eq!(usage, pool.used.get());
+
+        pool.load().unwrap();
+        assert_eq!(usage, pool.used.get());
+        assert_eq!(
+            pool.export_db("rollback.db").unwrap(),
+            [[1; 1024], [2; 1024]].concat()
+        );
+    }
+}
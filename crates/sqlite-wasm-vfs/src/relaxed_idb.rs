@@ -35,6 +35,8 @@
 //! The difference from IDBMirrorVFS is that `RelaxedIdbVFS` does only support pragma `synchronous=off`.
 //!
 //! As for performance, since both reading and writing are done in memory, the performance is very good.
+//! `xRead` never issues an IndexedDB request: every block is already in memory after preload, so there is
+//! no separate read cache to size or invalidate, and IndexedDB is only accessed by preload and commits.
 //! However, we need to pay attention to the performance of preload the database, because the database is divided
 //! into multiple blocks and stored in the indexed db, and it takes some time to read all of them into memory.
 //! After my test, when page_size is 64k, the loading speed is the fastest.
@@ -55,12 +57,15 @@ use rsqlite_vfs::{
     SQLiteIoMethods, SQLiteVfs, SQLiteVfsFile, VfsAppData, VfsError, VfsFile, VfsResult, VfsStore,
 };
 use std::time::Duration;
-use std::{cell::RefCell, marker::PhantomData};
+use std::{
+    cell::{Cell, RefCell},
+    marker::PhantomData,
+};
 
 use indexed_db_futures::database::Database;
 use indexed_db_futures::prelude::*;
 use indexed_db_futures::transaction::TransactionMode;
-use js_sys::{Number, Object, Reflect, Uint8Array};
+use js_sys::{Function, Number, Object, Reflect, Uint8Array};
 use std::collections::{hash_map, HashSet};
 use std::future::Future;
 use std::pin::Pin;
@@ -70,11 +75,12 @@ use std::{
     ffi::{c_char, CStr},
 };
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
-use wasm_bindgen::JsValue;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web_sys::EventTarget;
 
 type Result<T> = std::result::Result<T, RelaxedIdbError>;
 
-fn page_read<T, G: Fn(usize) -> Option<T>, R: Fn(T, &mut [u8], (usize, usize))>(
+pub(crate) fn page_read<T, G: Fn(usize) -> Option<T>, R: Fn(T, &mut [u8], (usize, usize))>(
     buf: &mut [u8],
     page_size: usize,
     file_size: usize,
@@ -128,6 +134,8 @@ struct IdbCommit {
 
 enum IdbCommitOp {
     Sync(String),
+    /// Sync every main file that has pending writes.
+    Flush,
     Delete(String),
     Clear,
 }
@@ -153,6 +161,8 @@ struct IdbPageFile {
     block_size: usize,
     blocks: HashMap<usize, Uint8Array>,
     tx_blocks: HashSet<usize>,
+    /// Blocks past the end of the file still have to be deleted from the indexed db.
+    truncate_pending: bool,
     sync_notified: bool,
 }
 
@@ -292,6 +302,7 @@ async fn preload_db_impl(
                     block_size: data.length() as _,
                     blocks: HashMap::from([(offset, data)]),
                     tx_blocks: HashSet::new(),
+                    truncate_pending: false,
                     sync_notified: false,
                 }));
             }
@@ -325,6 +336,8 @@ struct RelaxedIdb {
     idb: Database,
     name2file: RefCell<HashMap<String, IdbFile>>,
     tx: UnboundedSender<IdbCommit>,
+    coalesce_commits: Option<Duration>,
+    flush_scheduled: Cell<bool>,
 }
 
 impl RelaxedIdb {
@@ -348,6 +361,8 @@ impl RelaxedIdb {
             idb: indexed_db,
             name2file: RefCell::new(name2file),
             tx,
+            coalesce_commits: options.coalesce_commits,
+            flush_scheduled: Cell::new(false),
         })
     }
 
@@ -374,6 +389,36 @@ impl RelaxedIdb {
         Ok(WaitCommit(rx))
     }
 
+    /// Queue a flush of all pending commits once `delay` has passed.
+    ///
+    /// Only one flush is scheduled at a time, commits made until it runs
+    /// are written with it. Delays too long for `setTimeout` never flush.
+    fn schedule_flush(&'static self, delay: Duration) -> Result<()> {
+        if self.flush_scheduled.get() {
+            return Ok(());
+        }
+        let Ok(ms) = i32::try_from(delay.as_millis()) else {
+            return Ok(());
+        };
+
+        let global = js_sys::global();
+        let set_timeout = Reflect::get(&global, &JsValue::from("setTimeout"))
+            .ok()
+            .and_then(|func| func.dyn_into::<Function>().ok())
+            .ok_or_else(|| RelaxedIdbError::Generic("setTimeout is not available".into()))?;
+        let callback = Closure::once_into_js(move || {
+            self.flush_scheduled.set(false);
+            // Nobody waits for the result. Writes that fail stay pending and clear
+            // `sync_notified`, so the next commit schedules another flush.
+            let _ = self.send_task(IdbCommitOp::Flush);
+        });
+        set_timeout
+            .call2(&global, &callback, &JsValue::from(ms))
+            .map_err(|err| RelaxedIdbError::Generic(format!("failed to set timeout: {err:?}")))?;
+        self.flush_scheduled.set(true);
+        Ok(())
+    }
+
     async fn preload_db(&self, files: Vec<String>) -> Result<()> {
         let preload = {
             let name2file = self.name2file.borrow();
@@ -428,6 +473,7 @@ impl RelaxedIdb {
                 block_size: page_size,
                 blocks,
                 tx_blocks,
+                truncate_pending: false,
                 sync_notified: false,
             }),
         );
@@ -473,6 +519,10 @@ impl RelaxedIdb {
         self.name2file.borrow().contains_key(file)
     }
 
+    fn flush(&self) -> Result<WaitCommit> {
+        self.send_task_with_notify(IdbCommitOp::Flush)
+    }
+
     async fn delete_db_impl(&self, file: &str) -> Result<()> {
         let transaction = self
             .idb
@@ -492,11 +542,7 @@ impl RelaxedIdb {
     #[allow(clippy::await_holding_refcell_ref)]
     async fn sync_db_impl(&self, file: &str) -> Result<()> {
         let mut name2file = self.name2file.borrow_mut();
-        let Some(idb_file) = name2file.get_mut(file) else {
-            return Ok(());
-        };
-
-        let IdbFile::Main(idb_blocks) = idb_file else {
+        let Some(IdbFile::Main(idb_blocks)) = name2file.get_mut(file) else {
             return Ok(());
         };
 
@@ -507,36 +553,76 @@ impl RelaxedIdb {
         while idb_blocks.blocks.remove(&truncated_offset).is_some() {
             truncated_offset += idb_blocks.block_size;
         }
+        let truncate =
+            std::mem::take(&mut idb_blocks.truncate_pending) || file_size != truncated_offset;
 
         let tx_blocks = std::mem::take(&mut idb_blocks.tx_blocks);
-        if tx_blocks.is_empty() && file_size == truncated_offset {
+        if tx_blocks.is_empty() && !truncate {
             // no need to put or delete
             return Ok(());
         }
 
-        let path = JsValue::from(file);
-
-        let transaction = self
-            .idb
-            .transaction("blocks")
-            .with_mode(TransactionMode::Readwrite)
-            .build()?;
-
-        let store = transaction.object_store("blocks")?;
-
-        for offset in tx_blocks {
-            if let Some(buffer) = idb_blocks.blocks.get(&offset) {
-                store.put(&set_block(&path, offset, buffer)).build()?;
+        let transaction = (|| -> Result<_> {
+            let path = JsValue::from(file);
+            let transaction = self
+                .idb
+                .transaction("blocks")
+                .with_mode(TransactionMode::Readwrite)
+                .build()?;
+            let store = transaction.object_store("blocks")?;
+            for offset in &tx_blocks {
+                if let Some(buffer) = idb_blocks.blocks.get(offset) {
+                    store.put(&set_block(&path, *offset, buffer)).build()?;
+                }
             }
-        }
-        store.delete(key_range(file, file_size)).build()?;
+            store.delete(key_range(file, file_size)).build()?;
+            Ok(transaction)
+        })();
 
         // The `RefMut` from `name2file` is explicitly dropped here to avoid holding the borrow across an `.await` point.
         drop(name2file);
 
-        transaction.commit().await?;
+        let ret = match transaction {
+            Ok(transaction) => transaction.commit().await.map_err(RelaxedIdbError::from),
+            Err(err) => Err(err),
+        };
+        if ret.is_err() {
+            // Keep the writes pending, so the next sync or flush of the file retries them.
+            if let Some(IdbFile::Main(idb_blocks)) = self.name2file.borrow_mut().get_mut(file) {
+                idb_blocks.tx_blocks.extend(tx_blocks);
+                idb_blocks.truncate_pending |= truncate;
+            }
+        }
+        ret
+    }
 
-        Ok(())
+    /// Sync every main file with pending writes, returning the first error.
+    ///
+    /// A file that fails does not stop the others from being written.
+    async fn flush_impl(&self) -> Result<()> {
+        let files = self
+            .name2file
+            .borrow()
+            .iter()
+            .filter_map(|(name, file)| match file {
+                IdbFile::Main(file)
+                    if !file.tx_blocks.is_empty()
+                        || file.truncate_pending
+                        || file.sync_notified =>
+                {
+                    Some(name.clone())
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let mut ret = Ok(());
+        for file in files {
+            let sync = self.sync_db_impl(&file).await;
+            if ret.is_ok() {
+                ret = sync;
+            }
+        }
+        ret
     }
 
     async fn commit_loop(&self, mut rx: UnboundedReceiver<IdbCommit>) {
@@ -544,6 +630,7 @@ impl RelaxedIdb {
             let IdbCommit { op, notify } = commit;
             let ret = match op {
                 IdbCommitOp::Sync(file) => self.sync_db_impl(&file).await,
+                IdbCommitOp::Flush => self.flush_impl().await,
                 IdbCommitOp::Delete(file) => self.delete_db_impl(&file).await,
                 IdbCommitOp::Clear => clear_impl(&self.idb).await,
             };
@@ -702,7 +789,11 @@ impl SQLiteIoMethods for RelaxedIdbIoMethods {
             }
             SQLITE_FCNTL_SYNC | SQLITE_FCNTL_COMMIT_PHASETWO => {
                 if !file.sync_notified {
-                    if pool.send_task(IdbCommitOp::Sync(name.into())).is_err() {
+                    let ret = match pool.coalesce_commits {
+                        Some(delay) => pool.schedule_flush(delay),
+                        None => pool.send_task(IdbCommitOp::Sync(name.into())),
+                    };
+                    if ret.is_err() {
                         return pool.store_err(VfsError::new(
                             SQLITE_ERROR,
                             format!("failed to send sync task, file: {name}"),
@@ -807,6 +898,20 @@ impl RelaxedIdbCfgBuilder {
         self
     }
 
+    /// Coalesce the commits made within `delay` into one indexed db write.
+    ///
+    /// Instead of a write per SQLite commit, the first commit schedules a flush
+    /// after `delay`, and every commit until then is written with it, one
+    /// indexed db transaction per database. `RelaxedIdbUtil::flush` writes them
+    /// right away. This trades durability for throughput: everything committed
+    /// since the last flush is lost if the page goes away, see
+    /// [`flush_on_pagehide`]. A delay longer than `i32::MAX` milliseconds only
+    /// flushes on request.
+    pub fn coalesce_commits(mut self, delay: Duration) -> Self {
+        self.0.coalesce_commits = Some(delay);
+        self
+    }
+
     /// Build `RelaxedIdbCfg`.
     pub fn build(self) -> RelaxedIdbCfg {
         self.0
@@ -827,6 +932,9 @@ pub struct RelaxedIdbCfg {
     pub clear_on_init: bool,
     /// Select which dbs to preload into memory.
     pub preload: Preload,
+    /// Coalesce the commits made within this delay into one indexed db write,
+    /// see `RelaxedIdbCfgBuilder::coalesce_commits`.
+    pub coalesce_commits: Option<Duration>,
 }
 
 impl Default for RelaxedIdbCfg {
@@ -835,6 +943,7 @@ impl Default for RelaxedIdbCfg {
             vfs_name: "relaxed-idb".into(),
             clear_on_init: false,
             preload: Preload::All,
+            coalesce_commits: None,
         }
     }
 }
@@ -904,6 +1013,92 @@ impl RelaxedIdbUtil {
     pub fn count(&self) -> usize {
         self.pool.name2file.borrow().len()
     }
+
+    /// Write all pending changes to the indexed db.
+    ///
+    /// The returned future resolves once every commit queued before it,
+    /// and the flush itself, has completed.
+    pub fn flush(&self) -> Result<WaitCommit> {
+        self.pool.flush()
+    }
+}
+
+/// Event listeners registered by [`flush_on_pagehide`], removed on drop.
+pub struct FlushOnPageHide {
+    global: EventTarget,
+    document: Option<EventTarget>,
+    pagehide: Closure<dyn FnMut()>,
+    visibilitychange: Closure<dyn FnMut()>,
+}
+
+impl Drop for FlushOnPageHide {
+    fn drop(&mut self) {
+        let _ = self.global.remove_event_listener_with_callback(
+            "pagehide",
+            self.pagehide.as_ref().unchecked_ref(),
+        );
+        if let Some(document) = &self.document {
+            let _ = document.remove_event_listener_with_callback(
+                "visibilitychange",
+                self.visibilitychange.as_ref().unchecked_ref(),
+            );
+        }
+    }
+}
+
+/// Flush pending writes when the page is hidden or about to be unloaded.
+///
+/// With `synchronous=off`, commits are written to the indexed db in the
+/// background, so closing or backgrounding a tab right after a commit may lose it.
+/// This registers a `pagehide` listener, and a `visibilitychange` listener on the
+/// document if there is one, that start a flush as soon as the page is hidden.
+///
+/// The listeners stay registered as long as the returned value is alive.
+pub fn flush_on_pagehide(util: &RelaxedIdbUtil) -> Result<FlushOnPageHide> {
+    let pool = util.pool;
+    let global = js_sys::global().unchecked_into::<EventTarget>();
+    let document = Reflect::get(&global, &JsValue::from("document"))
+        .ok()
+        .filter(|document| !document.is_undefined() && !document.is_null())
+        .map(JsCast::unchecked_into::<EventTarget>);
+
+    // Nobody waits for the result, there is nothing left to do if it fails.
+    let pagehide = Closure::<dyn FnMut()>::new(move || {
+        let _ = pool.send_task(IdbCommitOp::Flush);
+    });
+    let visibilitychange = {
+        let document = document.clone();
+        Closure::<dyn FnMut()>::new(move || {
+            let hidden = document
+                .as_ref()
+                .and_then(|document| Reflect::get(document, &JsValue::from("visibilityState")).ok())
+                .is_some_and(|state| state == "hidden");
+            if hidden {
+                let _ = pool.send_task(IdbCommitOp::Flush);
+            }
+        })
+    };
+
+    let map_err =
+        |err: JsValue| RelaxedIdbError::Generic(format!("failed to add event listener: {err:?}"));
+    global
+        .add_event_listener_with_callback("pagehide", pagehide.as_ref().unchecked_ref())
+        .map_err(map_err)?;
+    let guard = FlushOnPageHide {
+        global,
+        document,
+        pagehide,
+        visibilitychange,
+    };
+    if let Some(document) = &guard.document {
+        document
+            .add_event_listener_with_callback(
+                "visibilitychange",
+                guard.visibilitychange.as_ref().unchecked_ref(),
+            )
+            .map_err(map_err)?;
+    }
+    Ok(guard)
 }
 
 /// Register `relaxed-idb` vfs and return a management tool which can be used
@@ -939,8 +1134,12 @@ pub async fn install<C: OsCallback>(
 
 #[cfg(test)]
 mod tests {
-    use super::{IdbFile, RelaxedIdb, RelaxedIdbCfgBuilder, RelaxedIdbStore};
-    use rsqlite_vfs::{test_suite::test_vfs_store, VfsAppData};
+    use super::{
+        flush_on_pagehide, install, preload_db_impl, IdbCommitOp, IdbFile, Preload, RelaxedIdb,
+        RelaxedIdbCfgBuilder, RelaxedIdbStore,
+    };
+    use rsqlite_vfs::{ffi::SQLITE_OPEN_MAIN_DB, test_suite::test_vfs_store, VfsAppData, VfsFile};
+    use wasm_bindgen::JsCast;
     use wasm_bindgen_test::wasm_bindgen_test;
 
     #[wasm_bindgen_test]
@@ -960,4 +1159,161 @@ mod tests {
 
         wasm_bindgen_futures::spawn_local(async move { while let Some(_) = rx.recv().await {} });
     }
+
+    #[wasm_bindgen_test]
+    async fn test_relaxed_idb_flush_on_pagehide() {
+        let util = install::<sqlite_wasm_rs::WasmOsCallback>(
+            &RelaxedIdbCfgBuilder::new()
+                .vfs_name("test_relaxed_idb_pagehide")
+                .clear_on_init(true)
+                .build(),
+            false,
+        )
+        .await
+        .unwrap();
+        let _guard = flush_on_pagehide(&util).unwrap();
+
+        util.import_db_unchecked("pagehide.db", &[1; 8192], 4096)
+            .unwrap()
+            .await
+            .unwrap();
+
+        // A write that has not been synced yet, as with `synchronous=off`
+        // right before the page goes away.
+        if let Some(IdbFile::Main(file)) = util.pool.name2file.borrow_mut().get_mut("pagehide.db") {
+            file.write(&[2; 4096], 4096).unwrap();
+        }
+
+        let global = js_sys::global().unchecked_into::<web_sys::EventTarget>();
+        global
+            .dispatch_event(&web_sys::Event::new("pagehide").unwrap())
+            .unwrap();
+
+        // Commits run in order, so once this resolves the flush has completed.
+        util.pool
+            .send_task_with_notify(IdbCommitOp::Sync("pagehide-barrier.db".into()))
+            .unwrap()
+            .await
+            .unwrap();
+
+        let stored = preload_db_impl(&util.pool.idb, &Preload::Paths(vec!["pagehide.db".into()]))
+            .await
+            .unwrap();
+        let Some(IdbFile::Main(file)) = stored.get("pagehide.db") else {
+            panic!("pagehide.db is not stored");
+        };
+        let mut buf = vec![0; 8192];
+        file.read(&mut buf, 0).unwrap();
+        assert_eq!(&buf[..4096], &[1; 4096]);
+        assert_eq!(&buf[4096..], &[2; 4096]);
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_relaxed_idb_coalesce_commits() {
+        use sqlite_wasm_rs::*;
+
+        let util = install::<sqlite_wasm_rs::WasmOsCallback>(
+            &RelaxedIdbCfgBuilder::new()
+                .vfs_name("test_relaxed_idb_coalesce")
+                .clear_on_init(true)
+                .coalesce_commits(std::time::Duration::from_secs(3600))
+                .build(),
+            false,
+        )
+        .await
+        .unwrap();
+
+        let mut db = std::ptr::null_mut();
+        unsafe {
+            let ret = sqlite3_open_v2(
+                c"coalesce.db".as_ptr(),
+                &mut db as *mut _,
+                SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+                c"test_relaxed_idb_coalesce".as_ptr(),
+            );
+            assert_eq!(SQLITE_OK, ret);
+            for sql in [
+                c"CREATE TABLE t (v)",
+                c"INSERT INTO t VALUES (1)",
+                c"INSERT INTO t VALUES (2)",
+                c"INSERT INTO t VALUES (3)",
+            ] {
+                let ret = sqlite3_exec(
+                    db,
+                    sql.as_ptr(),
+                    None,
+                    std::ptr::null_mut(),
+                    std::ptr::null_mut(),
+                );
+                assert_eq!(SQLITE_OK, ret);
+            }
+        }
+
+        // Commits run in order, so once this resolves any per-commit sync would have completed.
+        util.pool
+            .send_task_with_notify(IdbCommitOp::Sync("coalesce-barrier.db".into()))
+            .unwrap()
+            .await
+            .unwrap();
+        let paths = Preload::Paths(vec!["coalesce.db".into()]);
+        let stored = preload_db_impl(&util.pool.idb, &paths).await.unwrap();
+        assert!(stored.is_empty());
+
+        util.flush().unwrap().await.unwrap();
+        let stored = preload_db_impl(&util.pool.idb, &paths).await.unwrap();
+        let Some(IdbFile::Main(file)) = stored.get("coalesce.db") else {
+            panic!("coalesce.db is not stored");
+        };
+        let expected = util.export_db("coalesce.db").unwrap();
+        let mut buf = vec![0; file.size().unwrap()];
+        file.read(&mut buf, 0).unwrap();
+        assert_eq!(expected, buf);
+
+        unsafe { sqlite3_close(db) };
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_relaxed_idb_flush_failure() {
+        let options = RelaxedIdbCfgBuilder::new()
+            .vfs_name("test_relaxed_idb_flush_failure")
+            .clear_on_init(true)
+            .build();
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let pool = RelaxedIdb::new(&options, tx).await.unwrap();
+        for (name, byte) in [("a.db", 1), ("b.db", 2)] {
+            let mut file = IdbFile::new(SQLITE_OPEN_MAIN_DB);
+            file.write(&[byte; 4096], 0).unwrap();
+            pool.name2file.borrow_mut().insert(name.into(), file);
+        }
+
+        // Every transaction on a closed database fails.
+        pool.idb.close();
+        assert!(pool.flush_impl().await.is_err());
+        for file in pool.name2file.borrow().values() {
+            let IdbFile::Main(file) = file else {
+                unreachable!();
+            };
+            assert!(file.tx_blocks.contains(&0));
+        }
+
+        // The pending writes of both files are retried by the next flush.
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let options = RelaxedIdbCfgBuilder::new()
+            .vfs_name("test_relaxed_idb_flush_failure")
+            .preload(Preload::None)
+            .build();
+        let retry = RelaxedIdb::new(&options, tx).await.unwrap();
+        retry.name2file.replace(pool.name2file.take());
+        retry.flush_impl().await.unwrap();
+
+        let stored = preload_db_impl(&retry.idb, &Preload::All).await.unwrap();
+        for (name, byte) in [("a.db", 1), ("b.db", 2)] {
+            let Some(IdbFile::Main(file)) = stored.get(name) else {
+                panic!("{name} is not stored");
+            };
+            let mut buf = vec![0; 4096];
+            file.read(&mut buf, 0).unwrap();
+            assert_eq!(buf, [byte; 4096]);
+        }
+    }
 }
@@ -50,8 +50,8 @@ use std::{
     marker::PhantomData,
 };
 
-use js_sys::{Array, DataView, IteratorNext, Reflect, Uint8Array};
-use wasm_bindgen::{JsCast, JsValue};
+use js_sys::{Array, DataView, Function, IteratorNext, Reflect, Uint8Array};
+use wasm_bindgen::{closure::Closure, prelude::wasm_bindgen, JsCast, JsValue};
 use wasm_bindgen_futures::JsFuture;
 use web_sys::{
     FileSystemDirectoryHandle, FileSystemFileHandle, FileSystemGetDirectoryOptions,
@@ -80,6 +80,10 @@ fn read_write_options(at: f64) -> FileSystemReadWriteOptions {
 struct SyncAccessFile {
     handle: FileSystemSyncAccessHandle,
     opaque: String,
+    /// Number of `xSync` calls since the last flush, see `OpfsSAHPoolCfg::flush_batch_window`.
+    pending_syncs: u32,
+    /// Epoch timestamp in ms of the last flush.
+    last_flush: i64,
 }
 
 struct OpfsSAHPool {
@@ -101,6 +105,15 @@ struct OpfsSAHPool {
     /// A tuple holding the raw pointer to the `sqlite3_vfs` struct and whether it was registered as the default.
     vfs: Cell<(*mut sqlite3_vfs, bool)>,
     random: fn(&mut [u8]),
+    epoch_timestamp_in_ms: fn() -> i64,
+    /// Length of the randomly-generated names of new files in `dh_opaque`.
+    opaque_name_length: usize,
+    /// Flushes within this window after the last one are deferred, zero flushes on every `xSync`.
+    flush_batch_window: Duration,
+    /// Flush anyway after this many deferred `xSync` calls, zero for no limit.
+    flush_batch_max_syncs: u32,
+    /// Whether a timer is set to flush the deferred `xSync` calls.
+    flush_scheduled: Cell<bool>,
 }
 
 impl OpfsSAHPool {
@@ -110,6 +123,11 @@ impl OpfsSAHPool {
         let vfs_dir = &options.directory;
         let capacity = options.initial_capacity;
         let clear_files = options.clear_on_init;
+        if options.opaque_name_length == 0 {
+            return Err(OpfsSAHError::Generic(
+                "opaque_name_length must be greater than 0".into(),
+            ));
+        }
 
         let create_option = FileSystemGetDirectoryOptions::new();
         create_option.set_create(true);
@@ -159,6 +177,11 @@ impl OpfsSAHPool {
             open_files: RefCell::new(HashSet::new()),
             vfs: Cell::new((std::ptr::null_mut(), false)),
             random: C::random,
+            epoch_timestamp_in_ms: C::epoch_timestamp_in_ms,
+            opaque_name_length: options.opaque_name_length,
+            flush_batch_window: options.flush_batch_window,
+            flush_batch_max_syncs: options.flush_batch_max_syncs,
+            flush_scheduled: Cell::new(false),
         };
 
         pool.acquire_access_handles(clear_files).await?;
@@ -167,9 +190,27 @@ impl OpfsSAHPool {
         Ok(pool)
     }
 
+    /// Generates a name for a new file in `dh_opaque`, retrying until it
+    /// does not collide with any existing file of the pool.
+    fn new_opaque_name(&self) -> String {
+        let in_use = |opaque: &str| {
+            self.available_files
+                .borrow()
+                .iter()
+                .chain(self.map_filename_to_file.borrow().values())
+                .any(|file| file.opaque == opaque)
+        };
+        loop {
+            let opaque = rsqlite_vfs::random_name_with_len(self.random, self.opaque_name_length);
+            if !in_use(&opaque) {
+                return opaque;
+            }
+        }
+    }
+
     async fn add_capacity(&self, n: u32) -> Result<u32> {
         for _ in 0..n {
-            let opaque = rsqlite_vfs::random_name(self.random);
+            let opaque = self.new_opaque_name();
             let handle: FileSystemFileHandle =
                 JsFuture::from(self.dh_opaque.get_file_handle_with_options(&opaque, &{
                     let options = FileSystemGetFileOptions::new();
@@ -187,6 +228,8 @@ impl OpfsSAHPool {
             let file = SyncAccessFile {
                 handle: sah,
                 opaque,
+                pending_syncs: 0,
+                last_flush: 0,
             };
             self.set_associated_filename(&file.handle, None, 0)?;
             self.available_files.borrow_mut().push(file);
@@ -253,10 +296,10 @@ impl OpfsSAHPool {
                 .map_err(OpfsSAHError::Truncate)?;
             return Ok(None);
         }
-        // set_associated_filename ensures that it is utf8
+        // set_associated_filename ensures that it is utf8, anything else is a corrupted header
         let filename =
-            String::from_utf8(self.header_buffer.subarray(0, name_length as u32).to_vec()).unwrap();
-        Ok(Some(filename))
+            String::from_utf8(self.header_buffer.subarray(0, name_length as u32).to_vec()).ok();
+        Ok(filename)
     }
 
     fn set_associated_filename(
@@ -323,6 +366,8 @@ impl OpfsSAHPool {
                 let file = SyncAccessFile {
                     handle: sah,
                     opaque,
+                    pending_syncs: 0,
+                    last_flush: 0,
                 };
                 let clear_file = |file: SyncAccessFile| -> Result<()> {
                     self.set_associated_filename(&file.handle, None, 0)?;
@@ -353,6 +398,48 @@ impl OpfsSAHPool {
         }
     }
 
+    /// Checks whether the header of `sah` still records `filename` as its associated name.
+    fn is_header_valid(&self, sah: &FileSystemSyncAccessHandle, filename: &str) -> Result<bool> {
+        sah.read_with_buffer_source_and_options(&self.header_buffer, &read_write_options(0.0))
+            .map_err(OpfsSAHError::Read)?;
+        let name = self
+            .header_buffer
+            .subarray(0, HEADER_MAX_FILENAME_SIZE as u32)
+            .to_vec();
+        let name_length = name.iter().position(|&x| x == 0);
+        Ok(name_length.is_some_and(|length| &name[..length] == filename.as_bytes()))
+    }
+
+    fn repair_check(&self) -> Result<Vec<String>> {
+        let mut corrupt = vec![];
+        for (filename, file) in self.map_filename_to_file.borrow().iter() {
+            if !self.is_header_valid(&file.handle, filename)? {
+                corrupt.push(filename.clone());
+            }
+        }
+        Ok(corrupt)
+    }
+
+    fn forget_corrupt(&self, filename: &str) -> Result<bool> {
+        let valid = match self.with_file(filename, |file| {
+            self.is_header_valid(&file.handle, filename)
+        }) {
+            Some(valid) => valid?,
+            None => return Ok(false),
+        };
+        if valid {
+            return Err(OpfsSAHError::Generic(format!(
+                "{filename} header is valid, use delete_db instead"
+            )));
+        }
+        if self.open_files.borrow().contains(filename) {
+            return Err(OpfsSAHError::Generic(format!(
+                "Cannot forget {filename}: file is in use"
+            )));
+        }
+        self.delete_file(filename)
+    }
+
     fn delete_file(&self, filename: &str) -> Result<bool> {
         let mut map_filename_to_file = self.map_filename_to_file.borrow_mut();
         let mut available_files = self.available_files.borrow_mut();
@@ -417,6 +504,78 @@ impl OpfsSAHPool {
         Ok(f(file))
     }
 
+    /// Handle `xSync` of `file`, flushing it unless the flush can be batched
+    /// with the following ones.
+    fn sync_file(&self, file: &mut SyncAccessFile) -> VfsResult<()> {
+        file.pending_syncs += 1;
+        let now = (self.epoch_timestamp_in_ms)();
+        if !self.flush_batch_window.is_zero() {
+            let within_window =
+                now.saturating_sub(file.last_flush) < self.flush_batch_window.as_millis() as i64;
+            let below_max =
+                self.flush_batch_max_syncs == 0 || file.pending_syncs < self.flush_batch_max_syncs;
+            if within_window && below_max {
+                return Ok(());
+            }
+        }
+        file.flush()?;
+        file.pending_syncs = 0;
+        file.last_flush = now;
+        Ok(())
+    }
+
+    /// Flush the files with deferred syncs once `flush_batch_window` has
+    /// passed since `last_flush`, so the last syncs of a burst don't wait
+    /// for the next `xSync` or `xClose`.
+    ///
+    /// Only one flush is scheduled at a time, syncs deferred until it runs
+    /// are flushed with it. Returns `false` if no timer could be set.
+    fn schedule_flush(&'static self, last_flush: i64) -> bool {
+        if self.flush_scheduled.get() {
+            return true;
+        }
+        let elapsed = (self.epoch_timestamp_in_ms)().saturating_sub(last_flush);
+        let delay = (self.flush_batch_window.as_millis() as i64)
+            .saturating_sub(elapsed)
+            .clamp(0, i32::MAX.into());
+
+        let global = js_sys::global();
+        let Some(set_timeout) = Reflect::get(&global, &JsValue::from("setTimeout"))
+            .ok()
+            .and_then(|func| func.dyn_into::<Function>().ok())
+        else {
+            return false;
+        };
+        let callback = Closure::once_into_js(move || {
+            self.flush_scheduled.set(false);
+            self.flush_pending();
+        });
+        if set_timeout
+            .call2(&global, &callback, &JsValue::from(delay as i32))
+            .is_err()
+        {
+            return false;
+        }
+        self.flush_scheduled.set(true);
+        true
+    }
+
+    /// Flush every file with deferred syncs.
+    fn flush_pending(&self) {
+        let now = (self.epoch_timestamp_in_ms)();
+        let Ok(mut files) = self.map_filename_to_file.try_borrow_mut() else {
+            return;
+        };
+        for file in files.values_mut().filter(|file| file.pending_syncs > 0) {
+            // Nobody waits for the result, a failed flush stays pending for
+            // the next `xSync` or `xClose`.
+            if file.flush().is_ok() {
+                file.pending_syncs = 0;
+                file.last_flush = now;
+            }
+        }
+    }
+
     fn pause_vfs(&self) -> Result<()> {
         if self.is_paused.get() {
             return Ok(());
@@ -661,11 +820,47 @@ impl SQLiteIoMethods for SyncAccessHandleIoMethods {
         SQLITE_IOCAP_UNDELETABLE_WHEN_OPEN
     }
 
+    unsafe extern "C" fn xSync(
+        pFile: *mut sqlite3_file,
+        _flags: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int {
+        let vfs_file = SQLiteVfsFile::from_file(pFile);
+        let app_data = SyncAccessHandleStore::app_data(vfs_file.vfs);
+        let pool: &'static OpfsSAHPool = app_data;
+
+        let f = |file: &mut SyncAccessFile| {
+            pool.sync_file(file)?;
+            // Without a timer, nothing would flush a deferred sync that ends a burst.
+            if file.pending_syncs > 0 && !pool.schedule_flush(file.last_flush) {
+                file.flush()?;
+                file.pending_syncs = 0;
+            }
+            Ok(SQLITE_OK)
+        };
+
+        match SyncAccessHandleStore::with_file_mut(vfs_file, f) {
+            Ok(code) => code,
+            Err(err) => app_data.store_err(err),
+        }
+    }
+
     unsafe extern "C" fn xClose(pFile: *mut sqlite3_file) -> ::std::os::raw::c_int {
         let vfs_file = SQLiteVfsFile::from_file(pFile);
         // The VFS file handle will be dropped, so we must clone the filename to use it after the drop.
         let file = vfs_file.name().to_string();
         let app_data = SyncAccessHandleStore::app_data(vfs_file.vfs);
+        // Flush the syncs deferred by batching before the file is closed.
+        let flushed = app_data.with_file_mut(&file, |file| -> VfsResult<()> {
+            if file.pending_syncs == 0 {
+                return Ok(());
+            }
+            file.flush()?;
+            file.pending_syncs = 0;
+            Ok(())
+        });
+        if let Some(Err(err)) = flushed {
+            return app_data.store_err(err);
+        }
         let ret = Self::xCloseImpl(pFile);
         if ret == SQLITE_OK {
             let exist = app_data.open_files.borrow_mut().remove(&file);
@@ -754,6 +949,33 @@ impl OpfsSAHPoolCfgBuilder {
         self
     }
 
+    /// Length of the random names given to new files in the `.opaque`
+    /// directory. Must be greater than 0.
+    pub fn opaque_name_length(mut self, len: usize) -> Self {
+        self.0.opaque_name_length = len;
+        self
+    }
+
+    /// Batch the flushes of `xSync`: after a flush, the flushes of further
+    /// syncs within `window` are deferred, until `max_syncs` syncs are pending
+    /// (zero for no limit). A zero `window` flushes on every sync.
+    ///
+    /// This speeds up many small transactions, but the last transactions of
+    /// a burst may be lost on a crash until they are flushed, by a timer once
+    /// `window` has passed, or earlier by a sync or close.
+    pub fn flush_batch(mut self, window: Duration, max_syncs: u32) -> Self {
+        self.0.flush_batch_window = window;
+        self.0.flush_batch_max_syncs = max_syncs;
+        self
+    }
+
+    /// Register the VFS as the default VFS, so databases can be opened
+    /// without naming it.
+    pub fn make_default(mut self, set: bool) -> Self {
+        self.0.make_default = set;
+        self
+    }
+
     /// Build `OpfsSAHPoolCfg`.
     pub fn build(self) -> OpfsSAHPoolCfg {
         self.0
@@ -780,6 +1002,20 @@ pub struct OpfsSAHPoolCfg {
     /// Specifies the default capacity of the VFS, i.e. the number of files
     /// it may contain.
     pub initial_capacity: u32,
+    /// Length of the random names given to new files in the `.opaque`
+    /// directory. Must be greater than 0.
+    pub opaque_name_length: usize,
+    /// After a flush, the flushes of `xSync` within this window are deferred,
+    /// trading durability of the last transactions for throughput. A timer
+    /// flushes the deferred syncs once the window has passed. Zero flushes on
+    /// every sync.
+    pub flush_batch_window: Duration,
+    /// Flush anyway once this many syncs are pending within
+    /// `flush_batch_window`, zero for no limit.
+    pub flush_batch_max_syncs: u32,
+    /// Register the VFS as the default VFS, so databases can be opened
+    /// without naming it, like passing `true` as `default_vfs` to [`install`].
+    pub make_default: bool,
 }
 
 impl Default for OpfsSAHPoolCfg {
@@ -789,6 +1025,10 @@ impl Default for OpfsSAHPoolCfg {
             directory: ".opfs-sahpool".into(),
             clear_on_init: false,
             initial_capacity: 6,
+            opaque_name_length: 32,
+            flush_batch_window: Duration::ZERO,
+            flush_batch_max_syncs: 0,
+            make_default: false,
         }
     }
 }
@@ -904,6 +1144,23 @@ impl OpfsSAHPoolUtil {
         Ok(self.pool.has_filename(filename))
     }
 
+    /// Re-reads the header of every associated file and returns the
+    /// filenames whose header no longer matches, i.e. is corrupted.
+    ///
+    /// Corrupted files would otherwise be silently disassociated the next
+    /// time the VFS acquires its access handles.
+    pub fn repair_check(&self) -> Result<Vec<String>> {
+        self.pool.repair_check()
+    }
+
+    /// Reclaims the slot of a file reported by `repair_check`, returning it
+    /// to the pool. Returns false if the file does not exist.
+    ///
+    /// Fails if the header of the file is valid or if the file is in use.
+    pub fn forget_corrupt(&self, filename: &str) -> Result<bool> {
+        self.pool.forget_corrupt(filename)
+    }
+
     /// List all files.
     pub fn list(&self) -> Vec<String> {
         self.pool.get_filenames()
@@ -949,11 +1206,44 @@ impl OpfsSAHPoolUtil {
     }
 }
 
+/// Whether this context can use the `opfs-sahpool` vfs, exported to JS as
+/// `opfsAvailable()`.
+///
+/// `FileSystemSyncAccessHandle` is only exposed in dedicated workers, so this
+/// checks for a worker global scope with `createSyncAccessHandle` on
+/// `FileSystemFileHandle`.
+#[wasm_bindgen(js_name = opfsAvailable)]
+pub fn opfs_available() -> bool {
+    let global = js_sys::global();
+    if !global.is_instance_of::<WorkerGlobalScope>() {
+        return false;
+    }
+    Reflect::get(&global, &JsValue::from("FileSystemFileHandle"))
+        .and_then(|handle| Reflect::get(&handle, &JsValue::from("prototype")))
+        .and_then(|proto| Reflect::has(&proto, &JsValue::from("createSyncAccessHandle")))
+        .unwrap_or(false)
+}
+
+/// Whether the `opfs-sahpool` vfs is installed under `vfs_name`, or the
+/// default name if `None`, exported to JS as `sahpoolInstalled(vfsName)`.
+///
+/// A paused vfs is unregistered from SQLite and reported as not installed.
+#[wasm_bindgen(js_name = sahpoolInstalled)]
+pub fn sahpool_installed(vfs_name: Option<String>) -> bool {
+    let vfs_name = vfs_name.unwrap_or_else(|| OpfsSAHPoolCfg::default().vfs_name);
+    matches!(registered_vfs(&vfs_name), Ok(Some(_)))
+}
+
 /// Register `opfs-sahpool` vfs and return a management tool which can be used
 /// to perform basic administration of the file pool.
 ///
+/// The vfs becomes the default vfs if either `default_vfs` or
+/// `options.make_default` is set.
+///
 /// If the vfs corresponding to `options.vfs_name` has been registered,
-/// only return a management tool without register.
+/// only return a management tool without register, after making it the
+/// default vfs if requested. An earlier request to make it the default is
+/// kept, so it stays the default when it is paused and unpaused.
 pub async fn install<C: OsCallback>(
     options: &OpfsSAHPoolCfg,
     default_vfs: bool,
@@ -961,8 +1251,23 @@ pub async fn install<C: OsCallback>(
     static REGISTER_GUARD: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
     let _guard = REGISTER_GUARD.lock().await;
 
+    let default_vfs = default_vfs || options.make_default;
+
     let vfs = match registered_vfs(&options.vfs_name)? {
-        Some(vfs) => vfs,
+        Some(vfs) => {
+            // Registering again only moves the vfs to the head of the list.
+            if default_vfs {
+                match unsafe { sqlite3_vfs_register(vfs, 1) } {
+                    SQLITE_OK => (),
+                    error_code => {
+                        return Err(OpfsSAHError::Generic(format!(
+                            "Failed to make VFS the default (SQLite error code: {error_code})"
+                        )))
+                    }
+                }
+            }
+            vfs
+        }
         None => register_vfs::<SyncAccessHandleIoMethods, SyncAccessHandleVfs<C>>(
             &options.vfs_name,
             OpfsSAHPool::new::<C>(options).await?,
@@ -971,7 +1276,8 @@ pub async fn install<C: OsCallback>(
     };
 
     let pool = unsafe { SyncAccessHandleStore::app_data(vfs) };
-    pool.vfs.set((vfs, default_vfs));
+    let (_, was_default) = pool.vfs.get();
+    pool.vfs.set((vfs, was_default || default_vfs));
 
     Ok(OpfsSAHPoolUtil { pool })
 }
@@ -979,10 +1285,16 @@ pub async fn install<C: OsCallback>(
 #[cfg(test)]
 mod tests {
     use super::{
-        OpfsSAHPool, OpfsSAHPoolCfgBuilder, SyncAccessFile, SyncAccessHandleAppData,
-        SyncAccessHandleStore,
+        read_write_options, OpfsSAHPool, OpfsSAHPoolCfgBuilder, SyncAccessFile,
+        SyncAccessHandleAppData, SyncAccessHandleStore,
     };
+    use js_sys::{Function, Promise, Reflect};
+    use rsqlite_vfs::ffi::SQLITE_OPEN_MAIN_DB;
     use rsqlite_vfs::{test_suite::test_vfs_store, VfsAppData};
+    use std::collections::HashSet;
+    use std::time::Duration;
+    use wasm_bindgen::{JsCast, JsValue};
+    use wasm_bindgen_futures::JsFuture;
     use wasm_bindgen_test::wasm_bindgen_test;
 
     #[wasm_bindgen_test]
@@ -1000,4 +1312,148 @@ mod tests {
         )
         .unwrap();
     }
+
+    #[wasm_bindgen_test]
+    async fn test_opfs_repair_check() {
+        let pool = OpfsSAHPool::new::<sqlite_wasm_rs::WasmOsCallback>(
+            &OpfsSAHPoolCfgBuilder::new()
+                .directory("test_opfs_repair")
+                .clear_on_init(true)
+                .build(),
+        )
+        .await
+        .unwrap();
+
+        pool.with_new_file("healthy.db", SQLITE_OPEN_MAIN_DB, |_| Ok::<_, ()>(()))
+            .unwrap()
+            .unwrap();
+        pool.with_new_file("corrupt.db", SQLITE_OPEN_MAIN_DB, |file| {
+            file.handle
+                .write_with_u8_array_and_options(&[0xff; 16], &read_write_options(0.0))
+                .map(|_| ())
+        })
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(pool.repair_check().unwrap(), vec!["corrupt.db".to_string()]);
+
+        let available = pool.available_files.borrow().len();
+        pool.forget_corrupt("healthy.db").unwrap_err();
+        assert!(pool.forget_corrupt("corrupt.db").unwrap());
+        assert!(!pool.has_filename("corrupt.db"));
+        assert_eq!(available + 1, pool.available_files.borrow().len());
+        assert!(pool.repair_check().unwrap().is_empty());
+
+        pool.release_access_handles();
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_opfs_flush_batch() {
+        let pool = OpfsSAHPool::new::<sqlite_wasm_rs::WasmOsCallback>(
+            &OpfsSAHPoolCfgBuilder::new()
+                .directory("test_opfs_flush_batch")
+                .clear_on_init(true)
+                .flush_batch(Duration::from_secs(3600), 4)
+                .build(),
+        )
+        .await
+        .unwrap();
+
+        pool.with_new_file("batch.db", SQLITE_OPEN_MAIN_DB, |_| Ok::<_, ()>(()))
+            .unwrap()
+            .unwrap();
+        let pending = (0..10)
+            .map(|_| {
+                pool.with_file_mut("batch.db", |file| {
+                    pool.sync_file(file).map(|_| file.pending_syncs)
+                })
+                .unwrap()
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        // The first sync flushes, then every fourth one.
+        assert_eq!(pending, [0, 1, 2, 3, 0, 1, 2, 3, 0, 1]);
+
+        pool.release_access_handles();
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_opfs_flush_batch_timer() {
+        let pool: &'static OpfsSAHPool = Box::leak(Box::new(
+            OpfsSAHPool::new::<sqlite_wasm_rs::WasmOsCallback>(
+                &OpfsSAHPoolCfgBuilder::new()
+                    .directory("test_opfs_flush_batch_timer")
+                    .clear_on_init(true)
+                    .flush_batch(Duration::from_millis(50), 0)
+                    .build(),
+            )
+            .await
+            .unwrap(),
+        ));
+
+        pool.with_new_file("timer.db", SQLITE_OPEN_MAIN_DB, |_| Ok::<_, ()>(()))
+            .unwrap()
+            .unwrap();
+        let sync = || {
+            pool.with_file_mut("timer.db", |file| {
+                pool.sync_file(file)?;
+                assert!(pool.schedule_flush(file.last_flush));
+                Ok::<_, rsqlite_vfs::VfsError>(file.pending_syncs)
+            })
+            .unwrap()
+            .unwrap()
+        };
+        // The first sync flushes, the second one is deferred.
+        assert_eq!(0, sync());
+        assert_eq!(1, sync());
+
+        let timeout = Promise::new(&mut |resolve, _| {
+            let global = js_sys::global();
+            let set_timeout = Reflect::get(&global, &JsValue::from("setTimeout"))
+                .unwrap()
+                .dyn_into::<Function>()
+                .unwrap();
+            set_timeout
+                .call2(&global, &resolve, &JsValue::from(200))
+                .unwrap();
+        });
+        JsFuture::from(timeout).await.unwrap();
+
+        // Flushed by the timer without another sync.
+        let pending = pool
+            .with_file("timer.db", |file| Ok::<_, ()>(file.pending_syncs))
+            .unwrap()
+            .unwrap();
+        assert_eq!(0, pending);
+
+        pool.release_access_handles();
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_opfs_opaque_name_length() {
+        let pool = OpfsSAHPool::new::<sqlite_wasm_rs::WasmOsCallback>(
+            &OpfsSAHPoolCfgBuilder::new()
+                .directory("test_opfs_opaque_name")
+                .clear_on_init(true)
+                .initial_capacity(0)
+                .opaque_name_length(12)
+                .build(),
+        )
+        .await
+        .unwrap();
+        pool.reduce_capacity(pool.get_capacity()).await.unwrap();
+
+        assert_eq!(512, pool.add_capacity(512).await.unwrap());
+        let names = pool
+            .available_files
+            .borrow()
+            .iter()
+            .map(|file| file.opaque.clone())
+            .collect::<Vec<_>>();
+        assert!(names.iter().all(|name| name.len() == 12));
+        assert_eq!(names.len(), names.iter().collect::<HashSet<_>>().len());
+
+        pool.reduce_capacity(512).await.unwrap();
+        pool.release_access_handles();
+    }
 }
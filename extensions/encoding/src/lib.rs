@@ -0,0 +1,393 @@
+#![doc = include_str!("../README.md")]
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ffi::{c_char, c_int, CStr};
+
+use sqlite_wasm_rs::helpers::value_bytes;
+use sqlite_wasm_rs::{
+    sqlite3, sqlite3_api_routines, sqlite3_context, sqlite3_create_function_v2,
+    sqlite3_result_blob, sqlite3_result_error, sqlite3_result_null, sqlite3_result_text,
+    sqlite3_value, sqlite3_value_type, try_sqlite, SQLITE_DETERMINISTIC, SQLITE_INNOCUOUS,
+    SQLITE_NULL, SQLITE_OK, SQLITE_TRANSIENT, SQLITE_UTF8,
+};
+
+type XFunc = unsafe extern "C" fn(*mut sqlite3_context, c_int, *mut *mut sqlite3_value);
+
+const FUNCTIONS: [(&CStr, XFunc); 5] = [
+    (c"base64", x_base64),
+    (c"base64_decode", x_base64_decode),
+    (c"base32", x_base32),
+    (c"base32_decode", x_base32_decode),
+    (c"hex_decode", x_hex_decode),
+];
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Encode `bytes` with a power of two alphabet, padding the output with `=`
+/// to a multiple of `group` characters as described in RFC 4648.
+fn encode(bytes: &[u8], alphabet: &[u8], group: usize) -> String {
+    let bits = alphabet.len().trailing_zeros();
+    let mask = (1u32 << bits) - 1;
+
+    let mut out = String::with_capacity((bytes.len() * 8).div_ceil(bits as usize) + group);
+    let mut acc = 0u32;
+    let mut acc_bits = 0;
+    for &b in bytes {
+        acc = (acc << 8) | u32::from(b);
+        acc_bits += 8;
+        while acc_bits >= bits {
+            acc_bits -= bits;
+            out.push(alphabet[((acc >> acc_bits) & mask) as usize] as char);
+        }
+    }
+    if acc_bits > 0 {
+        out.push(alphabet[((acc << (bits - acc_bits)) & mask) as usize] as char);
+    }
+    let padded = out.len().div_ceil(group) * group;
+    while out.len() < padded {
+        out.push('=');
+    }
+    out
+}
+
+/// Inverse of [`encode`]. Trailing `=` padding is optional, any other
+/// character outside of the alphabet is rejected.
+fn decode(text: &[u8], alphabet: &[u8]) -> Option<Vec<u8>> {
+    let bits = alphabet.len().trailing_zeros();
+    let text = match text.iter().position(|&c| c == b'=') {
+        Some(pos) if text[pos..].iter().all(|&c| c == b'=') => &text[..pos],
+        Some(_) => return None,
+        None => text,
+    };
+
+    let mut out = Vec::with_capacity(text.len() * bits as usize / 8);
+    let mut acc = 0u32;
+    let mut acc_bits = 0;
+    for &c in text {
+        let idx = alphabet.iter().position(|&a| a == c)? as u32;
+        acc = (acc << bits) | idx;
+        acc_bits += bits;
+        if acc_bits >= 8 {
+            acc_bits -= 8;
+            out.push((acc >> acc_bits) as u8);
+        }
+    }
+    // The leftover bits only complete the last character and must be zero.
+    if acc_bits >= bits || acc & ((1 << acc_bits) - 1) != 0 {
+        return None;
+    }
+    Some(out)
+}
+
+fn hex_decode(text: &[u8]) -> Option<Vec<u8>> {
+    fn nibble(c: u8) -> Option<u8> {
+        match c {
+            b'0'..=b'9' => Some(c - b'0'),
+            b'a'..=b'f' => Some(c - b'a' + 10),
+            b'A'..=b'F' => Some(c - b'A' + 10),
+            _ => None,
+        }
+    }
+
+    let pairs = text.chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return None;
+    }
+    pairs
+        .map(|pair| Some((nibble(pair[0])? << 4) | nibble(pair[1])?))
+        .collect()
+}
+
+unsafe fn encode_func(
+    ctx: *mut sqlite3_context,
+    argv: *mut *mut sqlite3_value,
+    alphabet: &[u8],
+    group: usize,
+) {
+    let value = *argv;
+    if sqlite3_value_type(value) == SQLITE_NULL {
+        sqlite3_result_null(ctx);
+        return;
+    }
+    let text = encode(value_bytes(value), alphabet, group);
+    sqlite3_result_text(
+        ctx,
+        text.as_ptr().cast(),
+        text.len() as c_int,
+        SQLITE_TRANSIENT(),
+    );
+}
+
+unsafe fn decode_func(
+    ctx: *mut sqlite3_context,
+    argv: *mut *mut sqlite3_value,
+    decode: impl FnOnce(&[u8]) -> Option<Vec<u8>>,
+    err: &CStr,
+) {
+    let value = *argv;
+    if sqlite3_value_type(value) == SQLITE_NULL {
+        sqlite3_result_null(ctx);
+        return;
+    }
+    match decode(value_bytes(value)) {
+        Some(blob) => sqlite3_result_blob(
+            ctx,
+            blob.as_ptr().cast(),
+            blob.len() as c_int,
+            SQLITE_TRANSIENT(),
+        ),
+        None => sqlite3_result_error(ctx, err.as_ptr(), -1),
+    }
+}
+
+unsafe extern "C" fn x_base64(
+    ctx: *mut sqlite3_context,
+    _argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    encode_func(ctx, argv, BASE64_ALPHABET, 4);
+}
+
+unsafe extern "C" fn x_base64_decode(
+    ctx: *mut sqlite3_context,
+    _argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    decode_func(
+        ctx,
+        argv,
+        |text| decode(text, BASE64_ALPHABET),
+        c"base64_decode: invalid base64 input",
+    );
+}
+
+unsafe extern "C" fn x_base32(
+    ctx: *mut sqlite3_context,
+    _argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    encode_func(ctx, argv, BASE32_ALPHABET, 8);
+}
+
+unsafe extern "C" fn x_base32_decode(
+    ctx: *mut sqlite3_context,
+    _argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    decode_func(
+        ctx,
+        argv,
+        |text| decode(text, BASE32_ALPHABET),
+        c"base32_decode: invalid base32 input",
+    );
+}
+
+unsafe extern "C" fn x_hex_decode(
+    ctx: *mut sqlite3_context,
+    _argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    decode_func(ctx, argv, hex_decode, c"hex_decode: invalid hex input");
+}
+
+/// Register `base64(X)`, `base64_decode(X)`, `base32(X)`, `base32_decode(X)`
+/// and `hex_decode(X)`.
+///
+/// The encoders return TEXT, the decoders return a BLOB and fail with an
+/// error on malformed input. `NULL` is passed through unchanged.
+///
+/// The signature matches `sqlite3_auto_extension`, so it can be registered
+/// for every new connection or called directly on an open one.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe extern "C" fn sqlite3_encoding_init(
+    db: *mut sqlite3,
+    _pz_err_msg: *mut *mut c_char,
+    _p_api: *const sqlite3_api_routines,
+) -> c_int {
+    for (name, func) in FUNCTIONS {
+        try_sqlite!(sqlite3_create_function_v2(
+            db,
+            name.as_ptr(),
+            1,
+            SQLITE_UTF8 | SQLITE_DETERMINISTIC | SQLITE_INNOCUOUS,
+            core::ptr::null_mut(),
+            Some(func),
+            None,
+            None,
+            None,
+        ));
+    }
+    SQLITE_OK
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlite_wasm_rs::*;
+    use std::ffi::CStr;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn test_rfc4648_vectors() {
+        let cases = [
+            ("", "", ""),
+            ("f", "Zg==", "MY======"),
+            ("fo", "Zm8=", "MZXQ===="),
+            ("foo", "Zm9v", "MZXW6==="),
+            ("foob", "Zm9vYg==", "MZXW6YQ="),
+            ("fooba", "Zm9vYmE=", "MZXW6YTB"),
+            ("foobar", "Zm9vYmFy", "MZXW6YTBOI======"),
+        ];
+        for (plain, b64, b32) in cases {
+            assert_eq!(encode(plain.as_bytes(), BASE64_ALPHABET, 4), b64);
+            assert_eq!(encode(plain.as_bytes(), BASE32_ALPHABET, 8), b32);
+            assert_eq!(
+                decode(b64.as_bytes(), BASE64_ALPHABET).unwrap(),
+                plain.as_bytes()
+            );
+            assert_eq!(
+                decode(b32.as_bytes(), BASE32_ALPHABET).unwrap(),
+                plain.as_bytes()
+            );
+        }
+
+        assert!(decode(b"Zm9v!", BASE64_ALPHABET).is_none());
+        assert!(decode(b"Zg=a", BASE64_ALPHABET).is_none());
+        assert!(decode(b"Zh==", BASE64_ALPHABET).is_none());
+        assert_eq!(hex_decode(b"00fFa0").unwrap(), [0x00, 0xff, 0xa0]);
+        assert!(hex_decode(b"abc").is_none());
+        assert!(hex_decode(b"zz").is_none());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_encoding_functions() {
+        unsafe {
+            let mut db = std::ptr::null_mut();
+            let ret = sqlite3_open_v2(
+                c":memory:".as_ptr().cast(),
+                &mut db as *mut _,
+                SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+                std::ptr::null(),
+            );
+            assert_eq!(SQLITE_OK, ret);
+            assert_eq!(
+                SQLITE_OK,
+                sqlite3_encoding_init(db, std::ptr::null_mut(), std::ptr::null())
+            );
+
+            let sql: &CStr = c"SELECT base64_decode(base64(x)) = x, base32_decode(base32(x)) = x, \
+                hex_decode(hex(x)) = x, base64(x), base64(NULL) IS NULL \
+                FROM (SELECT x'00ff10203040fe' AS x)";
+            let mut stmt = std::ptr::null_mut();
+            let ret = sqlite3_prepare_v3(
+                db,
+                sql.as_ptr(),
+                -1,
+                0,
+                &mut stmt as *mut _,
+                std::ptr::null_mut(),
+            );
+            assert_eq!(SQLITE_OK, ret);
+            assert_eq!(SQLITE_ROW, sqlite3_step(stmt));
+            assert_eq!(1, sqlite3_column_int(stmt, 0));
+            assert_eq!(1, sqlite3_column_int(stmt, 1));
+            assert_eq!(1, sqlite3_column_int(stmt, 2));
+            assert_eq!(
+                "AP8QIDBA/g==",
+                CStr::from_ptr(sqlite3_column_text(stmt, 3).cast())
+                    .to_str()
+                    .unwrap()
+            );
+            assert_eq!(1, sqlite3_column_int(stmt, 4));
+            sqlite3_finalize(stmt);
+
+            let ret = sqlite3_exec(
+                db,
+                c"SELECT base64_decode('not base64!')".as_ptr(),
+                None,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            );
+            assert_eq!(SQLITE_ERROR, ret);
+            assert_eq!(
+                "base64_decode: invalid base64 input",
+                CStr::from_ptr(sqlite3_errmsg(db)).to_str().unwrap()
+            );
+
+            sqlite3_close(db);
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn test_init_stops_at_first_error() {
+        unsafe {
+            let mut db = std::ptr::null_mut();
+            let ret = sqlite3_open_v2(
+                c":memory:".as_ptr().cast(),
+                &mut db as *mut _,
+                SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+                std::ptr::null(),
+            );
+            assert_eq!(SQLITE_OK, ret);
+
+            // Redefining a function fails while a statement is running.
+            let ret = sqlite3_create_function_v2(
+                db,
+                c"base32".as_ptr(),
+                1,
+                SQLITE_UTF8,
+                std::ptr::null_mut(),
+                Some(x_base32),
+                None,
+                None,
+                None,
+            );
+            assert_eq!(SQLITE_OK, ret);
+            let mut stmt = std::ptr::null_mut();
+            let ret = sqlite3_prepare_v3(
+                db,
+                c"SELECT 1 UNION ALL SELECT 2".as_ptr(),
+                -1,
+                0,
+                &mut stmt as *mut _,
+                std::ptr::null_mut(),
+            );
+            assert_eq!(SQLITE_OK, ret);
+            assert_eq!(SQLITE_ROW, sqlite3_step(stmt));
+
+            assert_eq!(
+                SQLITE_BUSY,
+                sqlite3_encoding_init(db, std::ptr::null_mut(), std::ptr::null())
+            );
+            sqlite3_finalize(stmt);
+
+            // The functions before `base32` were registered, the ones after were not.
+            let exec = |sql: &CStr| {
+                sqlite3_exec(
+                    db,
+                    sql.as_ptr(),
+                    None,
+                    std::ptr::null_mut(),
+                    std::ptr::null_mut(),
+                )
+            };
+            assert_eq!(SQLITE_OK, exec(c"SELECT base64_decode('AA==')"));
+            assert_eq!(SQLITE_ERROR, exec(c"SELECT hex_decode('00')"));
+            assert_eq!(
+                "no such function: hex_decode",
+                CStr::from_ptr(sqlite3_errmsg(db)).to_str().unwrap()
+            );
+
+            sqlite3_close(db);
+        }
+    }
+}
@@ -0,0 +1,256 @@
+#![doc = include_str!("../README.md")]
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ffi::{c_char, c_int, CStr};
+
+use sqlite_wasm_rs::{
+    sqlite3, sqlite3_api_routines, sqlite3_context, sqlite3_create_function_v2,
+    sqlite3_result_int64, sqlite3_result_null, sqlite3_result_text, sqlite3_value,
+    sqlite3_value_bytes, sqlite3_value_text, sqlite3_value_type, try_sqlite, SQLITE_DETERMINISTIC,
+    SQLITE_INNOCUOUS, SQLITE_NULL, SQLITE_OK, SQLITE_TRANSIENT, SQLITE_UTF8,
+};
+
+type XFunc = unsafe extern "C" fn(*mut sqlite3_context, c_int, *mut *mut sqlite3_value);
+
+const FUNCTIONS: [(&CStr, c_int, XFunc); 2] = [
+    (c"edit_distance", 2, x_edit_distance),
+    (c"soundex", 1, x_soundex),
+];
+
+/// Returns the text representation of an argument, invalid UTF-8 is
+/// replaced with `U+FFFD`.
+unsafe fn value_text(value: *mut sqlite3_value) -> String {
+    // `sqlite3_value_text` must be called before `sqlite3_value_bytes`,
+    // so that the length matches the representation that was returned.
+    let ptr = sqlite3_value_text(value);
+    let len = sqlite3_value_bytes(value);
+    if ptr.is_null() || len <= 0 {
+        String::new()
+    } else {
+        String::from_utf8_lossy(core::slice::from_raw_parts(ptr, len as usize)).into_owned()
+    }
+}
+
+/// The number of single character insertions, deletions and substitutions
+/// needed to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    // `row[j]` is the distance between the prefix of `a` seen so far and
+    // the first `j` characters of `b`.
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// The American Soundex code of `text`, following the built-in `soundex`
+/// of SQLite: non-letters before the first letter are skipped, and `?000`
+/// is returned if there is no letter at all.
+fn soundex(text: &str) -> [u8; 4] {
+    fn code(c: u8) -> u8 {
+        match c.to_ascii_lowercase() {
+            b'b' | b'f' | b'p' | b'v' => b'1',
+            b'c' | b'g' | b'j' | b'k' | b'q' | b's' | b'x' | b'z' => b'2',
+            b'd' | b't' => b'3',
+            b'l' => b'4',
+            b'm' | b'n' => b'5',
+            b'r' => b'6',
+            _ => 0,
+        }
+    }
+
+    let mut bytes = text.bytes().skip_while(|c| !c.is_ascii_alphabetic());
+    let Some(first) = bytes.next() else {
+        return *b"?000";
+    };
+
+    let mut result = *b"0000";
+    result[0] = first.to_ascii_uppercase();
+    let mut len = 1;
+    let mut prev = code(first);
+    for c in bytes {
+        if len == result.len() {
+            break;
+        }
+        let code = code(c);
+        // Letters without a code separate repeated codes, e.g. both `c`s in
+        // "Tacoc" are kept.
+        if code != 0 && code != prev {
+            result[len] = code;
+            len += 1;
+        }
+        prev = code;
+    }
+    result
+}
+
+unsafe extern "C" fn x_edit_distance(
+    ctx: *mut sqlite3_context,
+    _argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    let (a, b) = (*argv, *argv.add(1));
+    if sqlite3_value_type(a) == SQLITE_NULL || sqlite3_value_type(b) == SQLITE_NULL {
+        sqlite3_result_null(ctx);
+        return;
+    }
+    let distance = edit_distance(&value_text(a), &value_text(b));
+    sqlite3_result_int64(ctx, distance as i64);
+}
+
+unsafe extern "C" fn x_soundex(
+    ctx: *mut sqlite3_context,
+    _argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    let value = *argv;
+    if sqlite3_value_type(value) == SQLITE_NULL {
+        sqlite3_result_null(ctx);
+        return;
+    }
+    let code = soundex(&value_text(value));
+    sqlite3_result_text(
+        ctx,
+        code.as_ptr().cast(),
+        code.len() as c_int,
+        SQLITE_TRANSIENT(),
+    );
+}
+
+/// Register `edit_distance(A, B)` and `soundex(X)`.
+///
+/// `edit_distance` returns the Levenshtein distance in characters,
+/// `soundex` returns a four character code. `NULL` arguments return `NULL`.
+///
+/// The signature matches `sqlite3_auto_extension`, so it can be registered
+/// for every new connection or called directly on an open one.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe extern "C" fn sqlite3_fuzzy_init(
+    db: *mut sqlite3,
+    _pz_err_msg: *mut *mut c_char,
+    _p_api: *const sqlite3_api_routines,
+) -> c_int {
+    for (name, n_arg, func) in FUNCTIONS {
+        try_sqlite!(sqlite3_create_function_v2(
+            db,
+            name.as_ptr(),
+            n_arg,
+            SQLITE_UTF8 | SQLITE_DETERMINISTIC | SQLITE_INNOCUOUS,
+            core::ptr::null_mut(),
+            Some(func),
+            None,
+            None,
+            None,
+        ));
+    }
+    SQLITE_OK
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{edit_distance, soundex, sqlite3_fuzzy_init};
+    use sqlite_wasm_rs::*;
+    use std::ffi::CStr;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    unsafe fn query_text(db: *mut sqlite3, sql: &CStr) -> Option<String> {
+        let mut stmt = std::ptr::null_mut();
+        let ret = sqlite3_prepare_v3(
+            db,
+            sql.as_ptr(),
+            -1,
+            0,
+            &mut stmt as *mut _,
+            std::ptr::null_mut(),
+        );
+        assert_eq!(SQLITE_OK, ret);
+        assert_eq!(SQLITE_ROW, sqlite3_step(stmt));
+        let text = sqlite3_column_text(stmt, 0);
+        let ret =
+            (!text.is_null()).then(|| CStr::from_ptr(text.cast()).to_str().unwrap().to_string());
+        sqlite3_finalize(stmt);
+        ret
+    }
+
+    #[wasm_bindgen_test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("abc", ""), 3);
+        assert_eq!(edit_distance("flaw", "lawn"), 2);
+        assert_eq!(edit_distance("same", "same"), 0);
+        // Distances are counted in characters, not bytes.
+        assert_eq!(edit_distance("café", "cafe"), 1);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_soundex() {
+        for (text, code) in [
+            ("Robert", b"R163"),
+            ("Rupert", b"R163"),
+            ("Rubin", b"R150"),
+            ("Ashcraft", b"A226"),
+            ("Tymczak", b"T522"),
+            ("Pfister", b"P236"),
+            ("Lee", b"L000"),
+            ("  o'Hara", b"O600"),
+            ("1234", b"?000"),
+            ("", b"?000"),
+        ] {
+            assert_eq!(&soundex(text), code, "{text}");
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn test_fuzzy_functions() {
+        unsafe {
+            let mut db = std::ptr::null_mut();
+            let ret = sqlite3_open_v2(
+                c":memory:".as_ptr().cast(),
+                &mut db as *mut _,
+                SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+                std::ptr::null(),
+            );
+            assert_eq!(SQLITE_OK, ret);
+            assert_eq!(
+                SQLITE_OK,
+                sqlite3_fuzzy_init(db, std::ptr::null_mut(), std::ptr::null())
+            );
+
+            assert_eq!(
+                query_text(db, c"SELECT edit_distance('kitten', 'sitting')").as_deref(),
+                Some("3")
+            );
+            assert_eq!(
+                query_text(db, c"SELECT soundex('Robert') = soundex('Rupert')").as_deref(),
+                Some("1")
+            );
+            assert_eq!(
+                query_text(db, c"SELECT soundex('Robert')").as_deref(),
+                Some("R163")
+            );
+            assert_eq!(
+                query_text(db, c"SELECT edit_distance(12345, 12445)").as_deref(),
+                Some("1")
+            );
+            assert_eq!(query_text(db, c"SELECT edit_distance('a', NULL)"), None);
+            assert_eq!(query_text(db, c"SELECT soundex(NULL)"), None);
+
+            sqlite3_close(db);
+        }
+    }
+}
@@ -0,0 +1,170 @@
+#![doc = include_str!("../README.md")]
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+use alloc::string::String;
+use core::ffi::{c_char, c_int, CStr};
+use core::fmt::Write;
+
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use sqlite_wasm_rs::helpers::value_bytes;
+use sqlite_wasm_rs::{
+    sqlite3, sqlite3_api_routines, sqlite3_context, sqlite3_create_function_v2,
+    sqlite3_result_blob, sqlite3_result_null, sqlite3_result_text, sqlite3_value,
+    sqlite3_value_type, try_sqlite, SQLITE_DETERMINISTIC, SQLITE_INNOCUOUS, SQLITE_NULL, SQLITE_OK,
+    SQLITE_TRANSIENT, SQLITE_UTF8,
+};
+
+type XFunc = unsafe extern "C" fn(*mut sqlite3_context, c_int, *mut *mut sqlite3_value);
+
+const FUNCTIONS: [(&CStr, XFunc); 6] = [
+    (c"sha256", x_hash::<Sha256, false>),
+    (c"sha256_blob", x_hash::<Sha256, true>),
+    (c"sha1", x_hash::<Sha1, false>),
+    (c"sha1_blob", x_hash::<Sha1, true>),
+    (c"md5", x_hash::<Md5, false>),
+    (c"md5_blob", x_hash::<Md5, true>),
+];
+
+fn to_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        // Writing to a String never fails.
+        let _ = write!(hex, "{b:02x}");
+    }
+    hex
+}
+
+unsafe extern "C" fn x_hash<D: Digest, const BLOB: bool>(
+    ctx: *mut sqlite3_context,
+    _argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    let value = *argv;
+    if sqlite3_value_type(value) == SQLITE_NULL {
+        sqlite3_result_null(ctx);
+        return;
+    }
+
+    let digest = D::digest(value_bytes(value));
+    if BLOB {
+        sqlite3_result_blob(
+            ctx,
+            digest.as_ptr().cast(),
+            digest.len() as c_int,
+            SQLITE_TRANSIENT(),
+        );
+    } else {
+        let hex = to_hex(&digest);
+        sqlite3_result_text(
+            ctx,
+            hex.as_ptr().cast(),
+            hex.len() as c_int,
+            SQLITE_TRANSIENT(),
+        );
+    }
+}
+
+/// Register `sha256(X)`, `sha1(X)`, `md5(X)` and their `_blob` variants.
+///
+/// The text variants return the lowercase hex digest, the `_blob` variants
+/// return the raw digest. `NULL` hashes to `NULL`.
+///
+/// The signature matches `sqlite3_auto_extension`, so it can be registered
+/// for every new connection or called directly on an open one.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe extern "C" fn sqlite3_hash_init(
+    db: *mut sqlite3,
+    _pz_err_msg: *mut *mut c_char,
+    _p_api: *const sqlite3_api_routines,
+) -> c_int {
+    for (name, func) in FUNCTIONS {
+        try_sqlite!(sqlite3_create_function_v2(
+            db,
+            name.as_ptr(),
+            1,
+            SQLITE_UTF8 | SQLITE_DETERMINISTIC | SQLITE_INNOCUOUS,
+            core::ptr::null_mut(),
+            Some(func),
+            None,
+            None,
+            None,
+        ));
+    }
+    SQLITE_OK
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sqlite3_hash_init;
+    use sqlite_wasm_rs::*;
+    use std::ffi::CStr;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    unsafe fn query_text(db: *mut sqlite3, sql: &CStr) -> Option<String> {
+        let mut stmt = std::ptr::null_mut();
+        let ret = sqlite3_prepare_v3(
+            db,
+            sql.as_ptr(),
+            -1,
+            0,
+            &mut stmt as *mut _,
+            std::ptr::null_mut(),
+        );
+        assert_eq!(SQLITE_OK, ret);
+        assert_eq!(SQLITE_ROW, sqlite3_step(stmt));
+        let text = sqlite3_column_text(stmt, 0);
+        let ret =
+            (!text.is_null()).then(|| CStr::from_ptr(text.cast()).to_str().unwrap().to_string());
+        sqlite3_finalize(stmt);
+        ret
+    }
+
+    #[wasm_bindgen_test]
+    fn test_hash_functions() {
+        unsafe {
+            let mut db = std::ptr::null_mut();
+            let ret = sqlite3_open_v2(
+                c":memory:".as_ptr().cast(),
+                &mut db as *mut _,
+                SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+                std::ptr::null(),
+            );
+            assert_eq!(SQLITE_OK, ret);
+            assert_eq!(
+                SQLITE_OK,
+                sqlite3_hash_init(db, std::ptr::null_mut(), std::ptr::null())
+            );
+
+            assert_eq!(
+                query_text(db, c"SELECT sha256('abc')").as_deref(),
+                Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+            );
+            assert_eq!(
+                query_text(db, c"SELECT sha1('abc')").as_deref(),
+                Some("a9993e364706816aba3e25717850c26c9cd0d89d")
+            );
+            assert_eq!(
+                query_text(db, c"SELECT md5('abc')").as_deref(),
+                Some("900150983cd24fb0d6963f7d28e17f72")
+            );
+            assert_eq!(
+                query_text(db, c"SELECT hex(md5_blob(x'616263'))").as_deref(),
+                Some("900150983CD24FB0D6963F7D28E17F72")
+            );
+            assert_eq!(
+                query_text(db, c"SELECT length(sha256_blob('abc'))").as_deref(),
+                Some("32")
+            );
+            assert_eq!(query_text(db, c"SELECT sha1(NULL)"), None);
+
+            sqlite3_close(db);
+        }
+    }
+}
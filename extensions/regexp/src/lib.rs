@@ -0,0 +1,288 @@
+#![doc = include_str!("../README.md")]
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use alloc::string::ToString;
+use core::ffi::{c_char, c_int, c_void, CStr};
+
+use regex::bytes::Regex;
+use sqlite_wasm_rs::helpers::value_bytes;
+use sqlite_wasm_rs::{
+    sqlite3, sqlite3_api_routines, sqlite3_context, sqlite3_create_function_v2,
+    sqlite3_get_auxdata, sqlite3_result_error, sqlite3_result_int, sqlite3_result_null,
+    sqlite3_result_text, sqlite3_set_auxdata, sqlite3_value, sqlite3_value_int, sqlite3_value_type,
+    try_sqlite, SQLITE_DETERMINISTIC, SQLITE_INNOCUOUS, SQLITE_NULL, SQLITE_OK, SQLITE_TRANSIENT,
+    SQLITE_UTF8,
+};
+
+#[cfg(test)]
+static COMPILED: core::sync::atomic::AtomicUsize = core::sync::atomic::AtomicUsize::new(0);
+
+type XFunc = unsafe extern "C" fn(*mut sqlite3_context, c_int, *mut *mut sqlite3_value);
+
+const FUNCTIONS: [(&CStr, c_int, XFunc); 4] = [
+    (c"regexp", 2, x_regexp),
+    (c"regexp_replace", 3, x_regexp_replace),
+    (c"regexp_extract", 2, x_regexp_extract),
+    (c"regexp_extract", 3, x_regexp_extract),
+];
+
+unsafe fn any_null(argv: *mut *mut sqlite3_value, argc: usize) -> bool {
+    (0..argc).any(|i| sqlite3_value_type(*argv.add(i)) == SQLITE_NULL)
+}
+
+unsafe fn result_bytes(ctx: *mut sqlite3_context, bytes: &[u8]) {
+    sqlite3_result_text(
+        ctx,
+        bytes.as_ptr().cast(),
+        bytes.len() as c_int,
+        SQLITE_TRANSIENT(),
+    );
+}
+
+unsafe extern "C" fn drop_regex(regex: *mut c_void) {
+    drop(Box::from_raw(regex.cast::<Regex>()));
+}
+
+/// Run `f` with the compiled pattern of argument `idx`.
+///
+/// The compiled pattern is stored with `sqlite3_set_auxdata`, so a constant
+/// pattern is compiled once per statement instead of once per row.
+unsafe fn with_regex(
+    ctx: *mut sqlite3_context,
+    argv: *mut *mut sqlite3_value,
+    idx: c_int,
+    f: impl FnOnce(&Regex),
+) {
+    let cached = sqlite3_get_auxdata(ctx, idx).cast::<Regex>();
+    if !cached.is_null() {
+        f(&*cached);
+        return;
+    }
+
+    let pattern = match core::str::from_utf8(value_bytes(*argv.add(idx as usize))) {
+        Ok(pattern) => pattern,
+        Err(_) => {
+            sqlite3_result_error(ctx, c"regexp: pattern is not valid UTF-8".as_ptr(), -1);
+            return;
+        }
+    };
+    let regex = match Regex::new(pattern) {
+        Ok(regex) => regex,
+        Err(err) => {
+            let msg = err.to_string();
+            sqlite3_result_error(ctx, msg.as_ptr().cast(), msg.len() as c_int);
+            return;
+        }
+    };
+    #[cfg(test)]
+    COMPILED.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+
+    f(&regex);
+    // SQLite takes ownership here, it may call the destructor right away
+    // if the pattern is not a constant.
+    sqlite3_set_auxdata(
+        ctx,
+        idx,
+        Box::into_raw(Box::new(regex)).cast(),
+        Some(drop_regex),
+    );
+}
+
+/// `regexp(pattern, text)`, this is also what `text REGEXP pattern` calls.
+unsafe extern "C" fn x_regexp(
+    ctx: *mut sqlite3_context,
+    argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    if any_null(argv, argc as usize) {
+        sqlite3_result_null(ctx);
+        return;
+    }
+    let text = value_bytes(*argv.add(1));
+    with_regex(ctx, argv, 0, |regex| {
+        sqlite3_result_int(ctx, c_int::from(regex.is_match(text)));
+    });
+}
+
+/// `regexp_replace(text, pattern, replacement)`, replaces every match.
+unsafe extern "C" fn x_regexp_replace(
+    ctx: *mut sqlite3_context,
+    argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    if any_null(argv, argc as usize) {
+        sqlite3_result_null(ctx);
+        return;
+    }
+    let text = value_bytes(*argv);
+    let replacement = value_bytes(*argv.add(2));
+    with_regex(ctx, argv, 1, |regex| {
+        result_bytes(ctx, &regex.replace_all(text, replacement));
+    });
+}
+
+/// `regexp_extract(text, pattern [, group])`, returns the first match or
+/// the given capture group of it, `NULL` if there is none.
+unsafe extern "C" fn x_regexp_extract(
+    ctx: *mut sqlite3_context,
+    argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    if any_null(argv, 2) {
+        sqlite3_result_null(ctx);
+        return;
+    }
+    let text = value_bytes(*argv);
+    let group = if argc > 2 {
+        sqlite3_value_int(*argv.add(2))
+    } else {
+        0
+    };
+    with_regex(ctx, argv, 1, |regex| {
+        let matched = usize::try_from(group)
+            .ok()
+            .and_then(|group| regex.captures(text)?.get(group));
+        match matched {
+            Some(m) => result_bytes(ctx, m.as_bytes()),
+            None => sqlite3_result_null(ctx),
+        }
+    });
+}
+
+/// Register `regexp(P, X)`, `regexp_replace(X, P, R)` and
+/// `regexp_extract(X, P [, N])`.
+///
+/// Registering `regexp` enables the `X REGEXP P` operator. Patterns use the
+/// syntax of the [`regex`](https://docs.rs/regex) crate, an invalid pattern
+/// fails the statement with the parse error as message.
+///
+/// The signature matches `sqlite3_auto_extension`, so it can be registered
+/// for every new connection or called directly on an open one.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe extern "C" fn sqlite3_regexp_init(
+    db: *mut sqlite3,
+    _pz_err_msg: *mut *mut c_char,
+    _p_api: *const sqlite3_api_routines,
+) -> c_int {
+    for (name, n_arg, func) in FUNCTIONS {
+        try_sqlite!(sqlite3_create_function_v2(
+            db,
+            name.as_ptr(),
+            n_arg,
+            SQLITE_UTF8 | SQLITE_DETERMINISTIC | SQLITE_INNOCUOUS,
+            core::ptr::null_mut(),
+            Some(func),
+            None,
+            None,
+            None,
+        ));
+    }
+    SQLITE_OK
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sqlite3_regexp_init, COMPILED};
+    use core::sync::atomic::Ordering;
+    use sqlite_wasm_rs::*;
+    use std::ffi::CStr;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    unsafe fn query_texts(db: *mut sqlite3, sql: &CStr) -> Vec<Option<String>> {
+        let mut stmt = std::ptr::null_mut();
+        let ret = sqlite3_prepare_v3(
+            db,
+            sql.as_ptr(),
+            -1,
+            0,
+            &mut stmt as *mut _,
+            std::ptr::null_mut(),
+        );
+        assert_eq!(SQLITE_OK, ret);
+        let mut rows = vec![];
+        while sqlite3_step(stmt) == SQLITE_ROW {
+            let text = sqlite3_column_text(stmt, 0);
+            rows.push(
+                (!text.is_null())
+                    .then(|| CStr::from_ptr(text.cast()).to_str().unwrap().to_string()),
+            );
+        }
+        sqlite3_finalize(stmt);
+        rows
+    }
+
+    #[wasm_bindgen_test]
+    fn test_regexp_functions() {
+        unsafe {
+            let mut db = std::ptr::null_mut();
+            let ret = sqlite3_open_v2(
+                c":memory:".as_ptr().cast(),
+                &mut db as *mut _,
+                SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+                std::ptr::null(),
+            );
+            assert_eq!(SQLITE_OK, ret);
+            assert_eq!(
+                SQLITE_OK,
+                sqlite3_regexp_init(db, std::ptr::null_mut(), std::ptr::null())
+            );
+            let ret = sqlite3_exec(
+                db,
+                c"CREATE TABLE t(name TEXT);
+                INSERT INTO t VALUES ('apple'), ('banana'), ('avocado'), ('cherry'), (NULL);"
+                    .as_ptr(),
+                None,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            );
+            assert_eq!(SQLITE_OK, ret);
+
+            let compiled = COMPILED.load(Ordering::Relaxed);
+            assert_eq!(
+                query_texts(
+                    db,
+                    c"SELECT name FROM t WHERE name REGEXP '^a' ORDER BY name"
+                ),
+                [Some("apple".into()), Some("avocado".into())]
+            );
+            // The constant pattern is compiled once and reused for every row.
+            assert_eq!(compiled + 1, COMPILED.load(Ordering::Relaxed));
+
+            assert_eq!(
+                query_texts(
+                    db,
+                    c"SELECT regexp_replace(name, '[aeiou]', '_') FROM t WHERE rowid <= 2"
+                ),
+                [Some("_ppl_".into()), Some("b_n_n_".into())]
+            );
+            assert_eq!(
+                query_texts(
+                    db,
+                    c"SELECT regexp_extract('key=value; k2=v2', '(\\w+)=(\\w+)', 2)"
+                ),
+                [Some("value".into())]
+            );
+            assert_eq!(
+                query_texts(db, c"SELECT regexp_extract('abc', '\\d+')"),
+                [None]
+            );
+
+            let ret = sqlite3_exec(
+                db,
+                c"SELECT 'abc' REGEXP '('".as_ptr(),
+                None,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            );
+            assert_eq!(SQLITE_ERROR, ret);
+
+            sqlite3_close(db);
+        }
+    }
+}
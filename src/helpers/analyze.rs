@@ -0,0 +1,49 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::stmt::{exec, Stmt};
+use super::Result;
+use crate::sqlite3;
+
+/// Run `ANALYZE` on every attached database of `db`.
+///
+/// This gathers the statistics stored in `sqlite_stat1`, which the query
+/// planner uses to choose between indexes. See [`analyze_stats`] to read them.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn analyze(db: *mut sqlite3) -> Result<()> {
+    exec(db, "ANALYZE")
+}
+
+/// The `(table, index, stat)` rows of `sqlite_stat1` of the main database.
+///
+/// `stat` starts with the approximate number of rows of the table, followed
+/// by the average number of rows matching each prefix of the index columns.
+/// The index is empty for the row count of a table without indexes. Returns
+/// no rows if the database was never analyzed.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn analyze_stats(db: *mut sqlite3) -> Result<Vec<(String, String, String)>> {
+    let exists = Stmt::prepare(
+        db,
+        "SELECT 1 FROM sqlite_schema WHERE type = 'table' AND name = 'sqlite_stat1'",
+    )?;
+    if !exists.step()? {
+        return Ok(Vec::new());
+    }
+
+    let stmt = Stmt::prepare(db, "SELECT tbl, idx, stat FROM sqlite_stat1")?;
+    let mut stats = Vec::new();
+    while stmt.step()? {
+        stats.push((
+            stmt.column_text(0).unwrap_or_default(),
+            stmt.column_text(1).unwrap_or_default(),
+            stmt.column_text(2).unwrap_or_default(),
+        ));
+    }
+    Ok(stats)
+}
@@ -0,0 +1,95 @@
+use alloc::string::String;
+use core::ptr;
+
+use super::stmt::Stmt;
+use super::Result;
+use crate::sqlite3;
+
+/// Attach a new, empty in-memory database as `schema_alias`.
+///
+/// Runs `ATTACH ':memory:' AS schema_alias`, which is handy as scratch space
+/// for temporary computations. The alias is bound as a parameter, so it
+/// needs no quoting. The database lives until it is detached or the
+/// connection is closed.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn attach_memory(db: *mut sqlite3, schema_alias: &str) -> Result<()> {
+    let stmt = Stmt::prepare(db, "ATTACH ':memory:' AS ?1")?;
+    stmt.bind_text(1, schema_alias)?;
+    stmt.step()?;
+    Ok(())
+}
+
+/// An attached database, detached again when the guard is dropped.
+///
+/// Runs `ATTACH filename AS schema_alias` on creation and
+/// `DETACH schema_alias` on drop, so the schema can't outlive the scope that
+/// uses it. `DETACH` fails while a statement reading the schema is still
+/// running, dropping the guard then leaves the database attached. Use
+/// [`Attached::detach`] to see that error.
+///
+/// ```rust
+/// use sqlite_wasm_rs::{self as ffi, helpers::{Attached, Result}};
+///
+/// unsafe fn with_archive(db: *mut ffi::sqlite3) -> Result<()> {
+///     let archive = Attached::new(db, "archive.db", "archive")?;
+///     // ... use `archive.items` ...
+///     archive.detach()
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Attached {
+    db: *mut sqlite3,
+    schema_alias: String,
+}
+
+impl Attached {
+    /// Attach the database `filename` as `schema_alias`.
+    ///
+    /// `filename` is interpreted like in `ATTACH`, e.g. `:memory:` creates a
+    /// new in-memory database and URI filenames select the VFS. Both values
+    /// are bound as parameters, so they need no quoting.
+    ///
+    /// # Safety
+    ///
+    /// `db` must be a valid database connection that outlives the guard.
+    pub unsafe fn new(db: *mut sqlite3, filename: &str, schema_alias: &str) -> Result<Self> {
+        let stmt = Stmt::prepare(db, "ATTACH ?1 AS ?2")?;
+        stmt.bind_text(1, filename)?;
+        stmt.bind_text(2, schema_alias)?;
+        stmt.step()?;
+        Ok(Self {
+            db,
+            schema_alias: schema_alias.into(),
+        })
+    }
+
+    /// The schema name the database is attached as.
+    pub fn schema_alias(&self) -> &str {
+        &self.schema_alias
+    }
+
+    /// Detach the database now and report whether that worked.
+    pub fn detach(mut self) -> Result<()> {
+        let ret = unsafe { self.run_detach() };
+        self.db = ptr::null_mut();
+        ret
+    }
+
+    unsafe fn run_detach(&self) -> Result<()> {
+        let stmt = Stmt::prepare(self.db, "DETACH ?1")?;
+        stmt.bind_text(1, &self.schema_alias)?;
+        stmt.step()?;
+        Ok(())
+    }
+}
+
+impl Drop for Attached {
+    fn drop(&mut self) {
+        if !self.db.is_null() {
+            let _ = unsafe { self.run_detach() };
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use core::ffi::{c_char, c_int};
+
+use super::{Result, SqliteError};
+use crate::{
+    sqlite3, sqlite3_api_routines, sqlite3_auto_extension, sqlite3_cancel_auto_extension, SQLITE_OK,
+};
+
+/// The entry point of an extension, as taken by `sqlite3_auto_extension`.
+///
+/// The `sqlite3_*_init` functions of the extension crates in this repository
+/// have this signature.
+pub type ExtensionInit = unsafe extern "C" fn(
+    db: *mut sqlite3,
+    pz_err_msg: *mut *mut c_char,
+    p_api: *const sqlite3_api_routines,
+) -> c_int;
+
+/// Register `init` to be called on every database connection opened from
+/// now on.
+///
+/// Connections that are already open are not affected. Registering the
+/// same function twice is a no-op. If `init` fails, opening the connection
+/// fails with its error.
+///
+/// # Safety
+///
+/// `init` must be safe to call with any new connection, from any thread
+/// that opens one.
+pub unsafe fn register_auto_extension(init: ExtensionInit) -> Result<()> {
+    let ret = sqlite3_auto_extension(Some(init));
+    if ret != SQLITE_OK {
+        return Err(SqliteError::from_code(ret));
+    }
+    Ok(())
+}
+
+/// Stop calling `init` on new connections and return whether it was
+/// registered.
+///
+/// Functions the extension already added to open connections stay there.
+pub fn cancel_auto_extension(init: ExtensionInit) -> bool {
+    unsafe { sqlite3_cancel_auto_extension(Some(init)) != 0 }
+}
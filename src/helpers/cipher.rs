@@ -0,0 +1,52 @@
+use core::ffi::{c_int, CStr};
+
+use super::{Result, SqliteError};
+use crate::{sqlite3, sqlite3_key_v2, sqlite3_rekey_v2, SQLITE_OK};
+
+/// Set the encryption key of the database `schema`, e.g. `c"main"`.
+///
+/// Wraps `sqlite3_key_v2` of SQLite3MultipleCiphers. Call it right after
+/// opening, before the database is read: a new database is encrypted with
+/// `passphrase`, an existing one is decrypted with it. A wrong key is only
+/// noticed when the database is read, which then fails with `SQLITE_NOTADB`.
+/// The cipher defaults to ChaCha20, select another one with `PRAGMA cipher`
+/// before setting the key.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection, opened with a VFS wrapped by
+/// SQLite3MultipleCiphers, e.g. `multipleciphers-memvfs`.
+pub unsafe fn key(db: *mut sqlite3, schema: &CStr, passphrase: &[u8]) -> Result<()> {
+    let ret = sqlite3_key_v2(
+        db,
+        schema.as_ptr(),
+        passphrase.as_ptr().cast(),
+        passphrase.len() as c_int,
+    );
+    match ret {
+        SQLITE_OK => Ok(()),
+        _ => Err(SqliteError::from_code(ret)),
+    }
+}
+
+/// Change the encryption key of the database `schema`, re-encrypting it.
+///
+/// Wraps `sqlite3_rekey_v2` of SQLite3MultipleCiphers. The database must be
+/// opened with its current key first. An empty key decrypts the database.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection, opened with a VFS wrapped by
+/// SQLite3MultipleCiphers, e.g. `multipleciphers-memvfs`.
+pub unsafe fn rekey(db: *mut sqlite3, schema: &CStr, passphrase: &[u8]) -> Result<()> {
+    let ret = sqlite3_rekey_v2(
+        db,
+        schema.as_ptr(),
+        passphrase.as_ptr().cast(),
+        passphrase.len() as c_int,
+    );
+    match ret {
+        SQLITE_OK => Ok(()),
+        _ => Err(SqliteError::from_code(ret)),
+    }
+}
@@ -0,0 +1,53 @@
+use core::ffi::c_int;
+
+use super::{Result, SqliteError};
+use crate::{
+    sqlite3_config, SQLITE_CONFIG_LOOKASIDE, SQLITE_CONFIG_MEMSTATUS, SQLITE_OK, SQLITE_RANGE,
+};
+
+/// Turn memory allocation statistics on or off with `SQLITE_CONFIG_MEMSTATUS`.
+///
+/// Accounting costs a little on every allocation. While it is off,
+/// `sqlite3_memory_used`, `sqlite3_memory_highwater`, `sqlite3_status64`
+/// and the heap limits stop tracking allocations, so the reported usage no
+/// longer changes. It is on by default.
+///
+/// Like every `sqlite3_config` option, this only works before
+/// `sqlite3_initialize` or after `sqlite3_shutdown`, and fails with
+/// `SQLITE_MISUSE` otherwise.
+///
+/// # Safety
+///
+/// Must not run concurrently with any other SQLite call.
+pub unsafe fn config_memstatus(enabled: bool) -> Result<()> {
+    let ret = sqlite3_config(SQLITE_CONFIG_MEMSTATUS, c_int::from(enabled));
+    if ret != SQLITE_OK {
+        return Err(SqliteError::from_code(ret));
+    }
+    Ok(())
+}
+
+/// Set the default lookaside of new connections to `slot_count` slots of
+/// `slot_size` bytes, with `SQLITE_CONFIG_LOOKASIDE`.
+///
+/// SQLite allocates the memory itself when a connection is opened. Use
+/// [`configure_lookaside`](super::configure_lookaside) to change it for a
+/// single connection. A `slot_count` of 0 disables lookaside.
+///
+/// Like every `sqlite3_config` option, this only works before
+/// `sqlite3_initialize` or after `sqlite3_shutdown`, and fails with
+/// `SQLITE_MISUSE` otherwise.
+///
+/// # Safety
+///
+/// Must not run concurrently with any other SQLite call.
+pub unsafe fn config_lookaside(slot_size: usize, slot_count: usize) -> Result<()> {
+    let (Ok(size), Ok(count)) = (c_int::try_from(slot_size), c_int::try_from(slot_count)) else {
+        return Err(SqliteError::from_code(SQLITE_RANGE));
+    };
+    let ret = sqlite3_config(SQLITE_CONFIG_LOOKASIDE, size, count);
+    if ret != SQLITE_OK {
+        return Err(SqliteError::from_code(ret));
+    }
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ffi::{c_int, CStr};
+
+use super::open::open;
+use super::stmt::{exec, Stmt};
+use super::{last_error, quote_identifier, serialize_to_vec, Result, SqliteError};
+use crate::{
+    sqlite3, sqlite3_backup_finish, sqlite3_backup_init, sqlite3_backup_step, sqlite3_bind_value,
+    sqlite3_close, sqlite3_column_value, SQLITE_ERROR, SQLITE_OK, SQLITE_OPEN_CREATE,
+    SQLITE_OPEN_READONLY, SQLITE_OPEN_READWRITE,
+};
+
+const TABLE_SQL: &str = "SELECT sql FROM sqlite_schema WHERE type = 'table' AND name = ?1";
+
+unsafe fn table_sql(db: *mut sqlite3, table: &str) -> Result<Option<String>> {
+    let stmt = Stmt::prepare(db, TABLE_SQL)?;
+    stmt.bind_text(1, table)?;
+    Ok(if stmt.step()? {
+        stmt.column_text(0)
+    } else {
+        None
+    })
+}
+
+/// The columns of `table` in declaration order, quoted for use in SQL.
+/// Empty if there is no such table.
+pub(super) unsafe fn table_columns(db: *mut sqlite3, table: &str) -> Result<Vec<String>> {
+    let stmt = Stmt::prepare(db, "SELECT name FROM pragma_table_info(?1)")?;
+    stmt.bind_text(1, table)?;
+    let mut columns = Vec::new();
+    while stmt.step()? {
+        columns.extend(stmt.column_text(0).as_deref().map(quote_identifier));
+    }
+    Ok(columns)
+}
+
+pub(super) unsafe fn copy_rows(src: *mut sqlite3, dst: *mut sqlite3, table: &str) -> Result<usize> {
+    let columns = table_columns(src, table)?;
+
+    let table = quote_identifier(table);
+    let columns_list = columns.join(", ");
+    let params = (1..=columns.len())
+        .map(|idx| format!("?{idx}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let select = Stmt::prepare(src, &format!("SELECT {columns_list} FROM {table}"))?;
+    let insert = Stmt::prepare(
+        dst,
+        &format!("INSERT INTO {table} ({columns_list}) VALUES ({params})"),
+    )?;
+
+    let mut rows = 0;
+    while select.step()? {
+        for idx in 0..columns.len() as c_int {
+            let value = sqlite3_column_value(select.as_ptr(), idx);
+            let ret = sqlite3_bind_value(insert.as_ptr(), idx + 1, value);
+            if ret != SQLITE_OK {
+                return Err(last_error(dst));
+            }
+        }
+        insert.step()?;
+        insert.reset()?;
+        rows += 1;
+    }
+    Ok(rows)
+}
+
+/// Copy every row of `table` from `src` to `dst`, returns the number of rows copied.
+///
+/// The columns are read with `PRAGMA table_info`, rows are streamed from a
+/// `SELECT` on `src` into a parameterized `INSERT` on `dst` with
+/// `sqlite3_bind_value`, so values keep their exact type. If `dst` has no
+/// such table, it is created with the `CREATE TABLE` statement of `src`.
+///
+/// The copy runs inside a savepoint on `dst` and is rolled back on error.
+///
+/// # Safety
+///
+/// `src` and `dst` must be valid database connections.
+pub unsafe fn copy_table(src: *mut sqlite3, dst: *mut sqlite3, table: &str) -> Result<usize> {
+    let Some(create) = table_sql(src, table)? else {
+        return Err(SqliteError::new(
+            SQLITE_ERROR,
+            format!("no such table: {table}"),
+        ));
+    };
+
+    exec(dst, "SAVEPOINT copy_table")?;
+    let copied = (|| {
+        if table_sql(dst, table)?.is_none() {
+            exec(dst, &create)?;
+        }
+        copy_rows(src, dst, table)
+    })();
+    match copied {
+        Ok(rows) => {
+            exec(dst, "RELEASE copy_table")?;
+            Ok(rows)
+        }
+        Err(err) => {
+            // Keep the original error, the rollback can only fail if `dst` is unusable.
+            let _ = exec(dst, "ROLLBACK TO copy_table; RELEASE copy_table");
+            Err(err)
+        }
+    }
+}
+
+unsafe fn export_into(src: *mut sqlite3, dst: *mut sqlite3, tables: &[&str]) -> Result<()> {
+    for table in tables {
+        copy_table(src, dst, table)?;
+        let indexes = Stmt::prepare(
+            src,
+            "SELECT sql FROM sqlite_schema WHERE type = 'index' AND tbl_name = ?1 AND sql IS NOT NULL",
+        )?;
+        indexes.bind_text(1, table)?;
+        while indexes.step()? {
+            exec(dst, &indexes.column_text(0).unwrap_or_default())?;
+        }
+    }
+    Ok(())
+}
+
+/// Copy `tables` of `src` into a new database and return it serialized.
+///
+/// Each table is created and filled like with [`copy_table`], then its
+/// indexes are created. Triggers and views are left out, since they can
+/// refer to tables that are not exported. The bytes can be loaded with
+/// `sqlite3_deserialize` or imported into a VFS, e.g. to share part of a
+/// database.
+///
+/// # Safety
+///
+/// `src` must be a valid database connection.
+pub unsafe fn export_tables(src: *mut sqlite3, tables: &[&str]) -> Result<Vec<u8>> {
+    let dst = open(
+        c":memory:",
+        SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+        None,
+    )?;
+    let exported = export_into(src, dst, tables).and_then(|()| serialize_to_vec(dst, c"main"));
+    sqlite3_close(dst);
+    exported
+}
+
+/// Copy the main database of `src` over the main database of `dst` with the
+/// online backup API.
+unsafe fn backup_main(src: *mut sqlite3, dst: *mut sqlite3) -> Result<()> {
+    let backup = sqlite3_backup_init(dst, c"main".as_ptr(), src, c"main".as_ptr());
+    if backup.is_null() {
+        return Err(last_error(dst));
+    }
+    // Errors of the step are reported again by `sqlite3_backup_finish`.
+    sqlite3_backup_step(backup, -1);
+    match sqlite3_backup_finish(backup) {
+        SQLITE_OK => Ok(()),
+        _ => Err(last_error(dst)),
+    }
+}
+
+/// Copy the database `src_name` of the VFS `src_vfs` to `dst_name` of the
+/// VFS `dst_vfs`, e.g. to move a database from `relaxed-idb` to
+/// `opfs-sahpool`.
+///
+/// Both databases are opened through SQLite and the pages are copied with
+/// the online backup API, so this works for any pair of registered VFSes
+/// without going through their import utilities. The source is opened
+/// read-only and must exist. The destination is created if needed, and any
+/// content it had is replaced.
+///
+/// # Safety
+///
+/// Neither database may be written by another connection during the copy.
+pub unsafe fn migrate_db(
+    src_vfs: &CStr,
+    src_name: &CStr,
+    dst_vfs: &CStr,
+    dst_name: &CStr,
+) -> Result<()> {
+    let src = open(src_name, SQLITE_OPEN_READONLY, Some(src_vfs))?;
+    let migrated = open(
+        dst_name,
+        SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+        Some(dst_vfs),
+    )
+    .and_then(|dst| {
+        let copied = backup_main(src, dst);
+        sqlite3_close(dst);
+        copied
+    });
+    sqlite3_close(src);
+    migrated
+}
@@ -0,0 +1,76 @@
+use alloc::format;
+use alloc::string::String;
+use core::ffi::CStr;
+use core::fmt::Write;
+
+use super::stmt::Stmt;
+use super::{quote_identifier, Result};
+use crate::{
+    sqlite3, sqlite3_column_blob, sqlite3_column_bytes, sqlite3_column_count, sqlite3_column_name,
+    sqlite3_column_type, SQLITE_BLOB, SQLITE_NULL,
+};
+
+/// Append `field` to `csv`, quoted if it contains a comma, quote or line break.
+fn push_field(csv: &mut String, field: &str) {
+    if field.contains([',', '"', '\r', '\n']) {
+        csv.push('"');
+        csv.push_str(&field.replace('"', "\"\""));
+        csv.push('"');
+    } else {
+        csv.push_str(field);
+    }
+}
+
+/// The rows of `table` as RFC 4180 CSV text, with a header row of the
+/// column names.
+///
+/// Records end with CRLF. Fields containing commas, quotes or line breaks
+/// are quoted, with quotes doubled. `NULL` is an empty field, blobs are
+/// written as uppercase hex and other values as `sqlite3_column_text`
+/// renders them, e.g. `1.5`.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn export_csv(db: *mut sqlite3, table: &str) -> Result<String> {
+    let stmt = Stmt::prepare(db, &format!("SELECT * FROM {}", quote_identifier(table)))?;
+    let columns = sqlite3_column_count(stmt.as_ptr());
+
+    let mut csv = String::new();
+    for idx in 0..columns {
+        if idx > 0 {
+            csv.push(',');
+        }
+        let name = sqlite3_column_name(stmt.as_ptr(), idx);
+        if !name.is_null() {
+            push_field(&mut csv, &CStr::from_ptr(name).to_string_lossy());
+        }
+    }
+    csv.push_str("\r\n");
+
+    while stmt.step()? {
+        for idx in 0..columns {
+            if idx > 0 {
+                csv.push(',');
+            }
+            match sqlite3_column_type(stmt.as_ptr(), idx) {
+                SQLITE_NULL => (),
+                SQLITE_BLOB => {
+                    // `sqlite3_column_blob` must be called before `sqlite3_column_bytes`.
+                    let ptr = sqlite3_column_blob(stmt.as_ptr(), idx);
+                    let len = sqlite3_column_bytes(stmt.as_ptr(), idx);
+                    if !ptr.is_null() && len > 0 {
+                        let blob = core::slice::from_raw_parts(ptr.cast::<u8>(), len as usize);
+                        for byte in blob {
+                            // Writing to a String never fails.
+                            let _ = write!(csv, "{byte:02X}");
+                        }
+                    }
+                }
+                _ => push_field(&mut csv, &stmt.column_text(idx).unwrap_or_default()),
+            }
+        }
+        csv.push_str("\r\n");
+    }
+    Ok(csv)
+}
@@ -0,0 +1,32 @@
+use core::ffi::c_int;
+
+use super::{Result, SqliteError};
+use crate::{sqlite3, sqlite3_db_config, SQLITE_DBCONFIG_TRUSTED_SCHEMA, SQLITE_OK};
+
+/// Turn `SQLITE_DBCONFIG_TRUSTED_SCHEMA` on or off and return the resulting
+/// setting.
+///
+/// With trusted schema off, views and triggers may only call SQL functions
+/// registered with `SQLITE_INNOCUOUS`, and virtual tables not marked
+/// innocuous can't be used from them either. Statements doing so fail with
+/// `SQLITE_ERROR` and an "unsafe use of" message. Turn it off for databases
+/// from untrusted sources, so a crafted schema can't make the application
+/// call its own functions behind its back. It is on by default unless
+/// SQLite is built with `SQLITE_TRUSTED_SCHEMA=0`.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn set_trusted_schema(db: *mut sqlite3, enabled: bool) -> Result<bool> {
+    let mut state: c_int = 0;
+    let ret = sqlite3_db_config(
+        db,
+        SQLITE_DBCONFIG_TRUSTED_SCHEMA,
+        c_int::from(enabled),
+        &mut state as *mut c_int,
+    );
+    if ret != SQLITE_OK {
+        return Err(SqliteError::from_code(ret));
+    }
+    Ok(state != 0)
+}
@@ -0,0 +1,128 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::copy::table_columns;
+use super::stmt::Stmt;
+use super::{quote_identifier, Result};
+use crate::sqlite3;
+
+/// The `CREATE` statements of the main database, separated by `;` and a newline.
+///
+/// Reads `sql` from `sqlite_schema` in creation order, which covers tables,
+/// indexes, views and triggers. Automatic indexes have no SQL and are left
+/// out. Returns an empty string for an empty database.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn dump_schema(db: *mut sqlite3) -> Result<String> {
+    let stmt = Stmt::prepare(db, "SELECT sql FROM sqlite_schema WHERE sql IS NOT NULL")?;
+    let mut schema = String::new();
+    while stmt.step()? {
+        if let Some(sql) = stmt.column_text(0) {
+            schema.push_str(&sql);
+            schema.push_str(";\n");
+        }
+    }
+    Ok(schema)
+}
+
+/// Append an `INSERT` statement for every row of `table` to `dump`.
+unsafe fn dump_rows(db: *mut sqlite3, table: &str, dump: &mut String) -> Result<()> {
+    let columns = table_columns(db, table)?;
+    if columns.is_empty() {
+        return Ok(());
+    }
+
+    let table = quote_identifier(table);
+    let columns_list = columns.join(",");
+    // `quote()` renders each value as an SQL literal of the same type.
+    let values = columns
+        .iter()
+        .map(|column| format!("quote({column})"))
+        .collect::<Vec<_>>()
+        .join(" || ',' || ");
+    let stmt = Stmt::prepare(db, &format!("SELECT {values} FROM {table}"))?;
+    while stmt.step()? {
+        let values = stmt.column_text(0).unwrap_or_default();
+        dump.push_str(&format!(
+            "INSERT INTO {table}({columns_list}) VALUES({values});\n"
+        ));
+    }
+    Ok(())
+}
+
+/// A `.dump` style SQL script of the main database, schema and rows.
+///
+/// The script creates every table, inserts its rows with values quoted by
+/// `quote()`, then creates indexes, triggers and views, inside one
+/// transaction. Running it on an empty database recreates the content, so
+/// it can be offered as a portable download.
+///
+/// Like the `.dump` command of the SQLite shell, virtual tables are written
+/// straight into `sqlite_schema` with `PRAGMA writable_schema`, and their
+/// shadow tables are dumped as ordinary tables, so e.g. FTS5 indexes keep
+/// their content. Internal `sqlite_` tables are skipped, except for
+/// `sqlite_sequence` so `AUTOINCREMENT` counters are kept.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn dump_all(db: *mut sqlite3) -> Result<String> {
+    let mut dump = String::from("PRAGMA foreign_keys=OFF;\nBEGIN TRANSACTION;\n");
+
+    let tables = Stmt::prepare(
+        db,
+        "SELECT name, sql, \
+         format('INSERT INTO sqlite_schema(type,name,tbl_name,rootpage,sql) \
+         VALUES(''table'',%Q,%Q,0,%Q);', name, name, sql) \
+         FROM sqlite_schema WHERE type = 'table' AND sql IS NOT NULL ORDER BY rowid",
+    )?;
+    let mut has_sequence = false;
+    let mut writable_schema = false;
+    while tables.step()? {
+        let name = tables.column_text(0).unwrap_or_default();
+        let sql = tables.column_text(1).unwrap_or_default();
+        if name == "sqlite_sequence" {
+            has_sequence = true;
+        } else if name.starts_with("sqlite_") {
+            // Internal tables, e.g. `sqlite_stat1`, are recreated by SQLite.
+        } else if sql.starts_with("CREATE VIRTUAL TABLE") {
+            // Creating the table would also create its shadow tables, which
+            // are dumped separately.
+            if !writable_schema {
+                dump.push_str("PRAGMA writable_schema=ON;\n");
+                writable_schema = true;
+            }
+            dump.push_str(&tables.column_text(2).unwrap_or_default());
+            dump.push('\n');
+        } else {
+            dump.push_str(&sql);
+            dump.push_str(";\n");
+            dump_rows(db, &name, &mut dump)?;
+        }
+    }
+    if has_sequence {
+        // The table is created together with the first AUTOINCREMENT table.
+        dump.push_str("DELETE FROM sqlite_sequence;\n");
+        dump_rows(db, "sqlite_sequence", &mut dump)?;
+    }
+
+    let others = Stmt::prepare(
+        db,
+        "SELECT sql FROM sqlite_schema \
+         WHERE type IN ('index', 'trigger', 'view') AND sql IS NOT NULL ORDER BY rowid",
+    )?;
+    while others.step()? {
+        dump.push_str(&others.column_text(0).unwrap_or_default());
+        dump.push_str(";\n");
+    }
+
+    dump.push_str("COMMIT;\n");
+    if writable_schema {
+        // Also reloads the schema, so the virtual tables can be used right away.
+        dump.push_str("PRAGMA writable_schema=RESET;\n");
+    }
+    Ok(dump)
+}
@@ -0,0 +1,89 @@
+use alloc::string::String;
+use core::ffi::{c_int, CStr};
+use core::fmt;
+
+use crate::{
+    sqlite3, sqlite3_errcode, sqlite3_errmsg, sqlite3_error_offset, sqlite3_errstr,
+    sqlite3_extended_errcode, Error, ErrorCode,
+};
+
+/// Result type of the helpers.
+pub type Result<T> = core::result::Result<T, SqliteError>;
+
+/// An error reported by SQLite, together with its message.
+///
+/// Unlike [`Error`], this also carries the text of `sqlite3_errmsg` and the
+/// position in the SQL that caused it, so it is usually obtained from
+/// [`last_error`] right after a call failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SqliteError {
+    /// Primary result code.
+    pub code: ErrorCode,
+    /// Extended result code, see `sqlite3_extended_errcode`.
+    pub extended_code: c_int,
+    /// English-language description of the error.
+    pub message: String,
+    /// Byte offset into the SQL of the token that caused the error, if any.
+    pub offset: Option<usize>,
+}
+
+impl SqliteError {
+    /// Create an error from a result code and a message.
+    pub fn new(result_code: c_int, message: impl Into<String>) -> Self {
+        Self {
+            code: Error::new(result_code).code,
+            extended_code: result_code,
+            message: message.into(),
+            offset: None,
+        }
+    }
+
+    /// Create an error from a result code, using `sqlite3_errstr` as message.
+    pub fn from_code(result_code: c_int) -> Self {
+        let message = unsafe { CStr::from_ptr(sqlite3_errstr(result_code)) };
+        Self::new(result_code, message.to_string_lossy())
+    }
+
+    /// The primary result code, e.g. `SQLITE_CONSTRAINT` for `SQLITE_CONSTRAINT_UNIQUE`.
+    pub fn primary_code(&self) -> c_int {
+        self.extended_code & 0xff
+    }
+}
+
+impl From<SqliteError> for Error {
+    fn from(err: SqliteError) -> Self {
+        Error::new(err.extended_code)
+    }
+}
+
+impl fmt::Display for SqliteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Error code {}: {}", self.extended_code, self.message)
+    }
+}
+
+impl core::error::Error for SqliteError {}
+
+/// The most recent error of a connection.
+///
+/// Gathers `sqlite3_errcode`, `sqlite3_extended_errcode`, `sqlite3_errmsg` and
+/// `sqlite3_error_offset` in one call. Like those functions, the result is
+/// only meaningful right after an API call on `db` failed.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn last_error(db: *mut sqlite3) -> SqliteError {
+    let extended_code = sqlite3_extended_errcode(db);
+    let message = CStr::from_ptr(sqlite3_errmsg(db))
+        .to_string_lossy()
+        .into_owned();
+    let offset = usize::try_from(sqlite3_error_offset(db)).ok();
+
+    SqliteError {
+        code: Error::new(sqlite3_errcode(db)).code,
+        extended_code,
+        message,
+        offset,
+    }
+}
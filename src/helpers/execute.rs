@@ -0,0 +1,301 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ffi::CStr;
+
+use js_sys::{Function, Promise, Reflect};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+use super::stmt::Stmt;
+use super::{quote_identifier, FromValue, IntoParams, Result, Row, SqliteError};
+use crate::{
+    sqlite3, sqlite3_changes64, sqlite3_column_count, sqlite3_column_name,
+    sqlite3_last_insert_rowid, sqlite3_set_last_insert_rowid, sqlite3_stmt_readonly,
+    sqlite3_total_changes64, SQLITE_RANGE,
+};
+
+/// The outcome of [`execute`], depending on whether the statement writes.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Execution {
+    /// The rows returned by a read-only statement.
+    Rows(Vec<Row>),
+    /// The number of rows inserted, updated or deleted by a statement that
+    /// writes. Statements that only change the schema report 0.
+    Changes(i64),
+}
+
+/// Run the first statement of `sql` with `params` bound to `?1`, `?2`, ...
+/// and return rows or a change count, depending on the statement.
+///
+/// `sqlite3_stmt_readonly` decides between the two: read-only statements,
+/// e.g. `SELECT`, return their rows, while every other statement returns
+/// the number of rows it changed, like `sqlite3_changes64`. Changes made by
+/// triggers are not counted. Rows of a `RETURNING` clause are dropped, use
+/// [`execute_returning`] to read them.
+///
+/// `params` is anything implementing [`IntoParams`], e.g. `&[Value]` or a
+/// tuple such as `(1, "a")`.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn execute(db: *mut sqlite3, sql: &str, params: impl IntoParams) -> Result<Execution> {
+    let stmt = Stmt::prepare(db, sql)?;
+    stmt.bind_all(&params.into_params())?;
+
+    if sqlite3_stmt_readonly(stmt.as_ptr()) != 0 {
+        let columns = sqlite3_column_count(stmt.as_ptr());
+        let mut rows = Vec::new();
+        while stmt.step()? {
+            rows.push((0..columns).map(|idx| stmt.column_value(idx)).collect());
+        }
+        return Ok(Execution::Rows(rows));
+    }
+
+    let total = sqlite3_total_changes64(db);
+    while stmt.step()? {}
+    // `sqlite3_changes64` keeps the count of the last INSERT, UPDATE or
+    // DELETE, so it is only read if this statement changed any rows.
+    Ok(Execution::Changes(
+        if sqlite3_total_changes64(db) == total {
+            0
+        } else {
+            sqlite3_changes64(db)
+        },
+    ))
+}
+
+/// The outcome of [`upsert`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UpsertResult {
+    /// The number of rows inserted or updated, like `sqlite3_changes64`.
+    pub changes: i64,
+    /// Whether a row was inserted rather than updated.
+    pub inserted: bool,
+}
+
+/// Run an `INSERT ... ON CONFLICT DO UPDATE` with `params` bound to `?1`,
+/// `?2`, ... and report whether it inserted or updated the row.
+///
+/// An insert sets `sqlite3_last_insert_rowid` while an update leaves it
+/// alone, so it is set to a sentinel before the statement runs and
+/// restored afterwards if no row was inserted. Tables `WITHOUT ROWID` never
+/// set it and always report an update, as does an inserted rowid of
+/// `i64::MIN`. Rows of a `RETURNING` clause are dropped.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn upsert(db: *mut sqlite3, sql: &str, params: impl IntoParams) -> Result<UpsertResult> {
+    const SENTINEL: i64 = i64::MIN;
+
+    let stmt = Stmt::prepare(db, sql)?;
+    stmt.bind_all(&params.into_params())?;
+
+    let last_rowid = sqlite3_last_insert_rowid(db);
+    let total = sqlite3_total_changes64(db);
+    sqlite3_set_last_insert_rowid(db, SENTINEL);
+    let ret: Result<()> = (|| {
+        while stmt.step()? {}
+        Ok(())
+    })();
+
+    let inserted = sqlite3_last_insert_rowid(db) != SENTINEL;
+    if !inserted {
+        sqlite3_set_last_insert_rowid(db, last_rowid);
+    }
+    ret?;
+
+    let changes = if sqlite3_total_changes64(db) == total {
+        0
+    } else {
+        sqlite3_changes64(db)
+    };
+    Ok(UpsertResult { changes, inserted })
+}
+
+/// Run the first statement of `sql` with `params` bound to `?1`, `?2`, ...
+/// and collect the rows it returns.
+///
+/// This is meant for `INSERT`, `UPDATE` and `DELETE` with a `RETURNING`
+/// clause, e.g. `INSERT INTO t(name) VALUES (?1) RETURNING id` gives the
+/// generated ids directly. Statements without `RETURNING` return no rows.
+/// `params` is anything implementing [`IntoParams`], like for [`execute`].
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn execute_returning(
+    db: *mut sqlite3,
+    sql: &str,
+    params: impl IntoParams,
+) -> Result<Vec<Row>> {
+    let stmt = Stmt::prepare(db, sql)?;
+    stmt.bind_all(&params.into_params())?;
+
+    let columns = sqlite3_column_count(stmt.as_ptr());
+    let mut rows = Vec::new();
+    while stmt.step()? {
+        rows.push((0..columns).map(|idx| stmt.column_value(idx)).collect());
+    }
+    Ok(rows)
+}
+
+/// Run the first statement of `sql` with `params` bound to `?1`, `?2`, ...
+/// and collect the first column of every row as `T`.
+///
+/// For example `query_column::<f64>(db, "SELECT salary FROM employees", ())`
+/// returns all salaries. Fails with `SQLITE_MISMATCH` if a value cannot be
+/// converted, see [`FromValue`], and with `SQLITE_RANGE` if the statement
+/// returns no columns.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn query_column<T: FromValue>(
+    db: *mut sqlite3,
+    sql: &str,
+    params: impl IntoParams,
+) -> Result<Vec<T>> {
+    let stmt = Stmt::prepare(db, sql)?;
+    stmt.bind_all(&params.into_params())?;
+
+    if sqlite3_column_count(stmt.as_ptr()) == 0 {
+        return Err(SqliteError::new(
+            SQLITE_RANGE,
+            "statement returns no columns",
+        ));
+    }
+    let mut values = Vec::new();
+    while stmt.step()? {
+        values.push(T::from_value(stmt.column_value(0))?);
+    }
+    Ok(values)
+}
+
+/// Count the rows of `from_and_where`, a query fragment following `FROM`,
+/// with `params` bound to `?1`, `?2`, ...
+///
+/// The fragment is wrapped in `SELECT count(*) FROM ...`, e.g.
+/// `count(db, "posts WHERE author = ?1", ("ann",))` gives the number of
+/// pages for a paginated list without fetching its rows. The fragment is
+/// pasted into the SQL as is, so it must not come from untrusted input.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn count(
+    db: *mut sqlite3,
+    from_and_where: &str,
+    params: impl IntoParams,
+) -> Result<i64> {
+    let stmt = Stmt::prepare(db, &format!("SELECT count(*) FROM {from_and_where}"))?;
+    stmt.bind_all(&params.into_params())?;
+    stmt.step()?;
+    Ok(stmt.column_int64(0))
+}
+
+/// Delete the rows of `table` matching `where_clause`, at most `batch_size`
+/// rows per statement, and return the number of rows deleted.
+///
+/// Runs `DELETE FROM table WHERE rowid IN (SELECT rowid FROM table WHERE
+/// where_clause LIMIT batch_size)` until it deletes nothing, and awaits a
+/// `setTimeout(0)` after every batch, so the event loop can run other tasks
+/// and render in between. Outside an explicit transaction every batch
+/// commits on its own, so no single write transaction holds the database
+/// for the whole delete. An empty `where_clause` deletes every row. The
+/// clause is pasted into the SQL as is, so it must not come from untrusted
+/// input. `WITHOUT ROWID` tables are not supported.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection and stay open until the future
+/// completes.
+pub async unsafe fn delete_in_batches(
+    db: *mut sqlite3,
+    table: &str,
+    where_clause: &str,
+    batch_size: usize,
+) -> Result<i64> {
+    if batch_size == 0 {
+        return Err(SqliteError::new(SQLITE_RANGE, "batch size must not be 0"));
+    }
+    let table = quote_identifier(table);
+    let filter = if where_clause.trim().is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {where_clause}")
+    };
+    let stmt = Stmt::prepare(
+        db,
+        &format!(
+            "DELETE FROM {table} WHERE rowid IN \
+             (SELECT rowid FROM {table}{filter} LIMIT {batch_size})"
+        ),
+    )?;
+    let mut deleted = 0;
+    loop {
+        while stmt.step()? {}
+        stmt.reset()?;
+        match sqlite3_changes64(db) {
+            0 => return Ok(deleted),
+            changes => deleted += changes,
+        }
+        yield_to_event_loop().await;
+    }
+}
+
+/// Resolve once the event loop ran the tasks queued before, like awaiting
+/// `new Promise(resolve => setTimeout(resolve, 0))`.
+async fn yield_to_event_loop() {
+    let promise = Promise::new(&mut |resolve, _| {
+        let global = js_sys::global();
+        let set_timeout = Reflect::get(&global, &JsValue::from("setTimeout"))
+            .ok()
+            .and_then(|func| func.dyn_into::<Function>().ok());
+        // Without `setTimeout`, at least let the queued microtasks run.
+        let _ = match set_timeout {
+            Some(set_timeout) => set_timeout.call2(&global, &resolve, &JsValue::from(0)),
+            None => resolve.call0(&JsValue::UNDEFINED),
+        };
+    });
+    // The promise never rejects.
+    let _ = JsFuture::from(promise).await;
+}
+
+/// Run the first statement of `sql` and return its column names and rows,
+/// with every value as text.
+///
+/// This is the shape of the classic `sqlite3_get_table`, built on
+/// prepare and step instead of the deprecated C call. Values are converted
+/// like `sqlite3_column_text`, e.g. `3.5` becomes `"3.5"`, and `NULL` is
+/// `None`.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+#[allow(clippy::type_complexity)]
+pub unsafe fn get_table(
+    db: *mut sqlite3,
+    sql: &str,
+) -> Result<(Vec<String>, Vec<Vec<Option<String>>>)> {
+    let stmt = Stmt::prepare(db, sql)?;
+
+    let columns = sqlite3_column_count(stmt.as_ptr());
+    let names = (0..columns)
+        .map(|idx| {
+            let name = sqlite3_column_name(stmt.as_ptr(), idx);
+            if name.is_null() {
+                String::new()
+            } else {
+                CStr::from_ptr(name).to_string_lossy().into_owned()
+            }
+        })
+        .collect();
+    let mut rows = Vec::new();
+    while stmt.step()? {
+        rows.push((0..columns).map(|idx| stmt.column_text(idx)).collect());
+    }
+    Ok((names, rows))
+}
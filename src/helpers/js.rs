@@ -0,0 +1,117 @@
+use alloc::string::String;
+use core::ffi::{c_int, CStr};
+
+use js_sys::{Array, BigInt, Object, Reflect, Uint8Array};
+use wasm_bindgen::JsValue;
+
+use super::stmt::Stmt;
+use super::Result;
+use crate::{
+    sqlite3, sqlite3_column_count, sqlite3_column_name, sqlite3_column_value, sqlite3_value,
+    sqlite3_value_blob, sqlite3_value_bytes, sqlite3_value_double, sqlite3_value_int64,
+    sqlite3_value_text, sqlite3_value_type, SQLITE_BLOB, SQLITE_FLOAT, SQLITE_INTEGER, SQLITE_TEXT,
+};
+
+/// Largest integer a JS `Number` represents exactly, `Number.MAX_SAFE_INTEGER`.
+pub(crate) const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Convert `value` to JS: `INTEGER` to `number`, or `bigint` if it is
+/// outside the safe integer range, `REAL` to `number`, `TEXT` to `string`,
+/// `BLOB` to `Uint8Array` and `NULL` to `null`.
+pub(crate) unsafe fn value_to_js(value: *mut sqlite3_value) -> JsValue {
+    match sqlite3_value_type(value) {
+        SQLITE_INTEGER => {
+            let value = sqlite3_value_int64(value);
+            if value.unsigned_abs() <= MAX_SAFE_INTEGER {
+                JsValue::from_f64(value as f64)
+            } else {
+                BigInt::from(value).into()
+            }
+        }
+        SQLITE_FLOAT => JsValue::from_f64(sqlite3_value_double(value)),
+        SQLITE_TEXT => {
+            let ptr = sqlite3_value_text(value);
+            if ptr.is_null() {
+                JsValue::NULL
+            } else {
+                let len = sqlite3_value_bytes(value) as usize;
+                JsValue::from_str(&String::from_utf8_lossy(core::slice::from_raw_parts(
+                    ptr, len,
+                )))
+            }
+        }
+        SQLITE_BLOB => {
+            // `sqlite3_value_blob` must be called before `sqlite3_value_bytes`.
+            let ptr = sqlite3_value_blob(value);
+            let len = sqlite3_value_bytes(value);
+            if ptr.is_null() || len <= 0 {
+                Uint8Array::new_with_length(0).into()
+            } else {
+                Uint8Array::from(core::slice::from_raw_parts(ptr.cast(), len as usize)).into()
+            }
+        }
+        _ => JsValue::NULL,
+    }
+}
+
+/// Run the first statement of `sql` and collect its rows into a JS array.
+///
+/// Every row is a plain object keyed by column name. Values are converted as
+/// follows: `INTEGER` to `number`, or `bigint` if it is outside the safe
+/// integer range, `REAL` to `number`, `TEXT` to `string`, `BLOB` to
+/// `Uint8Array` and `NULL` to `null`. When several columns share a name, the
+/// last one wins.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn query_js(db: *mut sqlite3, sql: &str) -> Result<Array> {
+    let stmt = Stmt::prepare(db, sql)?;
+    let names = (0..sqlite3_column_count(stmt.as_ptr()))
+        .map(|idx| {
+            let name = sqlite3_column_name(stmt.as_ptr(), idx);
+            if name.is_null() {
+                JsValue::from_str("")
+            } else {
+                JsValue::from_str(&CStr::from_ptr(name).to_string_lossy())
+            }
+        })
+        .collect::<alloc::vec::Vec<_>>();
+
+    let rows = Array::new();
+    while stmt.step()? {
+        let row = Object::new();
+        for (idx, name) in names.iter().enumerate() {
+            // Defining a property on a plain object cannot fail.
+            let value = sqlite3_column_value(stmt.as_ptr(), idx as c_int);
+            let _ = Reflect::set(&row, name, &value_to_js(value));
+        }
+        rows.push(&row);
+    }
+    Ok(rows)
+}
+
+/// The `wasm_bindgen` export, in a private module so Rust callers can only
+/// reach [`query_js`] with its safety contract.
+mod export {
+    use alloc::string::ToString;
+
+    use js_sys::Array;
+    use wasm_bindgen::prelude::wasm_bindgen;
+    use wasm_bindgen::JsValue;
+
+    use crate::sqlite3;
+
+    /// JS binding of [`query_js`], exported as `queryRows(db, sql)`.
+    ///
+    /// `db` must be the address of an open `sqlite3` connection, as returned
+    /// to JS by the code that opened it, and stay open for the duration of
+    /// the call. Errors are thrown as a JS `Error` with the SQLite message.
+    ///
+    /// [`query_js`]: super::query_js
+    #[wasm_bindgen(js_name = queryRows)]
+    pub fn query_rows(db: usize, sql: &str) -> core::result::Result<Array, JsValue> {
+        unsafe { super::query_js(db as *mut sqlite3, sql) }
+            .map_err(|err| js_sys::Error::new(&err.to_string()).into())
+    }
+}
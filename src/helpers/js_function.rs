@@ -0,0 +1,154 @@
+use alloc::boxed::Box;
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::string::String;
+use core::ffi::{c_int, c_void};
+
+use js_sys::{Array, Function, Uint8Array};
+use wasm_bindgen::{JsCast, JsValue};
+
+use super::js::{value_to_js, MAX_SAFE_INTEGER};
+use super::{last_error, result_text, Result, SqliteError};
+use crate::{
+    sqlite3, sqlite3_context, sqlite3_create_function_v2, sqlite3_result_blob64,
+    sqlite3_result_double, sqlite3_result_error, sqlite3_result_int64, sqlite3_result_null,
+    sqlite3_user_data, sqlite3_value, SQLITE_MISUSE, SQLITE_OK, SQLITE_TRANSIENT, SQLITE_UTF8,
+};
+
+unsafe fn result_error(ctx: *mut sqlite3_context, msg: &str) {
+    sqlite3_result_error(ctx, msg.as_ptr().cast(), msg.len() as c_int);
+}
+
+unsafe fn result_js(ctx: *mut sqlite3_context, value: JsValue) {
+    if value.is_null() || value.is_undefined() {
+        sqlite3_result_null(ctx);
+    } else if let Some(number) = value.as_f64() {
+        let safe = -(MAX_SAFE_INTEGER as f64)..=MAX_SAFE_INTEGER as f64;
+        // `f64::fract` is not in `core`, the round trip through `i64` is exact in range.
+        if safe.contains(&number) && number == (number as i64) as f64 {
+            sqlite3_result_int64(ctx, number as i64);
+        } else {
+            sqlite3_result_double(ctx, number);
+        }
+    } else if value.is_bigint() {
+        match i64::try_from(value) {
+            Ok(value) => sqlite3_result_int64(ctx, value),
+            Err(_) => result_error(ctx, "bigint result is out of the 64-bit integer range"),
+        }
+    } else if let Some(flag) = value.as_bool() {
+        sqlite3_result_int64(ctx, flag as i64);
+    } else if let Some(text) = value.as_string() {
+        result_text(ctx, &text);
+    } else if let Some(bytes) = value.dyn_ref::<Uint8Array>() {
+        let bytes = bytes.to_vec();
+        sqlite3_result_blob64(
+            ctx,
+            bytes.as_ptr().cast(),
+            bytes.len() as u64,
+            SQLITE_TRANSIENT(),
+        );
+    } else {
+        result_error(ctx, "unsupported result type of JS function");
+    }
+}
+
+unsafe extern "C" fn call_js_function(
+    ctx: *mut sqlite3_context,
+    argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    let func = &*sqlite3_user_data(ctx).cast::<Function>();
+    let args = (0..argc as usize)
+        .map(|idx| value_to_js(*argv.add(idx)))
+        .collect::<Array>();
+    match func.apply(&JsValue::NULL, &args) {
+        Ok(value) => result_js(ctx, value),
+        Err(err) => {
+            let msg = match err.dyn_ref::<js_sys::Error>() {
+                Some(err) => String::from(err.message()),
+                None => err.as_string().unwrap_or_else(|| format!("{err:?}")),
+            };
+            result_error(ctx, &msg);
+        }
+    }
+}
+
+unsafe extern "C" fn drop_js_function(func: *mut c_void) {
+    drop(Box::from_raw(func.cast::<Function>()));
+}
+
+/// Register `func` as the SQL function `name` taking `n_arg` arguments.
+///
+/// `n_arg` is `-1` for any number of arguments. The arguments are converted
+/// like the values of `query_js`: `INTEGER` to `number`, or `bigint` if it
+/// is outside the safe integer range, `REAL` to `number`, `TEXT` to
+/// `string`, `BLOB` to `Uint8Array` and `NULL` to `null`. The return value
+/// is converted back: integral numbers and `bigint` to `INTEGER`, other
+/// numbers to `REAL`, booleans to `0` or `1`, `string` to `TEXT`,
+/// `Uint8Array` to `BLOB` and `null` or `undefined` to `NULL`. A thrown
+/// exception fails the statement with its message.
+///
+/// The function is called with `this` set to `null` and kept alive until it
+/// is replaced or the connection is closed. It is not marked
+/// `SQLITE_DETERMINISTIC`.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn create_function(
+    db: *mut sqlite3,
+    name: &str,
+    n_arg: c_int,
+    func: Function,
+) -> Result<()> {
+    let name = CString::new(name)
+        .map_err(|_| SqliteError::new(SQLITE_MISUSE, "function name contains a nul byte"))?;
+    // SQLite calls `drop_js_function` itself if the registration fails.
+    let ret = sqlite3_create_function_v2(
+        db,
+        name.as_ptr(),
+        n_arg,
+        SQLITE_UTF8,
+        Box::into_raw(Box::new(func)).cast(),
+        Some(call_js_function),
+        None,
+        None,
+        Some(drop_js_function),
+    );
+    if ret == SQLITE_OK {
+        Ok(())
+    } else {
+        Err(last_error(db))
+    }
+}
+
+/// The `wasm_bindgen` export, in a private module so Rust callers can only
+/// reach [`create_function`] with its safety contract.
+mod export {
+    use alloc::string::ToString;
+
+    use js_sys::Function;
+    use wasm_bindgen::prelude::wasm_bindgen;
+    use wasm_bindgen::JsValue;
+
+    use crate::sqlite3;
+
+    /// JS binding of [`create_function`], exported as
+    /// `createFunction(db, name, nArg, func)`.
+    ///
+    /// `db` must be the address of an open `sqlite3` connection, as returned
+    /// to JS by the code that opened it, and stay open for the duration of
+    /// the call. Errors are thrown as a JS `Error` with the SQLite message.
+    ///
+    /// [`create_function`]: super::create_function
+    #[wasm_bindgen(js_name = createFunction)]
+    pub fn create_function(
+        db: usize,
+        name: &str,
+        n_arg: i32,
+        func: Function,
+    ) -> core::result::Result<(), JsValue> {
+        unsafe { super::create_function(db as *mut sqlite3, name, n_arg, func) }
+            .map_err(|err| js_sys::Error::new(&err.to_string()).into())
+    }
+}
@@ -0,0 +1,81 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ffi::{c_char, c_int};
+use core::ptr;
+
+use super::{Result, SqliteError};
+use crate::{
+    sqlite3_keyword_check, sqlite3_keyword_count, sqlite3_keyword_name, SQLITE_MISUSE, SQLITE_OK,
+};
+
+/// Longest function name accepted by `sqlite3_create_function_v2`, in bytes.
+const MAX_FUNCTION_NAME: usize = 255;
+
+/// The SQL keywords starting with `prefix`, ignoring ASCII case.
+///
+/// Keywords come from `sqlite3_keyword_name`, are upper case and sorted
+/// alphabetically. An empty prefix returns every keyword.
+pub fn keyword_suggestions(prefix: &str) -> Vec<String> {
+    let mut keywords = Vec::new();
+    for idx in 0..unsafe { sqlite3_keyword_count() } {
+        let mut name: *const c_char = ptr::null();
+        let mut len: c_int = 0;
+        let ret = unsafe { sqlite3_keyword_name(idx, &mut name as *mut _, &mut len as *mut _) };
+        if ret != SQLITE_OK || name.is_null() {
+            continue;
+        }
+        // The names are not NUL-terminated.
+        let name = unsafe { core::slice::from_raw_parts(name.cast::<u8>(), len as usize) };
+        if name.len() >= prefix.len()
+            && name[..prefix.len()].eq_ignore_ascii_case(prefix.as_bytes())
+        {
+            keywords.push(String::from_utf8_lossy(name).into_owned());
+        }
+    }
+    keywords.sort_unstable();
+    keywords
+}
+
+/// Check that `name` can be registered as an SQL function and called
+/// without quoting.
+///
+/// `sqlite3_create_function_v2` only rejects names longer than 255 bytes,
+/// with a bare `SQLITE_MISUSE`, and happily registers names that no
+/// statement can call, such as `select` or `my-func`. This checks the name
+/// up front and fails with `SQLITE_MISUSE` and a message saying what is
+/// wrong: it must be a plain identifier, i.e. letters, digits, `_`, `$` and
+/// non-ASCII characters, not starting with a digit or `$`, and not an SQL
+/// keyword. Some keywords, e.g. `replace`, can still name functions since
+/// SQLite falls back to an identifier where a keyword doesn't fit, but they
+/// are rejected too to stay on the safe side.
+pub fn validate_function_name(name: &str) -> Result<()> {
+    let invalid = |reason: &str| {
+        Err(SqliteError::new(
+            SQLITE_MISUSE,
+            format!("invalid function name `{name}`: {reason}"),
+        ))
+    };
+    let Some(first) = name.chars().next() else {
+        return invalid("the name is empty");
+    };
+    if name.len() > MAX_FUNCTION_NAME {
+        return invalid("the name is longer than 255 bytes");
+    }
+    if first.is_ascii_digit() || first == '$' {
+        return invalid("an identifier can't start with a digit or `$`");
+    }
+    if let Some(ch) = name
+        .chars()
+        .find(|&ch| ch.is_ascii() && !(ch.is_ascii_alphanumeric() || ch == '_' || ch == '$'))
+    {
+        return invalid(&format!(
+            "`{}` is not allowed in an identifier",
+            ch.escape_default()
+        ));
+    }
+    if unsafe { sqlite3_keyword_check(name.as_ptr().cast(), name.len() as c_int) } != 0 {
+        return invalid("the name is an SQL keyword");
+    }
+    Ok(())
+}
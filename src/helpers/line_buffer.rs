@@ -0,0 +1,82 @@
+use alloc::ffi::CString;
+use alloc::string::String;
+
+use crate::sqlite3_complete;
+
+/// Whether `sql` ends with a complete statement, see `sqlite3_complete`.
+fn is_complete_sql(sql: &str) -> bool {
+    // Text with a NUL byte can't be passed to SQLite, so it never completes.
+    CString::new(sql).is_ok_and(|sql| unsafe { sqlite3_complete(sql.as_ptr()) } != 0)
+}
+
+/// Accumulates input lines of an interactive console until they form
+/// complete SQL statements.
+///
+/// Completeness is decided by `sqlite3_complete`, so a `;` inside a string
+/// literal, a comment or a `CREATE TRIGGER` body does not end a statement.
+///
+/// ```rust
+/// use sqlite_wasm_rs::helpers::SqlLineBuffer;
+///
+/// let mut buffer = SqlLineBuffer::new();
+/// buffer.push_line("SELECT 'a;");
+/// assert!(!buffer.is_complete());
+/// buffer.push_line("b';");
+/// assert_eq!(
+///     Some("SELECT 'a;\nb';".to_string()),
+///     buffer.take_complete_statements()
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SqlLineBuffer {
+    buffer: String,
+}
+
+impl SqlLineBuffer {
+    /// Create an empty buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a line of input, a newline is added after it.
+    pub fn push_line(&mut self, line: &str) {
+        self.buffer.push_str(line);
+        self.buffer.push('\n');
+    }
+
+    /// Whether the buffer holds only whitespace.
+    pub fn is_empty(&self) -> bool {
+        self.buffer.trim().is_empty()
+    }
+
+    /// Whether the buffer ends with a complete statement.
+    ///
+    /// Text after the last `;` other than whitespace and comments makes the
+    /// buffer incomplete.
+    pub fn is_complete(&self) -> bool {
+        is_complete_sql(&self.buffer)
+    }
+
+    /// Remove and return the longest prefix of the buffer made of complete
+    /// statements, without the trailing newline.
+    ///
+    /// Any incomplete statement after it stays in the buffer for the next
+    /// lines. Returns `None` if no statement is complete yet.
+    pub fn take_complete_statements(&mut self) -> Option<String> {
+        let end = self
+            .buffer
+            .char_indices()
+            .rev()
+            .filter(|&(_, c)| c == ';')
+            .map(|(idx, _)| idx + 1)
+            .find(|&end| is_complete_sql(&self.buffer[..end]))?;
+        let rest = self.buffer.split_off(end);
+        let statements = core::mem::replace(&mut self.buffer, String::from(rest.trim_start()));
+        Some(statements)
+    }
+
+    /// Discard the buffered input.
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+    }
+}
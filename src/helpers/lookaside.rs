@@ -0,0 +1,71 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use core::ffi::{c_int, c_void};
+
+use super::{Result, SqliteError};
+use crate::{sqlite3, sqlite3_db_config, SQLITE_DBCONFIG_LOOKASIDE, SQLITE_OK, SQLITE_RANGE};
+
+/// The memory handed to a connection by [`configure_lookaside`].
+///
+/// SQLite keeps using the buffer until the connection is closed, including
+/// during `sqlite3_close` itself, so the buffer must be dropped only after
+/// the connection is closed.
+#[derive(Debug)]
+pub struct LookasideBuffer {
+    // `u64` keeps the buffer 8-byte aligned, as SQLite requires.
+    _buf: Box<[u64]>,
+}
+
+/// Give the connection `slot_count` lookaside slots of `slot_size` bytes,
+/// backed by a buffer allocated here.
+///
+/// Lookaside is a per-connection allocator for small, short-lived objects.
+/// Sizing it explicitly bounds how much memory a connection sets aside,
+/// which matters in memory-constrained wasm builds. `slot_size` is rounded
+/// down to a multiple of 8 by SQLite, and lookaside is disabled if it is
+/// too small for a slot or `slot_count` is 0.
+///
+/// Fails with `SQLITE_BUSY` while lookaside memory is in use, e.g. by a
+/// prepared statement, so configure it right after opening the connection.
+///
+/// ```rust
+/// use sqlite_wasm_rs::{self as ffi, helpers::{configure_lookaside, Result}};
+///
+/// unsafe fn run(db: *mut ffi::sqlite3) -> Result<()> {
+///     let lookaside = configure_lookaside(db, 256, 64)?;
+///     // ... use `db` ...
+///     ffi::sqlite3_close(db);
+///     drop(lookaside);
+///     Ok(())
+/// }
+/// ```
+///
+/// # Safety
+///
+/// `db` must be a valid database connection, and the returned buffer must
+/// be kept alive until `db` is closed or lookaside is configured again.
+pub unsafe fn configure_lookaside(
+    db: *mut sqlite3,
+    slot_size: usize,
+    slot_count: usize,
+) -> Result<LookasideBuffer> {
+    let (Ok(size), Ok(count), Some(len)) = (
+        c_int::try_from(slot_size),
+        c_int::try_from(slot_count),
+        slot_size.checked_mul(slot_count),
+    ) else {
+        return Err(SqliteError::from_code(SQLITE_RANGE));
+    };
+    let mut buf = vec![0u64; len.div_ceil(8)].into_boxed_slice();
+    let ret = sqlite3_db_config(
+        db,
+        SQLITE_DBCONFIG_LOOKASIDE,
+        buf.as_mut_ptr().cast::<c_void>(),
+        size,
+        count,
+    );
+    if ret != SQLITE_OK {
+        return Err(SqliteError::from_code(ret));
+    }
+    Ok(LookasideBuffer { _buf: buf })
+}
@@ -0,0 +1,104 @@
+//! Helpers built on top of the raw bindings for patterns that otherwise
+//! require a lot of boilerplate.
+//!
+//! The helpers work directly with `*mut sqlite3` and `*mut sqlite3_stmt`
+//! pointers, so they can be mixed freely with the raw C API.
+
+mod analyze;
+mod attach;
+mod auto_extension;
+#[cfg(feature = "sqlite3mc")]
+mod cipher;
+mod config;
+mod copy;
+mod csv;
+mod db_config;
+mod dump;
+mod error;
+mod execute;
+#[cfg(feature = "js-api")]
+mod js;
+#[cfg(feature = "js-functions")]
+mod js_function;
+mod keyword;
+mod line_buffer;
+mod lookaside;
+mod open;
+mod pragma;
+mod progress;
+mod quote;
+mod recover;
+mod result;
+mod savepoint;
+mod scanstatus;
+mod schema;
+mod seeded_random;
+mod serialize;
+mod stmt;
+mod str_builder;
+#[cfg(feature = "test-control")]
+mod test_control;
+mod timed;
+mod uri;
+mod value;
+mod vfs;
+mod vtab;
+
+pub use self::analyze::{analyze, analyze_stats};
+pub use self::attach::{attach_memory, Attached};
+pub use self::auto_extension::{cancel_auto_extension, register_auto_extension, ExtensionInit};
+#[cfg(feature = "sqlite3mc")]
+pub use self::cipher::{key, rekey};
+pub use self::config::{config_lookaside, config_memstatus};
+pub use self::copy::{copy_table, export_tables, migrate_db};
+pub use self::csv::export_csv;
+pub use self::db_config::set_trusted_schema;
+pub use self::dump::{dump_all, dump_schema};
+pub use self::error::{last_error, Result, SqliteError};
+pub use self::execute::{
+    count, delete_in_batches, execute, execute_returning, get_table, query_column, upsert,
+    Execution, UpsertResult,
+};
+#[cfg(feature = "js-api")]
+pub use self::js::query_js;
+#[cfg(feature = "js-functions")]
+pub use self::js_function::create_function;
+pub use self::keyword::{keyword_suggestions, validate_function_name};
+pub use self::line_buffer::SqlLineBuffer;
+pub use self::lookaside::{configure_lookaside, LookasideBuffer};
+pub use self::open::{
+    close_checked, open_shared_memory, open_temp, open_with_busy_timeout, open_with_foreign_keys,
+};
+pub use self::pragma::{
+    cache_size, db_tags, integrity_check_limited, journal_mode, set_application_id, set_cache_size,
+    set_journal_mode, set_user_version, wal_checkpoint, CheckpointMode, WalCheckpoint,
+};
+pub use self::progress::with_progress;
+pub use self::quote::quote_identifier;
+pub use self::recover::recover;
+pub use self::result::{result_blob, result_static_text, result_text, result_value};
+pub use self::savepoint::Savepoint;
+pub use self::scanstatus::{scan_status, ScanStatus};
+pub use self::schema::{
+    db_name, ensure_index, list_tables, list_triggers, list_views, table_indexes, IndexInfo,
+    TriggerInfo,
+};
+pub use self::seeded_random::create_seeded_random;
+pub use self::serialize::{
+    clone_db, is_sqlite_database, serialize_to_vec, serialized_size, vacuum_into_vec,
+};
+pub use self::stmt::{
+    bind_option, bind_row, column_as_bool, column_as_datetime, prepared_statements, rebind,
+    reset_all_statements, statement_columns, statement_returns_rows, step_row, ColumnSpec,
+    StatementIter,
+};
+pub use self::str_builder::StrBuilder;
+#[cfg(feature = "test-control")]
+pub use self::test_control::test_control;
+pub use self::timed::timed_query;
+pub use self::uri::{uri_key, uri_keys};
+pub use self::value::{
+    value_as_i64_or_text, value_bytes, value_len, FromValue, IntoParams, Row, Value,
+};
+pub use self::vfs::{default_vfs_name, truncate_database};
+pub use self::vtab::{vtab_config, VtabConfig};
@@ -0,0 +1,162 @@
+use core::ffi::{c_int, CStr};
+use core::ptr;
+
+use super::stmt::{exec, Stmt};
+use super::{last_error, prepared_statements, Result, SqliteError};
+use crate::{
+    sqlite3, sqlite3_busy_timeout, sqlite3_close, sqlite3_open_v2, SQLITE_ERROR, SQLITE_OK,
+    SQLITE_OPEN_CREATE, SQLITE_OPEN_READWRITE,
+};
+
+/// Open a database with `sqlite3_open_v2`, closing the handle again on failure.
+pub(super) unsafe fn open(
+    filename: &CStr,
+    flags: c_int,
+    vfs: Option<&CStr>,
+) -> Result<*mut sqlite3> {
+    let mut db: *mut sqlite3 = ptr::null_mut();
+    let ret = sqlite3_open_v2(
+        filename.as_ptr(),
+        &mut db as *mut _,
+        flags,
+        vfs.map_or(ptr::null(), CStr::as_ptr),
+    );
+    if ret != SQLITE_OK {
+        // A handle is allocated even on failure, unless memory ran out.
+        let err = if db.is_null() {
+            SqliteError::from_code(ret)
+        } else {
+            last_error(db)
+        };
+        sqlite3_close(db);
+        return Err(err);
+    }
+    Ok(db)
+}
+
+/// Open a database with `sqlite3_busy_timeout(db, ms)` already applied.
+///
+/// This only sets the `busy_timeout` of the connection, it does not make
+/// SQLite wait on anything by itself. The timeout is used when the VFS
+/// reports a held lock, and the VFSes of this crate and `sqlite-wasm-vfs`,
+/// `memvfs` included, implement `xLock` as a no-op and never do, so with
+/// them the setting has no effect. It is meant for a custom VFS with real
+/// file locking.
+///
+/// # Safety
+///
+/// The returned connection must be closed with `sqlite3_close`.
+pub unsafe fn open_with_busy_timeout(
+    filename: &CStr,
+    flags: c_int,
+    vfs: Option<&CStr>,
+    ms: c_int,
+) -> Result<*mut sqlite3> {
+    let db = open(filename, flags, vfs)?;
+    let ret = sqlite3_busy_timeout(db, ms);
+    if ret != SQLITE_OK {
+        let err = last_error(db);
+        sqlite3_close(db);
+        return Err(err);
+    }
+    Ok(db)
+}
+
+/// Open a database with foreign key constraints enforced.
+///
+/// Foreign keys are off by default on every new connection. This runs
+/// `PRAGMA foreign_keys=ON` right after opening and reads the pragma back,
+/// failing with `SQLITE_ERROR` if it did not take effect, so constraint
+/// violations can't slip through silently.
+///
+/// # Safety
+///
+/// The returned connection must be closed with `sqlite3_close`.
+pub unsafe fn open_with_foreign_keys(
+    filename: &CStr,
+    flags: c_int,
+    vfs: Option<&CStr>,
+) -> Result<*mut sqlite3> {
+    let db = open(filename, flags, vfs)?;
+    let enabled: Result<bool> = (|| {
+        exec(db, "PRAGMA foreign_keys=ON")?;
+        let stmt = Stmt::prepare(db, "PRAGMA foreign_keys")?;
+        Ok(stmt.step()? && stmt.column_int64(0) == 1)
+    })();
+    match enabled {
+        Ok(true) => Ok(db),
+        Ok(false) => {
+            sqlite3_close(db);
+            Err(SqliteError::new(
+                SQLITE_ERROR,
+                "foreign keys could not be enabled",
+            ))
+        }
+        Err(err) => {
+            sqlite3_close(db);
+            Err(err)
+        }
+    }
+}
+
+/// Open the in-memory database `name`, shared by every connection opening it.
+///
+/// SQLite's own `:memory:` databases can only be shared through shared
+/// cache, which is omitted from this build. Instead the database is a file of
+/// the `memvfs` VFS, which keeps its files in memory by name, so all
+/// connections opening the same `name` see the same data. The data outlives
+/// the connections and is only freed with [`MemVfsUtil::delete_db`].
+///
+/// [`MemVfsUtil::delete_db`]: crate::MemVfsUtil::delete_db
+///
+/// # Safety
+///
+/// The returned connection must be closed with `sqlite3_close`.
+pub unsafe fn open_shared_memory(name: &CStr) -> Result<*mut sqlite3> {
+    open(
+        name,
+        SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+        Some(c"memvfs"),
+    )
+}
+
+/// Open a private, unnamed temporary database.
+///
+/// The filename is empty, which SQLite treats as a temporary database of
+/// this connection only. Built with `SQLITE_TEMP_STORE=2`, it is kept in
+/// memory like the other temporary files and never reaches a VFS, so
+/// nothing is left behind once the connection is closed.
+///
+/// # Safety
+///
+/// The returned connection must be closed with `sqlite3_close`.
+pub unsafe fn open_temp() -> Result<*mut sqlite3> {
+    open(c"", SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE, None)
+}
+
+/// Close `db`, or report how many prepared statements still keep it open.
+///
+/// `sqlite3_close` refuses to close a connection with unfinalized statements
+/// and only returns `SQLITE_BUSY`. This counts them with
+/// [`prepared_statements`] first and returns the count as the error, leaving
+/// the connection open so the statements can be finalized and the close
+/// retried.
+///
+/// The `Err` payload is the number of prepared statements that are still
+/// open. An unfinished `sqlite3_backup` also keeps the connection open
+/// without any statement, which is reported as `Err(0)`.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection, it must not be used after
+/// `Ok(())` is returned.
+pub unsafe fn close_checked(db: *mut sqlite3) -> core::result::Result<(), usize> {
+    let open = prepared_statements(db).len();
+    if open > 0 {
+        return Err(open);
+    }
+    match sqlite3_close(db) {
+        SQLITE_OK => Ok(()),
+        _ => Err(0),
+    }
+}
@@ -0,0 +1,195 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::stmt::{exec, Stmt};
+use super::{Result, SqliteError};
+use crate::{sqlite3, SQLITE_MISUSE, SQLITE_RANGE};
+
+const JOURNAL_MODES: [&str; 6] = ["DELETE", "TRUNCATE", "PERSIST", "MEMORY", "WAL", "OFF"];
+
+/// Run a pragma that returns a single text value.
+unsafe fn pragma_text(db: *mut sqlite3, sql: &str) -> Result<String> {
+    let stmt = Stmt::prepare(db, sql)?;
+    if !stmt.step()? {
+        return Err(SqliteError::new(
+            SQLITE_MISUSE,
+            format!("no result from `{sql}`"),
+        ));
+    }
+    Ok(stmt.column_text(0).unwrap_or_default())
+}
+
+/// Run a pragma that returns a single integer value.
+unsafe fn pragma_int(db: *mut sqlite3, sql: &str) -> Result<i64> {
+    let stmt = Stmt::prepare(db, sql)?;
+    if !stmt.step()? {
+        return Err(SqliteError::new(
+            SQLITE_MISUSE,
+            format!("no result from `{sql}`"),
+        ));
+    }
+    Ok(stmt.column_int64(0))
+}
+
+/// The journal mode of the main database, as reported by `PRAGMA journal_mode`.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn journal_mode(db: *mut sqlite3) -> Result<String> {
+    pragma_text(db, "PRAGMA journal_mode")
+}
+
+/// Set the journal mode of the main database and return the resulting mode.
+///
+/// `mode` is one of `DELETE`, `TRUNCATE`, `PERSIST`, `MEMORY`, `WAL` or `OFF`,
+/// case-insensitive. SQLite silently keeps the current mode if the change is
+/// not possible, e.g. in-memory databases only support `MEMORY` and `OFF`,
+/// so compare the returned mode to check whether the change took effect.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn set_journal_mode(db: *mut sqlite3, mode: &str) -> Result<String> {
+    if !JOURNAL_MODES.iter().any(|m| m.eq_ignore_ascii_case(mode)) {
+        return Err(SqliteError::new(
+            SQLITE_MISUSE,
+            format!("invalid journal mode: {mode}"),
+        ));
+    }
+    pragma_text(db, &format!("PRAGMA journal_mode = {mode}"))
+}
+
+/// The page cache size of the main database, as reported by `PRAGMA cache_size`.
+///
+/// A positive value is a number of pages, a negative value `-N` limits the
+/// cache to about `N` KiB regardless of the page size.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn cache_size(db: *mut sqlite3) -> Result<i64> {
+    pragma_int(db, "PRAGMA cache_size")
+}
+
+/// Set the page cache size of the main database.
+///
+/// Follows the convention of `PRAGMA cache_size`: a positive `kib_or_pages`
+/// is a number of pages, a negative one `-N` is about `N` KiB. The value is
+/// stored as given, so [`cache_size`] returns it with the same sign.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn set_cache_size(db: *mut sqlite3, kib_or_pages: i64) -> Result<()> {
+    exec(db, &format!("PRAGMA cache_size = {kib_or_pages}"))
+}
+
+/// Checkpoint modes of [`wal_checkpoint`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckpointMode {
+    /// Checkpoint as many frames as possible without waiting for readers or writers.
+    Passive,
+    /// Wait for writers, then checkpoint all frames.
+    Full,
+    /// Like `Full`, then wait for readers so the next writer restarts the log.
+    Restart,
+    /// Like `Restart`, then truncate the log file to zero bytes.
+    Truncate,
+}
+
+/// Result of [`wal_checkpoint`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WalCheckpoint {
+    /// The checkpoint could not complete because of a lock held by another connection.
+    pub busy: bool,
+    /// Number of frames in the log, `-1` if the database is not in WAL mode.
+    pub log_frames: i64,
+    /// Number of frames checkpointed, `-1` if the database is not in WAL mode.
+    pub checkpointed_frames: i64,
+}
+
+/// Run `PRAGMA wal_checkpoint` on the main database.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn wal_checkpoint(db: *mut sqlite3, mode: CheckpointMode) -> Result<WalCheckpoint> {
+    let mode = match mode {
+        CheckpointMode::Passive => "PASSIVE",
+        CheckpointMode::Full => "FULL",
+        CheckpointMode::Restart => "RESTART",
+        CheckpointMode::Truncate => "TRUNCATE",
+    };
+    let stmt = Stmt::prepare(db, &format!("PRAGMA wal_checkpoint({mode})"))?;
+    if !stmt.step()? {
+        return Err(SqliteError::new(
+            SQLITE_MISUSE,
+            "no result from `PRAGMA wal_checkpoint`",
+        ));
+    }
+    Ok(WalCheckpoint {
+        busy: stmt.column_int64(0) != 0,
+        log_frames: stmt.column_int64(1),
+        checkpointed_frames: stmt.column_int64(2),
+    })
+}
+
+/// Run `PRAGMA integrity_check(N)` and return at most `max_errors` problems.
+///
+/// The check stops once `max_errors` problems are found, which bounds the
+/// work on large, badly damaged databases. An empty result means the check
+/// reported `ok`. Fails with `SQLITE_RANGE` if `max_errors` is 0, which
+/// SQLite would otherwise treat as the default limit of 100.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn integrity_check_limited(db: *mut sqlite3, max_errors: u32) -> Result<Vec<String>> {
+    if max_errors == 0 {
+        return Err(SqliteError::from_code(SQLITE_RANGE));
+    }
+    let stmt = Stmt::prepare(db, &format!("PRAGMA integrity_check({max_errors})"))?;
+    let mut errors = Vec::new();
+    while stmt.step()? {
+        errors.push(stmt.column_text(0).unwrap_or_default());
+    }
+    if errors == ["ok"] {
+        errors.clear();
+    }
+    Ok(errors)
+}
+
+/// The `application_id` and `user_version` of the main database.
+///
+/// Both are 32-bit integers in the database header, 0 in a new database.
+/// SQLite never interprets them: applications set `application_id` to mark
+/// their file format and `user_version` to track their schema version.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn db_tags(db: *mut sqlite3) -> Result<(i32, i32)> {
+    let application_id = pragma_int(db, "PRAGMA application_id")?;
+    let user_version = pragma_int(db, "PRAGMA user_version")?;
+    Ok((application_id as i32, user_version as i32))
+}
+
+/// Set the `application_id` of the main database, see [`db_tags`].
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn set_application_id(db: *mut sqlite3, application_id: i32) -> Result<()> {
+    exec(db, &format!("PRAGMA application_id = {application_id}"))
+}
+
+/// Set the `user_version` of the main database, see [`db_tags`].
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn set_user_version(db: *mut sqlite3, user_version: i32) -> Result<()> {
+    exec(db, &format!("PRAGMA user_version = {user_version}"))
+}
@@ -0,0 +1,54 @@
+use core::ffi::{c_int, c_void};
+use core::ptr;
+
+use crate::{sqlite3, sqlite3_progress_handler};
+
+struct Progress<P> {
+    count: u64,
+    callback: P,
+}
+
+unsafe extern "C" fn on_progress<P: FnMut(u64)>(data: *mut c_void) -> c_int {
+    let progress = &mut *data.cast::<Progress<P>>();
+    progress.count += 1;
+    (progress.callback)(progress.count);
+    0
+}
+
+/// Removes the progress handler, also if `f` panics.
+struct HandlerGuard(*mut sqlite3);
+
+impl Drop for HandlerGuard {
+    fn drop(&mut self) {
+        unsafe { sqlite3_progress_handler(self.0, 0, None, ptr::null_mut()) };
+    }
+}
+
+/// Run `f` with a progress handler that calls `callback` about every
+/// `every_n_ops` virtual machine instructions, and remove it afterwards.
+///
+/// `callback` gets the number of times it has been called so far, starting
+/// at 1, e.g. to update a progress indicator during a long bulk operation.
+/// It can't interrupt the statement. A connection has a single progress
+/// handler, so this replaces one set before, and no handler is left once
+/// `f` returns. `every_n_ops` below 1 disables the handler.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection, and `f` and `callback` must
+/// not set another progress handler on it.
+pub unsafe fn with_progress<P, F, T>(db: *mut sqlite3, every_n_ops: c_int, callback: P, f: F) -> T
+where
+    P: FnMut(u64),
+    F: FnOnce() -> T,
+{
+    let mut progress = Progress { count: 0, callback };
+    sqlite3_progress_handler(
+        db,
+        every_n_ops,
+        Some(on_progress::<P>),
+        (&mut progress as *mut Progress<P>).cast(),
+    );
+    let _guard = HandlerGuard(db);
+    f()
+}
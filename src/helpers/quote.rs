@@ -0,0 +1,18 @@
+use alloc::string::String;
+
+/// Quote `name` as an SQL identifier, e.g. `my "table"` becomes `"my ""table"""`.
+///
+/// Use this when a table or column name has to be spliced into SQL text,
+/// since identifiers cannot be bound as parameters.
+pub fn quote_identifier(name: &str) -> String {
+    let mut quoted = String::with_capacity(name.len() + 2);
+    quoted.push('"');
+    for c in name.chars() {
+        if c == '"' {
+            quoted.push('"');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
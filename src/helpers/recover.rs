@@ -0,0 +1,92 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::copy::copy_rows;
+use super::open::open;
+use super::serialize_to_vec;
+use super::stmt::{exec, Stmt};
+use super::Result;
+use crate::{sqlite3, sqlite3_close, SQLITE_OPEN_CREATE, SQLITE_OPEN_READWRITE};
+
+/// An entry of `sqlite_schema`, with shadow and virtual tables marked by type.
+struct SchemaEntry {
+    kind: String,
+    name: String,
+    sql: String,
+}
+
+unsafe fn read_schema(db: *mut sqlite3) -> Result<Vec<SchemaEntry>> {
+    let stmt = Stmt::prepare(
+        db,
+        "SELECT coalesce(l.type, s.type), s.name, s.sql FROM sqlite_schema s \
+         LEFT JOIN pragma_table_list l ON l.schema = 'main' AND l.name = s.name \
+         WHERE s.sql IS NOT NULL ORDER BY s.rowid",
+    )?;
+    let mut entries = Vec::new();
+    while stmt.step()? {
+        entries.push(SchemaEntry {
+            kind: stmt.column_text(0).unwrap_or_default(),
+            name: stmt.column_text(1).unwrap_or_default(),
+            sql: stmt.column_text(2).unwrap_or_default(),
+        });
+    }
+    Ok(entries)
+}
+
+unsafe fn recover_into(src: *mut sqlite3, dst: *mut sqlite3) -> Result<()> {
+    let schema = read_schema(src)?;
+
+    exec(dst, "BEGIN")?;
+    for entry in schema.iter().filter(|entry| entry.kind == "table") {
+        if entry.name.starts_with("sqlite_") {
+            continue;
+        }
+        // Rows read before a damaged page are kept, the rest of the table
+        // is lost.
+        if exec(dst, &entry.sql).is_ok() {
+            let _ = copy_rows(src, dst, &entry.name);
+        }
+    }
+    if schema.iter().any(|entry| entry.name == "sqlite_sequence") {
+        // Copying the rows already filled in the counters, replace them with
+        // the original ones.
+        if exec(dst, "DELETE FROM sqlite_sequence").is_ok() {
+            let _ = copy_rows(src, dst, "sqlite_sequence");
+        }
+    }
+    // Indexes are rebuilt from the recovered rows. A UNIQUE index fails if
+    // the damage left duplicates behind, and is dropped then.
+    for entry in schema
+        .iter()
+        .filter(|entry| matches!(entry.kind.as_str(), "index" | "view" | "trigger"))
+    {
+        let _ = exec(dst, &entry.sql);
+    }
+    exec(dst, "COMMIT")
+}
+
+/// Salvage what can still be read from a damaged main database of `db`.
+///
+/// A best-effort alternative to the `sqlite3_recover` extension, which is
+/// not part of this build: the schema is read from `sqlite_schema`, then the
+/// rows of every table are copied into a new in-memory database until the
+/// first read error, skipping tables that can't be read at all. Indexes,
+/// views and triggers are created afterwards. Virtual tables and their
+/// shadow tables are skipped. Returns the new database serialized, which can
+/// be written back to storage or loaded with `sqlite3_deserialize`.
+///
+/// Fails if the schema itself can't be read.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn recover(db: *mut sqlite3) -> Result<Vec<u8>> {
+    let dst = open(
+        c":memory:",
+        SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+        None,
+    )?;
+    let recovered = recover_into(db, dst).and_then(|()| serialize_to_vec(dst, c"main"));
+    sqlite3_close(dst);
+    recovered
+}
@@ -0,0 +1,77 @@
+use core::ffi::c_uchar;
+
+use crate::{
+    sqlite3_context, sqlite3_result_blob64, sqlite3_result_text64, sqlite3_result_value,
+    sqlite3_value, SQLITE_STATIC, SQLITE_TRANSIENT, SQLITE_UTF8,
+};
+
+/// Set the result of an SQL function to a copy of `text`.
+///
+/// Uses `sqlite3_result_text64`, so the length is not limited to `c_int`.
+/// Texts longer than `SQLITE_LIMIT_LENGTH` make the function fail with
+/// `SQLITE_TOOBIG`. Taking `&str` guarantees the text is valid UTF-8, which
+/// is the only encoding supported here.
+///
+/// # Safety
+///
+/// `ctx` must be the context passed to the function implementation.
+pub unsafe fn result_text(ctx: *mut sqlite3_context, text: &str) {
+    sqlite3_result_text64(
+        ctx,
+        text.as_ptr().cast(),
+        text.len() as u64,
+        SQLITE_TRANSIENT(),
+        SQLITE_UTF8 as c_uchar,
+    );
+}
+
+/// Set the result of an SQL function to `text` without copying it.
+///
+/// Like [`result_text`], but passes `SQLITE_STATIC`: the `'static` lifetime
+/// guarantees the text outlives every use of the result, so SQLite can
+/// point at it instead of allocating a copy on every call and freeing it
+/// again. Meant for functions returning string literals.
+///
+/// # Safety
+///
+/// `ctx` must be the context passed to the function implementation.
+pub unsafe fn result_static_text(ctx: *mut sqlite3_context, text: &'static str) {
+    sqlite3_result_text64(
+        ctx,
+        text.as_ptr().cast(),
+        text.len() as u64,
+        SQLITE_STATIC(),
+        SQLITE_UTF8 as c_uchar,
+    );
+}
+
+/// Set the result of an SQL function to a copy of `blob`.
+///
+/// Uses `sqlite3_result_blob64`, so blobs of more than `c_int::MAX` bytes
+/// are not truncated. Blobs longer than `SQLITE_LIMIT_LENGTH` make the
+/// function fail with `SQLITE_TOOBIG`.
+///
+/// # Safety
+///
+/// `ctx` must be the context passed to the function implementation.
+pub unsafe fn result_blob(ctx: *mut sqlite3_context, blob: &[u8]) {
+    sqlite3_result_blob64(
+        ctx,
+        blob.as_ptr().cast(),
+        blob.len() as u64,
+        SQLITE_TRANSIENT(),
+    );
+}
+
+/// Set the result of an SQL function to a copy of `value`.
+///
+/// Wraps `sqlite3_result_value`, the type and content of `value` are kept
+/// as is, so a function can pass one of its arguments through unchanged.
+///
+/// # Safety
+///
+/// `ctx` must be the context passed to the function implementation and
+/// `value` a valid value, e.g. one of its arguments.
+pub unsafe fn result_value(ctx: *mut sqlite3_context, value: *mut sqlite3_value) {
+    sqlite3_result_value(ctx, value);
+}
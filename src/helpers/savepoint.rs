@@ -0,0 +1,95 @@
+use alloc::format;
+use alloc::string::String;
+use core::ptr;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use super::stmt::exec;
+use super::Result;
+use crate::{sqlite3, sqlite3_get_autocommit};
+
+/// Numbers savepoint names, so nested guards never share a name.
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A savepoint, rolled back when the guard is dropped unless released.
+///
+/// Unlike `BEGIN`, savepoints nest, so a function can open one whether or
+/// not its caller already started a transaction. The outermost savepoint,
+/// opened while the connection is in autocommit mode, starts a transaction
+/// and releasing it commits. Releasing a nested one merges its changes into
+/// the enclosing transaction, rolling it back discards only its changes.
+///
+/// ```rust
+/// use sqlite_wasm_rs::{self as ffi, helpers::{Result, Savepoint}};
+///
+/// unsafe fn transfer(db: *mut ffi::sqlite3) -> Result<()> {
+///     let savepoint = Savepoint::new(db)?;
+///     // ... statements that must apply together ...
+///     savepoint.release()
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Savepoint {
+    db: *mut sqlite3,
+    name: String,
+    outermost: bool,
+}
+
+impl Savepoint {
+    /// Open a new savepoint with a unique name.
+    ///
+    /// # Safety
+    ///
+    /// `db` must be a valid database connection that outlives the guard.
+    pub unsafe fn new(db: *mut sqlite3) -> Result<Self> {
+        let outermost = sqlite3_get_autocommit(db) != 0;
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let name = if outermost {
+            format!("sp_outer_{id}")
+        } else {
+            format!("sp_nested_{id}")
+        };
+        exec(db, &format!("SAVEPOINT {name}"))?;
+        Ok(Self {
+            db,
+            name,
+            outermost,
+        })
+    }
+
+    /// The name of the savepoint.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the savepoint started the transaction, so releasing it commits.
+    pub fn is_outermost(&self) -> bool {
+        self.outermost
+    }
+
+    /// Keep the changes made since the savepoint, with `RELEASE`.
+    pub fn release(mut self) -> Result<()> {
+        let ret = unsafe { exec(self.db, &format!("RELEASE {}", self.name)) };
+        self.db = ptr::null_mut();
+        ret
+    }
+
+    /// Discard the changes made since the savepoint, with `ROLLBACK TO`
+    /// followed by `RELEASE`.
+    pub fn rollback(mut self) -> Result<()> {
+        let ret = unsafe { self.run_rollback() };
+        self.db = ptr::null_mut();
+        ret
+    }
+
+    unsafe fn run_rollback(&self) -> Result<()> {
+        exec(self.db, &format!("ROLLBACK TO {0}; RELEASE {0}", self.name))
+    }
+}
+
+impl Drop for Savepoint {
+    fn drop(&mut self) {
+        if !self.db.is_null() {
+            let _ = unsafe { self.run_rollback() };
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ffi::{c_char, c_int, c_void, CStr};
+use core::ptr;
+
+use crate::{
+    sqlite3_int64, sqlite3_stmt, sqlite3_stmt_scanstatus_v2, SQLITE_SCANSTAT_EST,
+    SQLITE_SCANSTAT_EXPLAIN, SQLITE_SCANSTAT_NAME, SQLITE_SCANSTAT_NLOOP, SQLITE_SCANSTAT_NVISIT,
+    SQLITE_SCANSTAT_PARENTID, SQLITE_SCANSTAT_SELECTID,
+};
+
+/// Profiling counters of one loop of a statement, see [`scan_status`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScanStatus {
+    /// Number of times the loop was run.
+    pub loops: i64,
+    /// Number of rows visited by the loop, over all runs.
+    pub visits: i64,
+    /// Rows per run estimated by the query planner.
+    pub estimated_rows: f64,
+    /// Name of the table or index scanned by the loop.
+    pub name: Option<String>,
+    /// The `EXPLAIN QUERY PLAN` line of the loop.
+    pub explain: Option<String>,
+    /// Id of the `EXPLAIN QUERY PLAN` node of the loop.
+    pub select_id: c_int,
+    /// Id of the parent `EXPLAIN QUERY PLAN` node.
+    pub parent_id: c_int,
+}
+
+unsafe fn scan_int64(stmt: *mut sqlite3_stmt, idx: c_int, op: c_int) -> Option<i64> {
+    let mut out: sqlite3_int64 = 0;
+    let ret = sqlite3_stmt_scanstatus_v2(stmt, idx, op, 0, (&mut out as *mut sqlite3_int64).cast());
+    (ret == 0).then_some(out)
+}
+
+unsafe fn scan_int(stmt: *mut sqlite3_stmt, idx: c_int, op: c_int) -> c_int {
+    let mut out: c_int = 0;
+    sqlite3_stmt_scanstatus_v2(stmt, idx, op, 0, (&mut out as *mut c_int).cast());
+    out
+}
+
+unsafe fn scan_text(stmt: *mut sqlite3_stmt, idx: c_int, op: c_int) -> Option<String> {
+    let mut out: *const c_char = ptr::null();
+    sqlite3_stmt_scanstatus_v2(stmt, idx, op, 0, (&mut out as *mut *const c_char).cast());
+    (!out.is_null()).then(|| CStr::from_ptr(out).to_string_lossy().into_owned())
+}
+
+/// Read the `sqlite3_stmt_scanstatus_v2` counters of every loop of `stmt`.
+///
+/// The counters accumulate while the statement runs, so call this after
+/// stepping it, and `sqlite3_stmt_scanstatus_reset` to start over. The
+/// library is built with `SQLITE_ENABLE_STMT_SCANSTATUS` for this.
+///
+/// # Safety
+///
+/// `stmt` must be a valid prepared statement.
+pub unsafe fn scan_status(stmt: *mut sqlite3_stmt) -> Vec<ScanStatus> {
+    let mut loops = Vec::new();
+    for idx in 0.. {
+        let Some(nloop) = scan_int64(stmt, idx, SQLITE_SCANSTAT_NLOOP) else {
+            break;
+        };
+        let mut estimated_rows = 0f64;
+        sqlite3_stmt_scanstatus_v2(
+            stmt,
+            idx,
+            SQLITE_SCANSTAT_EST,
+            0,
+            (&mut estimated_rows as *mut f64).cast::<c_void>(),
+        );
+        loops.push(ScanStatus {
+            loops: nloop,
+            visits: scan_int64(stmt, idx, SQLITE_SCANSTAT_NVISIT).unwrap_or_default(),
+            estimated_rows,
+            name: scan_text(stmt, idx, SQLITE_SCANSTAT_NAME),
+            explain: scan_text(stmt, idx, SQLITE_SCANSTAT_EXPLAIN),
+            select_id: scan_int(stmt, idx, SQLITE_SCANSTAT_SELECTID),
+            parent_id: scan_int(stmt, idx, SQLITE_SCANSTAT_PARENTID),
+        });
+    }
+    loops
+}
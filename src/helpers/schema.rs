@@ -0,0 +1,166 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ffi::{c_int, CStr};
+
+use super::stmt::{exec, Stmt};
+use super::Result;
+use crate::{sqlite3, sqlite3_db_name};
+
+/// An index of a table, see [`table_indexes`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexInfo {
+    /// Name of the index, automatic indexes are named `sqlite_autoindex_*`.
+    pub name: String,
+    /// Whether the index is `UNIQUE`, including primary key and unique constraints.
+    pub unique: bool,
+    /// Indexed columns in index order, expressions are reported as an empty string.
+    pub columns: Vec<String>,
+}
+
+/// A trigger, see [`list_triggers`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TriggerInfo {
+    /// Name of the trigger.
+    pub name: String,
+    /// Table or view the trigger is attached to.
+    pub table: String,
+    /// The `CREATE TRIGGER` statement as stored in `sqlite_schema`.
+    pub sql: String,
+}
+
+/// List the indexes of `table` with `PRAGMA index_list` and `PRAGMA index_info`.
+///
+/// Returns an empty list if the table has no indexes or does not exist.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn table_indexes(db: *mut sqlite3, table: &str) -> Result<Vec<IndexInfo>> {
+    let list = Stmt::prepare(db, "SELECT name, \"unique\" FROM pragma_index_list(?1)")?;
+    list.bind_text(1, table)?;
+    let info = Stmt::prepare(db, "SELECT name FROM pragma_index_info(?1) ORDER BY seqno")?;
+
+    let mut indexes = Vec::new();
+    while list.step()? {
+        let name = list.column_text(0).unwrap_or_default();
+        let unique = list.column_int64(1) != 0;
+
+        info.bind_text(1, &name)?;
+        let mut columns = Vec::new();
+        while info.step()? {
+            columns.push(info.column_text(0).unwrap_or_default());
+        }
+        info.reset()?;
+
+        indexes.push(IndexInfo {
+            name,
+            unique,
+            columns,
+        });
+    }
+    Ok(indexes)
+}
+
+/// The schema name of the database at index `n` of the connection.
+///
+/// Index 0 is `main`, 1 is `temp`, and attached databases follow in the order
+/// of `PRAGMA database_list`. Returns `None` if `n` is out of range.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn db_name(db: *mut sqlite3, n: c_int) -> Option<String> {
+    let name = sqlite3_db_name(db, n);
+    if name.is_null() {
+        return None;
+    }
+    Some(CStr::from_ptr(name).to_string_lossy().into_owned())
+}
+
+unsafe fn schema_names(db: *mut sqlite3, kind: &str) -> Result<Vec<String>> {
+    let stmt = Stmt::prepare(
+        db,
+        "SELECT name FROM sqlite_schema \
+         WHERE type = ?1 AND name NOT LIKE 'sqlite\\_%' ESCAPE '\\' ORDER BY name",
+    )?;
+    stmt.bind_text(1, kind)?;
+    let mut names = Vec::new();
+    while stmt.step()? {
+        names.push(stmt.column_text(0).unwrap_or_default());
+    }
+    Ok(names)
+}
+
+/// The names of the tables in the `main` schema, sorted by name.
+///
+/// Internal tables such as `sqlite_sequence` and `sqlite_stat1` are left
+/// out. Virtual tables are included, their shadow tables as well.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn list_tables(db: *mut sqlite3) -> Result<Vec<String>> {
+    schema_names(db, "table")
+}
+
+/// The names of the views in the `main` schema, sorted by name.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn list_views(db: *mut sqlite3) -> Result<Vec<String>> {
+    schema_names(db, "view")
+}
+
+/// The triggers in the `main` schema, sorted by name.
+///
+/// With `table`, only the triggers on that table or view are listed, the
+/// name is compared case-insensitively like SQL identifiers.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn list_triggers(db: *mut sqlite3, table: Option<&str>) -> Result<Vec<TriggerInfo>> {
+    let stmt = Stmt::prepare(
+        db,
+        "SELECT name, tbl_name, sql FROM sqlite_schema WHERE type = 'trigger' \
+         AND (?1 IS NULL OR tbl_name = ?1 COLLATE NOCASE) ORDER BY name",
+    )?;
+    // An unbound parameter is NULL, which lists all triggers.
+    if let Some(table) = table {
+        stmt.bind_text(1, table)?;
+    }
+    let mut triggers = Vec::new();
+    while stmt.step()? {
+        triggers.push(TriggerInfo {
+            name: stmt.column_text(0).unwrap_or_default(),
+            table: stmt.column_text(1).unwrap_or_default(),
+            sql: stmt.column_text(2).unwrap_or_default(),
+        });
+    }
+    Ok(triggers)
+}
+
+/// `PRAGMA schema_version` of the main database.
+unsafe fn schema_version(db: *mut sqlite3) -> Result<i64> {
+    let stmt = Stmt::prepare(db, "PRAGMA schema_version")?;
+    stmt.step()?;
+    Ok(stmt.column_int64(0))
+}
+
+/// Run `ddl`, typically `CREATE INDEX IF NOT EXISTS ...`, and return whether
+/// it created the index.
+///
+/// `IF NOT EXISTS` hides whether the index was already there, so this
+/// compares the schema version of the main database before and after. That
+/// makes it work for any DDL statement on the main schema, but not for
+/// indexes of attached databases.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn ensure_index(db: *mut sqlite3, ddl: &str) -> Result<bool> {
+    let before = schema_version(db)?;
+    exec(db, ddl)?;
+    Ok(schema_version(db)? != before)
+}
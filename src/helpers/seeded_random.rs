@@ -0,0 +1,79 @@
+use alloc::boxed::Box;
+use core::cell::Cell;
+use core::ffi::{c_int, c_void, CStr};
+use core::ptr;
+
+use super::{last_error, Result, SqliteError};
+use crate::{
+    sqlite3, sqlite3_context, sqlite3_context_db_handle, sqlite3_create_function_v2,
+    sqlite3_get_clientdata, sqlite3_result_error, sqlite3_result_int64, sqlite3_set_clientdata,
+    sqlite3_value, SQLITE_OK, SQLITE_UTF8,
+};
+
+/// Client data key of the PRNG state of `seeded_random()`.
+const STATE_KEY: &CStr = c"sqlite-wasm-rs.seeded_random";
+
+/// SplitMix64, small and fast, with every seed giving a full-period sequence.
+fn next_random(state: &Cell<u64>) -> u64 {
+    let next = state.get().wrapping_add(0x9e37_79b9_7f4a_7c15);
+    state.set(next);
+    let mut z = next;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+unsafe extern "C" fn x_seeded_random(
+    ctx: *mut sqlite3_context,
+    _argc: c_int,
+    _argv: *mut *mut sqlite3_value,
+) {
+    let state = sqlite3_get_clientdata(sqlite3_context_db_handle(ctx), STATE_KEY.as_ptr());
+    if state.is_null() {
+        const MSG: &str = "seeded_random() is not seeded";
+        sqlite3_result_error(ctx, MSG.as_ptr().cast(), MSG.len() as c_int);
+        return;
+    }
+    sqlite3_result_int64(ctx, next_random(&*state.cast::<Cell<u64>>()) as i64);
+}
+
+unsafe extern "C" fn drop_state(state: *mut c_void) {
+    drop(Box::from_raw(state.cast::<Cell<u64>>()));
+}
+
+/// Register `seeded_random()`, an SQL function returning a reproducible
+/// sequence of 64-bit integers starting from `seed`.
+///
+/// Unlike the built-in `random()`, the same seed always gives the same
+/// sequence, e.g. for sampling that has to be repeatable. The PRNG state is
+/// kept in the client data of the connection, so each connection has its own
+/// sequence, and calling this again reseeds it. The sequence is not suitable
+/// for cryptography.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn create_seeded_random(db: *mut sqlite3, seed: u64) -> Result<()> {
+    let state = Box::into_raw(Box::new(Cell::new(seed)));
+    // SQLite calls `drop_state` itself if this fails, and on the previous
+    // state when it is replaced.
+    let ret = sqlite3_set_clientdata(db, STATE_KEY.as_ptr(), state.cast(), Some(drop_state));
+    if ret != SQLITE_OK {
+        return Err(SqliteError::from_code(ret));
+    }
+    let ret = sqlite3_create_function_v2(
+        db,
+        c"seeded_random".as_ptr(),
+        0,
+        SQLITE_UTF8,
+        ptr::null_mut(),
+        Some(x_seeded_random),
+        None,
+        None,
+        None,
+    );
+    if ret != SQLITE_OK {
+        return Err(last_error(db));
+    }
+    Ok(())
+}
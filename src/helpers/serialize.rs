@@ -0,0 +1,176 @@
+use alloc::vec::Vec;
+use core::ffi::CStr;
+
+use super::open::open;
+use super::stmt::Stmt;
+use super::{last_error, Result, SqliteError};
+use crate::utils::{check_db_and_page_size, check_import_db};
+use crate::{
+    sqlite3, sqlite3_close, sqlite3_deserialize, sqlite3_free, sqlite3_int64, sqlite3_malloc64,
+    sqlite3_serialize, SQLITE_DESERIALIZE_FREEONCLOSE, SQLITE_DESERIALIZE_RESIZEABLE, SQLITE_ERROR,
+    SQLITE_NOMEM, SQLITE_OK, SQLITE_OPEN_CREATE, SQLITE_OPEN_READWRITE, SQLITE_OPEN_URI,
+    SQLITE_SERIALIZE_NOCOPY,
+};
+
+/// A shared database of the `memdb` VFS, it lives while a connection has it open.
+const VACUUM_TARGET: &CStr = c"file:/sqlite-wasm-rs-vacuum-into?vfs=memdb";
+
+/// Serialize the database `schema` into a `Vec<u8>`.
+///
+/// The bytes are the same as the database file on disk and can be loaded
+/// with `sqlite3_deserialize`. The buffer allocated by `sqlite3_serialize`
+/// is copied and released with `sqlite3_free` before returning.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn serialize_to_vec(db: *mut sqlite3, schema: &CStr) -> Result<Vec<u8>> {
+    let mut size: sqlite3_int64 = 0;
+    let ptr = sqlite3_serialize(db, schema.as_ptr(), &mut size as *mut _, 0);
+    if ptr.is_null() {
+        return match size {
+            // A database without any pages serializes to nothing.
+            0 => Ok(Vec::new()),
+            // `size` is left at -1 if the schema does not exist.
+            _ if size < 0 => Err(SqliteError::new(
+                SQLITE_ERROR,
+                alloc::format!("unknown database {}", schema.to_string_lossy()),
+            )),
+            _ => Err(SqliteError::from_code(SQLITE_NOMEM)),
+        };
+    }
+
+    let bytes = core::slice::from_raw_parts(ptr, size as usize).to_vec();
+    sqlite3_free(ptr.cast());
+    Ok(bytes)
+}
+
+/// The number of bytes [`serialize_to_vec`] would return for `schema`.
+///
+/// Calls `sqlite3_serialize` with `SQLITE_SERIALIZE_NOCOPY`, which reports
+/// the size, page size times page count, without allocating a copy of the
+/// database. Useful to show the size of a download before creating it.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn serialized_size(db: *mut sqlite3, schema: &CStr) -> Result<i64> {
+    let mut size: sqlite3_int64 = 0;
+    sqlite3_serialize(
+        db,
+        schema.as_ptr(),
+        &mut size as *mut _,
+        SQLITE_SERIALIZE_NOCOPY,
+    );
+    // `size` is left at -1 if the schema does not exist.
+    if size < 0 {
+        return Err(SqliteError::new(
+            SQLITE_ERROR,
+            alloc::format!("unknown database {}", schema.to_string_lossy()),
+        ));
+    }
+    Ok(size)
+}
+
+/// A compacted copy of the main database of `db`, serialized.
+///
+/// Runs `VACUUM INTO` with a temporary database of SQLite's in-memory
+/// `memdb` VFS as the target, so no file is left behind. Unlike
+/// [`serialize_to_vec`], free pages are dropped and the content is
+/// defragmented, so the bytes are usually smaller, e.g. after deleting many
+/// rows. The database of `db` itself is not changed.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn vacuum_into_vec(db: *mut sqlite3) -> Result<Vec<u8>> {
+    // Keep the target open, the shared database is freed with its last
+    // connection, which `VACUUM INTO` closes when it is done.
+    let target = open(
+        VACUUM_TARGET,
+        SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE | SQLITE_OPEN_URI,
+        None,
+    )?;
+    let vacuumed = (|| {
+        let stmt = Stmt::prepare(db, "VACUUM INTO ?1")?;
+        stmt.bind_text(1, &VACUUM_TARGET.to_string_lossy())?;
+        stmt.step()?;
+        serialize_to_vec(target, c"main")
+    })();
+    sqlite3_close(target);
+    vacuumed
+}
+
+/// A copy of the main database of `db` in a new in-memory connection.
+///
+/// The main database is serialized and deserialized into a fresh
+/// `:memory:` connection, so the clone is independent of `db`: writes to
+/// either one are not seen by the other. Attached databases, temp tables
+/// and connection settings are not copied.
+///
+/// In-memory databases can't use WAL, so if `db` is in WAL mode the file
+/// format bytes of the header are switched back to the rollback journal
+/// and the clone always starts in the default journal mode of `:memory:`.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection, and the returned connection
+/// must be closed with `sqlite3_close`.
+pub unsafe fn clone_db(db: *mut sqlite3) -> Result<*mut sqlite3> {
+    let mut bytes = serialize_to_vec(db, c"main")?;
+    // Bytes 18 and 19 are the read and write versions, 2 for WAL.
+    if bytes.len() >= 20 {
+        bytes[18] = 1;
+        bytes[19] = 1;
+    }
+
+    let clone = open(
+        c":memory:",
+        SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+        None,
+    )?;
+    if bytes.is_empty() {
+        return Ok(clone);
+    }
+
+    let buf = sqlite3_malloc64(bytes.len() as u64).cast::<u8>();
+    if buf.is_null() {
+        sqlite3_close(clone);
+        return Err(SqliteError::from_code(SQLITE_NOMEM));
+    }
+    core::ptr::copy_nonoverlapping(bytes.as_ptr(), buf, bytes.len());
+    // With `SQLITE_DESERIALIZE_FREEONCLOSE` the buffer is freed by SQLite,
+    // even if deserializing fails.
+    let ret = sqlite3_deserialize(
+        clone,
+        c"main".as_ptr(),
+        buf,
+        bytes.len() as sqlite3_int64,
+        bytes.len() as sqlite3_int64,
+        SQLITE_DESERIALIZE_FREEONCLOSE | SQLITE_DESERIALIZE_RESIZEABLE,
+    );
+    if ret != SQLITE_OK {
+        let err = last_error(clone);
+        sqlite3_close(clone);
+        return Err(err);
+    }
+    Ok(clone)
+}
+
+/// Cheap check whether `bytes` look like an SQLite database file.
+///
+/// Verifies the 16-byte `SQLite format 3\0` header magic, that the page size
+/// in the header is a power of two between 512 and 65536, and that the length
+/// is a multiple of it. Use it to reject arbitrary files before handing them
+/// to `sqlite3_deserialize` or a VFS import. Passing does not guarantee the
+/// database is free of corruption.
+pub fn is_sqlite_database(bytes: &[u8]) -> bool {
+    // The header string is followed by a NUL in the file.
+    if bytes.get(15) != Some(&0) {
+        return false;
+    }
+    match check_import_db(bytes) {
+        Ok(page_size) => check_db_and_page_size(bytes.len(), page_size).is_ok(),
+        Err(_) => false,
+    }
+}
@@ -0,0 +1,628 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ffi::{c_char, c_int, c_uint, CStr};
+use core::mem::ManuallyDrop;
+use core::ptr;
+
+use super::{last_error, Result, Row, SqliteError, Value};
+use crate::{
+    sqlite3, sqlite3_bind_blob64, sqlite3_bind_double, sqlite3_bind_int64, sqlite3_bind_null,
+    sqlite3_bind_parameter_count, sqlite3_bind_text, sqlite3_clear_bindings, sqlite3_column_blob,
+    sqlite3_column_bytes, sqlite3_column_count, sqlite3_column_decltype, sqlite3_column_double,
+    sqlite3_column_int64, sqlite3_column_name, sqlite3_column_origin_name,
+    sqlite3_column_table_name, sqlite3_column_text, sqlite3_column_type, sqlite3_db_handle,
+    sqlite3_finalize, sqlite3_next_stmt, sqlite3_prepare_v3, sqlite3_reset, sqlite3_step,
+    sqlite3_stmt, SQLITE_BLOB, SQLITE_DONE, SQLITE_FLOAT, SQLITE_INTEGER, SQLITE_MISMATCH,
+    SQLITE_MISUSE, SQLITE_OK, SQLITE_RANGE, SQLITE_ROW, SQLITE_TEXT, SQLITE_TRANSIENT,
+};
+
+/// An iterator over the statements of a multi-statement SQL string.
+///
+/// Each statement is prepared with `sqlite3_prepare_v3`, and the tail pointer
+/// is used to continue with the rest of the string. Whitespace, comments and
+/// empty statements between statements are skipped.
+///
+/// The caller owns every yielded statement and must `sqlite3_finalize` it.
+/// Iteration stops after the first error.
+///
+/// ```rust
+/// use sqlite_wasm_rs::{self as ffi, helpers::StatementIter};
+///
+/// unsafe fn exec_all(db: *mut ffi::sqlite3, sql: &str) {
+///     for stmt in StatementIter::new(db, sql) {
+///         let stmt = stmt.unwrap();
+///         while ffi::sqlite3_step(stmt) == ffi::SQLITE_ROW {}
+///         ffi::sqlite3_finalize(stmt);
+///     }
+/// }
+/// ```
+pub struct StatementIter<'a> {
+    db: *mut sqlite3,
+    sql: &'a str,
+    offset: usize,
+    prep_flags: c_uint,
+}
+
+impl<'a> StatementIter<'a> {
+    /// Create an iterator over the statements in `sql`.
+    ///
+    /// # Safety
+    ///
+    /// `db` must be a valid database connection that outlives the iterator.
+    pub unsafe fn new(db: *mut sqlite3, sql: &'a str) -> Self {
+        Self {
+            db,
+            sql,
+            offset: 0,
+            prep_flags: 0,
+        }
+    }
+
+    /// `SQLITE_PREPARE_*` flags passed to `sqlite3_prepare_v3`.
+    pub fn prep_flags(mut self, prep_flags: c_uint) -> Self {
+        self.prep_flags = prep_flags;
+        self
+    }
+}
+
+impl Iterator for StatementIter<'_> {
+    type Item = Result<*mut sqlite3_stmt>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.offset < self.sql.len() {
+            let rest = &self.sql.as_bytes()[self.offset..];
+            let mut stmt: *mut sqlite3_stmt = ptr::null_mut();
+            let mut tail: *const c_char = ptr::null();
+            let ret = unsafe {
+                sqlite3_prepare_v3(
+                    self.db,
+                    rest.as_ptr().cast(),
+                    rest.len() as c_int,
+                    self.prep_flags,
+                    &mut stmt as *mut _,
+                    &mut tail as *mut _,
+                )
+            };
+            if ret != SQLITE_OK {
+                let mut err = unsafe { last_error(self.db) };
+                // Make the offset relative to the whole SQL string.
+                err.offset = err.offset.map(|offset| offset + self.offset);
+                self.offset = self.sql.len();
+                return Some(Err(err));
+            }
+
+            // pzTail points into `rest`, past the end of the statement just compiled.
+            let consumed = if tail.is_null() {
+                rest.len()
+            } else {
+                tail as usize - rest.as_ptr() as usize
+            };
+            self.offset += consumed;
+
+            if !stmt.is_null() {
+                return Some(Ok(stmt));
+            }
+            if consumed == 0 {
+                break;
+            }
+        }
+        None
+    }
+}
+
+/// A prepared statement that is finalized on drop.
+pub(crate) struct Stmt {
+    db: *mut sqlite3,
+    raw: *mut sqlite3_stmt,
+}
+
+impl Stmt {
+    /// Prepare the first statement of `sql`.
+    pub(crate) unsafe fn prepare(db: *mut sqlite3, sql: &str) -> Result<Self> {
+        let mut raw: *mut sqlite3_stmt = ptr::null_mut();
+        let ret = sqlite3_prepare_v3(
+            db,
+            sql.as_ptr().cast(),
+            sql.len() as c_int,
+            0,
+            &mut raw as *mut _,
+            ptr::null_mut(),
+        );
+        if ret != SQLITE_OK {
+            return Err(last_error(db));
+        }
+        if raw.is_null() {
+            return Err(SqliteError::new(SQLITE_MISUSE, "empty statement"));
+        }
+        Ok(Self { db, raw })
+    }
+
+    /// Wrap a statement owned by the caller, which is not finalized on drop.
+    pub(crate) unsafe fn borrowed(raw: *mut sqlite3_stmt) -> ManuallyDrop<Self> {
+        ManuallyDrop::new(Self {
+            db: sqlite3_db_handle(raw),
+            raw,
+        })
+    }
+
+    pub(crate) fn as_ptr(&self) -> *mut sqlite3_stmt {
+        self.raw
+    }
+
+    /// Step the statement, returns `true` if a row is available.
+    pub(crate) unsafe fn step(&self) -> Result<bool> {
+        match sqlite3_step(self.raw) {
+            SQLITE_ROW => Ok(true),
+            SQLITE_DONE => Ok(false),
+            _ => Err(last_error(self.db)),
+        }
+    }
+
+    pub(crate) unsafe fn reset(&self) -> Result<()> {
+        match sqlite3_reset(self.raw) {
+            SQLITE_OK => Ok(()),
+            _ => Err(last_error(self.db)),
+        }
+    }
+
+    pub(crate) unsafe fn bind_text(&self, idx: c_int, text: &str) -> Result<()> {
+        let ret = sqlite3_bind_text(
+            self.raw,
+            idx,
+            text.as_ptr().cast(),
+            text.len() as c_int,
+            SQLITE_TRANSIENT(),
+        );
+        match ret {
+            SQLITE_OK => Ok(()),
+            _ => Err(last_error(self.db)),
+        }
+    }
+
+    pub(crate) unsafe fn bind_value(&self, idx: c_int, value: &Value) -> Result<()> {
+        let ret = match value {
+            Value::Null => sqlite3_bind_null(self.raw, idx),
+            Value::Integer(value) => sqlite3_bind_int64(self.raw, idx, *value),
+            Value::Real(value) => sqlite3_bind_double(self.raw, idx, *value),
+            Value::Text(text) => return self.bind_text(idx, text),
+            Value::Blob(blob) => sqlite3_bind_blob64(
+                self.raw,
+                idx,
+                blob.as_ptr().cast(),
+                blob.len() as u64,
+                SQLITE_TRANSIENT(),
+            ),
+        };
+        match ret {
+            SQLITE_OK => Ok(()),
+            _ => Err(last_error(self.db)),
+        }
+    }
+
+    /// Bind `params` to the parameters `?1`, `?2`, ... in order.
+    pub(crate) unsafe fn bind_all(&self, params: &[Value]) -> Result<()> {
+        for (idx, value) in params.iter().enumerate() {
+            self.bind_value(idx as c_int + 1, value)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) unsafe fn column_int64(&self, idx: c_int) -> i64 {
+        sqlite3_column_int64(self.raw, idx)
+    }
+
+    /// Text of column `idx` of the current row, `None` for `NULL`.
+    pub(crate) unsafe fn column_text(&self, idx: c_int) -> Option<String> {
+        let text = sqlite3_column_text(self.raw, idx);
+        if text.is_null() {
+            return None;
+        }
+        let len = sqlite3_column_bytes(self.raw, idx) as usize;
+        let bytes = core::slice::from_raw_parts(text, len);
+        Some(String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    /// Value of column `idx` of the current row.
+    pub(crate) unsafe fn column_value(&self, idx: c_int) -> Value {
+        match sqlite3_column_type(self.raw, idx) {
+            SQLITE_INTEGER => Value::Integer(self.column_int64(idx)),
+            SQLITE_FLOAT => Value::Real(sqlite3_column_double(self.raw, idx)),
+            SQLITE_TEXT => self.column_text(idx).map_or(Value::Null, Value::Text),
+            SQLITE_BLOB => {
+                // `sqlite3_column_blob` must be called before `sqlite3_column_bytes`.
+                let ptr = sqlite3_column_blob(self.raw, idx);
+                let len = sqlite3_column_bytes(self.raw, idx);
+                if ptr.is_null() || len <= 0 {
+                    Value::Blob(Vec::new())
+                } else {
+                    Value::Blob(core::slice::from_raw_parts(ptr.cast(), len as usize).to_vec())
+                }
+            }
+            _ => Value::Null,
+        }
+    }
+}
+
+impl Drop for Stmt {
+    fn drop(&mut self) {
+        unsafe {
+            sqlite3_finalize(self.raw);
+        }
+    }
+}
+
+/// Step `stmt` once and read every column of the new row.
+///
+/// Returns `None` once the statement is done. The column count is read once
+/// and all values are converted in a single pass, so a row costs one call
+/// instead of a `sqlite3_column_*` round trip per column from the caller.
+///
+/// ```rust
+/// use sqlite_wasm_rs::{self as ffi, helpers::{step_row, Row, Result}};
+///
+/// unsafe fn collect_rows(stmt: *mut ffi::sqlite3_stmt) -> Result<Vec<Row>> {
+///     let mut rows = vec![];
+///     while let Some(row) = step_row(stmt)? {
+///         rows.push(row);
+///     }
+///     Ok(rows)
+/// }
+/// ```
+///
+/// # Safety
+///
+/// `stmt` must be a valid prepared statement, it is not finalized.
+pub unsafe fn step_row(stmt: *mut sqlite3_stmt) -> Result<Option<Row>> {
+    let stmt = Stmt::borrowed(stmt);
+    if !stmt.step()? {
+        return Ok(None);
+    }
+    let columns = sqlite3_column_count(stmt.raw);
+    Ok(Some(
+        (0..columns).map(|idx| stmt.column_value(idx)).collect(),
+    ))
+}
+
+/// Bind `values` to the parameters `?1`, `?2`, ... of `stmt` in order.
+///
+/// Fails with `SQLITE_RANGE` without binding anything if the number of
+/// values differs from `sqlite3_bind_parameter_count`, so a row can't be
+/// inserted with columns silently left NULL. Named parameters count by their
+/// position too.
+///
+/// Text and blobs are bound with `SQLITE_TRANSIENT`, SQLite copies them
+/// once when they are bound and the copy stays bound across steps and
+/// resets, so `values` don't need to outlive the call. To bind a single
+/// blob by hand, pass `SQLITE_TRANSIENT()` to `sqlite3_bind_blob64` the same
+/// way.
+///
+/// # Safety
+///
+/// `stmt` must be a valid prepared statement.
+pub unsafe fn bind_row(stmt: *mut sqlite3_stmt, values: &[Value]) -> Result<()> {
+    let count = sqlite3_bind_parameter_count(stmt);
+    if values.len() != count as usize {
+        return Err(SqliteError::new(
+            SQLITE_RANGE,
+            format!("expected {count} parameters, got {}", values.len()),
+        ));
+    }
+    let stmt = Stmt::borrowed(stmt);
+    stmt.bind_all(values)
+}
+
+/// Reset `stmt`, clear its bindings and bind `values` to the parameters
+/// `?1`, `?2`, ... in order.
+///
+/// Reusing a statement with new parameters otherwise takes `sqlite3_reset`,
+/// `sqlite3_clear_bindings` and a bind call per value. Parameters without a
+/// value are left NULL, more values than parameters fail with
+/// `SQLITE_RANGE`. The result of the reset is ignored, it only repeats the
+/// error of the last step.
+///
+/// # Safety
+///
+/// `stmt` must be a valid prepared statement.
+pub unsafe fn rebind(stmt: *mut sqlite3_stmt, values: &[Value]) -> Result<()> {
+    sqlite3_reset(stmt);
+    sqlite3_clear_bindings(stmt);
+    let stmt = Stmt::borrowed(stmt);
+    stmt.bind_all(values)
+}
+
+/// Bind `value` to parameter `idx` of `stmt`, or `NULL` for `None`.
+///
+/// Saves matching on every optional column. `T` is converted like the
+/// other values of [`Value`], e.g. `Some(5i64)` binds an integer.
+///
+/// # Safety
+///
+/// `stmt` must be a valid prepared statement.
+pub unsafe fn bind_option<T: Into<Value>>(
+    stmt: *mut sqlite3_stmt,
+    idx: c_int,
+    value: Option<T>,
+) -> Result<()> {
+    let stmt = Stmt::borrowed(stmt);
+    stmt.bind_value(idx, &value.into())
+}
+
+/// The prepared statements of `db` that are not finalized yet.
+///
+/// Walks the list of `sqlite3_next_stmt`, most recently prepared first.
+/// The statements are collected up front, so they can be finalized while
+/// iterating over the result, e.g. to clean up before closing.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn prepared_statements(db: *mut sqlite3) -> Vec<*mut sqlite3_stmt> {
+    let mut stmts = Vec::new();
+    let mut stmt = sqlite3_next_stmt(db, ptr::null_mut());
+    while !stmt.is_null() {
+        stmts.push(stmt);
+        stmt = sqlite3_next_stmt(db, stmt);
+    }
+    stmts
+}
+
+/// Reset every prepared statement of `db`, so the next step starts over.
+///
+/// Handy before reusing a cached connection: statements left in the middle
+/// of their rows keep read transactions open, which blocks e.g. `VACUUM` or
+/// checkpoints. Bindings are kept. The results of `sqlite3_reset` are
+/// ignored, they only repeat the error of the last step.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn reset_all_statements(db: *mut sqlite3) {
+    for stmt in prepared_statements(db) {
+        sqlite3_reset(stmt);
+    }
+}
+
+/// Metadata of a result column, see [`statement_columns`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnSpec {
+    /// Name of the column in the result, including any `AS` alias.
+    pub name: String,
+    /// Declared type of the table column, `None` for expressions.
+    pub declared_type: Option<String>,
+    /// Table the column is read from, `None` for expressions.
+    pub table: Option<String>,
+    /// Name of the column in `table`, `None` for expressions.
+    pub origin: Option<String>,
+}
+
+/// Owned copy of a column metadata string, `None` for NULL.
+unsafe fn column_meta(text: *const c_char) -> Option<String> {
+    if text.is_null() {
+        return None;
+    }
+    Some(CStr::from_ptr(text).to_string_lossy().into_owned())
+}
+
+/// Metadata of every result column of `stmt`, gathered in a single pass.
+///
+/// The metadata is fixed once the statement is prepared, so this is meant to
+/// be called once right after prepare and the result kept as the schema of
+/// the statement, instead of calling `sqlite3_column_*_name` per row.
+///
+/// # Safety
+///
+/// `stmt` must be a valid prepared statement.
+pub unsafe fn statement_columns(stmt: *mut sqlite3_stmt) -> Vec<ColumnSpec> {
+    (0..sqlite3_column_count(stmt))
+        .map(|idx| ColumnSpec {
+            name: column_meta(sqlite3_column_name(stmt, idx)).unwrap_or_default(),
+            declared_type: column_meta(sqlite3_column_decltype(stmt, idx)),
+            table: column_meta(sqlite3_column_table_name(stmt, idx)),
+            origin: column_meta(sqlite3_column_origin_name(stmt, idx)),
+        })
+        .collect()
+}
+
+/// Run every statement of `sql`, discarding any rows.
+pub(crate) unsafe fn exec(db: *mut sqlite3, sql: &str) -> Result<()> {
+    for stmt in StatementIter::new(db, sql) {
+        let stmt = Stmt { db, raw: stmt? };
+        while stmt.step()? {}
+    }
+    Ok(())
+}
+
+/// Whether `stmt` produces rows, i.e. has at least one result column.
+///
+/// Lets a caller choose between iterating over the rows and just executing
+/// the statement. Based on `sqlite3_column_count`, so it is known right
+/// after prepare, and `INSERT`, `UPDATE` or `DELETE` with a `RETURNING`
+/// clause count as producing rows too.
+///
+/// # Safety
+///
+/// `stmt` must be a valid prepared statement.
+pub unsafe fn statement_returns_rows(stmt: *mut sqlite3_stmt) -> bool {
+    sqlite3_column_count(stmt) > 0
+}
+
+/// Fail with `SQLITE_MISMATCH` if column `col` of `stmt` has a declared type
+/// that contains none of `words`. Expressions have no declared type and pass.
+unsafe fn check_decltype(
+    stmt: *mut sqlite3_stmt,
+    col: c_int,
+    words: &[&str],
+    expected: &str,
+) -> Result<()> {
+    let Some(declared) = column_meta(sqlite3_column_decltype(stmt, col)) else {
+        return Ok(());
+    };
+    let upper = declared.to_ascii_uppercase();
+    if words.iter().any(|word| upper.contains(word)) {
+        return Ok(());
+    }
+    Err(SqliteError::new(
+        SQLITE_MISMATCH,
+        format!("column {col} is declared as {declared}, not {expected}"),
+    ))
+}
+
+/// Read column `col` of the current row of `stmt` as a boolean.
+///
+/// SQLite has no boolean type, a `BOOLEAN` column holds integers. Numbers
+/// are `true` unless 0, the texts `true`, `false`, `1` and `0` are accepted
+/// too, case-insensitive, and `NULL` is `None`. Fails with `SQLITE_MISMATCH`
+/// for other values, and if the column is declared with a type that does
+/// not contain `BOOL`.
+///
+/// # Safety
+///
+/// `stmt` must be a valid prepared statement positioned on a row.
+pub unsafe fn column_as_bool(stmt: *mut sqlite3_stmt, col: c_int) -> Result<Option<bool>> {
+    check_decltype(stmt, col, &["BOOL"], "BOOLEAN")?;
+    let stmt = Stmt::borrowed(stmt);
+    match stmt.column_value(col) {
+        Value::Null => Ok(None),
+        Value::Integer(value) => Ok(Some(value != 0)),
+        Value::Real(value) => Ok(Some(value != 0.0)),
+        Value::Text(text) if text == "1" || text.eq_ignore_ascii_case("true") => Ok(Some(true)),
+        Value::Text(text) if text == "0" || text.eq_ignore_ascii_case("false") => Ok(Some(false)),
+        value => Err(SqliteError::new(
+            SQLITE_MISMATCH,
+            format!("{value:?} is not a boolean"),
+        )),
+    }
+}
+
+/// Read column `col` of the current row of `stmt` as a date and time, in
+/// seconds since the Unix epoch.
+///
+/// The value is converted like `unixepoch(value, 'auto', 'subsec')` does,
+/// without running SQL: texts are time strings such as
+/// `2024-01-02 03:04:05.250` or `2024-01-02T05:04:05+02:00`, in UTC unless
+/// they carry an offset, and numbers, integer, real or numeric text, are
+/// Julian day numbers if they are between 0 and 5373484.5 and Unix time in
+/// seconds otherwise. The result keeps milliseconds, `NULL` is `None`. Fails
+/// with `SQLITE_MISMATCH` for blobs and values that are not a date, and if
+/// the column is declared with a type that contains neither `DATE` nor
+/// `TIME`.
+///
+/// # Safety
+///
+/// `stmt` must be a valid prepared statement positioned on a row.
+pub unsafe fn column_as_datetime(stmt: *mut sqlite3_stmt, col: c_int) -> Result<Option<f64>> {
+    check_decltype(stmt, col, &["DATE", "TIME"], "DATETIME")?;
+    let stmt = Stmt::borrowed(stmt);
+    let value = stmt.column_value(col);
+    let secs = match &value {
+        Value::Null => return Ok(None),
+        Value::Integer(value) => number_to_unix(*value as f64),
+        Value::Real(value) => number_to_unix(*value),
+        Value::Text(text) => match text.trim().parse::<f64>() {
+            Ok(value) => number_to_unix(value),
+            Err(_) => time_string_to_unix(text),
+        },
+        Value::Blob(_) => None,
+    };
+    secs.map(Some).ok_or_else(|| {
+        SqliteError::new(SQLITE_MISMATCH, format!("{value:?} is not a date and time"))
+    })
+}
+
+/// The Julian day number of the Unix epoch in milliseconds.
+const UNIX_EPOCH_JULIAN_MS: i64 = 210_866_760_000_000;
+
+/// Unix time in seconds of a number read with the `'auto'` modifier, rounded
+/// to milliseconds like SQLite, or `None` if it is out of range.
+fn number_to_unix(value: f64) -> Option<f64> {
+    let julian_ms = if (0.0..5_373_484.5).contains(&value) {
+        value * 86_400_000.0
+    } else if (-210_866_760_000.0..=253_402_300_799.0).contains(&value) {
+        value * 1000.0 + UNIX_EPOCH_JULIAN_MS as f64
+    } else {
+        return None;
+    };
+    Some(((julian_ms + 0.5) as i64 - UNIX_EPOCH_JULIAN_MS) as f64 / 1000.0)
+}
+
+/// Unix time in seconds of a time string
+/// `YYYY-MM-DD[( |T)HH:MM[:SS[.SSS]]][Z|(+|-)HH:MM]`, or `None` if it is
+/// not one.
+fn time_string_to_unix(text: &str) -> Option<f64> {
+    let text = text.trim().as_bytes();
+    let mut pos = 0;
+    let year = digits(text, &mut pos, 4)?;
+    expect(text, &mut pos, b'-')?;
+    let month = digits(text, &mut pos, 2)?;
+    expect(text, &mut pos, b'-')?;
+    let day = digits(text, &mut pos, 2)?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let mut ms = days_from_civil(year, month, day) * 86_400_000;
+
+    if let Some(b' ' | b'T') = text.get(pos) {
+        pos += 1;
+        let hour = digits(text, &mut pos, 2)?;
+        expect(text, &mut pos, b':')?;
+        let minute = digits(text, &mut pos, 2)?;
+        let mut second_ms = 0;
+        if expect(text, &mut pos, b':').is_some() {
+            second_ms = digits(text, &mut pos, 2)? * 1000;
+            if expect(text, &mut pos, b'.').is_some() {
+                let start = pos;
+                while text.get(pos).is_some_and(u8::is_ascii_digit) {
+                    pos += 1;
+                }
+                // Only milliseconds are kept, rounded.
+                let fraction = core::str::from_utf8(&text[start - 1..pos]).ok()?;
+                second_ms += (fraction.parse::<f64>().ok()? * 1000.0 + 0.5) as i64;
+            }
+        }
+        if hour > 24 || minute > 59 || second_ms >= 60_000 {
+            return None;
+        }
+        ms += hour * 3_600_000 + minute * 60_000 + second_ms;
+    }
+
+    match text.get(pos) {
+        None => (),
+        Some(b'Z' | b'z') => pos += 1,
+        Some(&sign @ (b'+' | b'-')) => {
+            pos += 1;
+            let hours = digits(text, &mut pos, 2)?;
+            expect(text, &mut pos, b':')?;
+            let minutes = digits(text, &mut pos, 2)?;
+            let offset = (hours * 60 + minutes) * 60_000;
+            ms -= if sign == b'+' { offset } else { -offset };
+        }
+        Some(_) => return None,
+    }
+    (pos == text.len()).then(|| ms as f64 / 1000.0)
+}
+
+/// Read exactly `len` ASCII digits at `pos`.
+fn digits(text: &[u8], pos: &mut usize, len: usize) -> Option<i64> {
+    let digits = text.get(*pos..*pos + len)?;
+    if !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    *pos += len;
+    Some(digits.iter().fold(0, |n, d| n * 10 + i64::from(d - b'0')))
+}
+
+/// Skip `byte` at `pos`, `None` if it is something else.
+fn expect(text: &[u8], pos: &mut usize, byte: u8) -> Option<()> {
+    (text.get(*pos) == Some(&byte)).then(|| *pos += 1)
+}
+
+/// Days between 1970-01-01 and a date of the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
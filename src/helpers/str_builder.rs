@@ -0,0 +1,145 @@
+use alloc::string::String;
+use core::ffi::{c_char, c_int, c_uchar};
+use core::mem::ManuallyDrop;
+
+use super::{result_static_text, Result, SqliteError};
+use crate::{
+    sqlite3, sqlite3_context, sqlite3_free, sqlite3_result_error_code, sqlite3_result_error_nomem,
+    sqlite3_result_error_toobig, sqlite3_result_text64, sqlite3_str, sqlite3_str_append,
+    sqlite3_str_appendchar, sqlite3_str_errcode, sqlite3_str_finish, sqlite3_str_free,
+    sqlite3_str_length, sqlite3_str_new, SQLITE_NOMEM, SQLITE_OK, SQLITE_TOOBIG, SQLITE_UTF8,
+};
+
+/// A string built in memory from SQLite with the `sqlite3_str` interface.
+///
+/// Appending grows a single `sqlite3_malloc64` buffer, and [`result`]
+/// hands that buffer to SQLite as the result of a function, so building a
+/// result string takes no Rust allocations. Errors such as `SQLITE_NOMEM`
+/// or `SQLITE_TOOBIG` are sticky: later appends are ignored and the error is
+/// reported when the string is finished.
+///
+/// ```rust
+/// use core::ffi::c_int;
+/// use sqlite_wasm_rs::{self as ffi, helpers::StrBuilder};
+///
+/// unsafe extern "C" fn x_greet(
+///     ctx: *mut ffi::sqlite3_context,
+///     _argc: c_int,
+///     _argv: *mut *mut ffi::sqlite3_value,
+/// ) {
+///     let mut text = StrBuilder::new(ffi::sqlite3_context_db_handle(ctx));
+///     text.push_str("hello");
+///     text.push_repeated('!', 3);
+///     text.result(ctx);
+/// }
+/// ```
+///
+/// [`result`]: StrBuilder::result
+#[derive(Debug)]
+pub struct StrBuilder {
+    raw: *mut sqlite3_str,
+}
+
+impl StrBuilder {
+    /// Start an empty string, limited to the `SQLITE_LIMIT_LENGTH` of `db`.
+    ///
+    /// # Safety
+    ///
+    /// `db` must be a valid database connection or null, which limits the
+    /// string to `SQLITE_MAX_LENGTH` instead.
+    pub unsafe fn new(db: *mut sqlite3) -> Self {
+        Self {
+            raw: sqlite3_str_new(db),
+        }
+    }
+
+    /// Append `text`.
+    pub fn push_str(&mut self, text: &str) {
+        unsafe { sqlite3_str_append(self.raw, text.as_ptr().cast(), text.len() as c_int) };
+    }
+
+    /// Append `ch` `n` times.
+    pub fn push_repeated(&mut self, ch: char, n: usize) {
+        if ch.is_ascii() {
+            let n = c_int::try_from(n).unwrap_or(c_int::MAX);
+            unsafe { sqlite3_str_appendchar(self.raw, n, ch as u8 as c_char) };
+        } else {
+            let mut buf = [0; 4];
+            let ch = ch.encode_utf8(&mut buf);
+            for _ in 0..n {
+                self.push_str(ch);
+            }
+        }
+    }
+
+    /// Length of the string in bytes, 0 after an error.
+    pub fn len(&self) -> usize {
+        unsafe { sqlite3_str_length(self.raw) as usize }
+    }
+
+    /// Whether the string is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Finish the string and copy it into a `String`.
+    pub fn finish(self) -> Result<String> {
+        let this = ManuallyDrop::new(self);
+        unsafe {
+            let code = sqlite3_str_errcode(this.raw);
+            let len = sqlite3_str_length(this.raw) as usize;
+            let text = sqlite3_str_finish(this.raw);
+            if code != SQLITE_OK {
+                sqlite3_free(text.cast());
+                return Err(SqliteError::from_code(code));
+            }
+            if text.is_null() {
+                return Ok(String::new());
+            }
+            let string =
+                String::from_utf8_lossy(core::slice::from_raw_parts(text.cast(), len)).into_owned();
+            sqlite3_free(text.cast());
+            Ok(string)
+        }
+    }
+
+    /// Finish the string and set it as the result of an SQL function.
+    ///
+    /// The buffer is passed to SQLite with `sqlite3_free` as its destructor
+    /// instead of being copied. After an error, the function fails with that
+    /// error instead.
+    ///
+    /// # Safety
+    ///
+    /// `ctx` must be the context passed to the function implementation.
+    pub unsafe fn result(self, ctx: *mut sqlite3_context) {
+        let this = ManuallyDrop::new(self);
+        let code = sqlite3_str_errcode(this.raw);
+        let len = sqlite3_str_length(this.raw);
+        let text = sqlite3_str_finish(this.raw);
+        match code {
+            SQLITE_OK if text.is_null() => result_static_text(ctx, ""),
+            SQLITE_OK => sqlite3_result_text64(
+                ctx,
+                text,
+                len as u64,
+                Some(sqlite3_free),
+                SQLITE_UTF8 as c_uchar,
+            ),
+            _ => {
+                sqlite3_free(text.cast());
+                match code {
+                    SQLITE_NOMEM => sqlite3_result_error_nomem(ctx),
+                    SQLITE_TOOBIG => sqlite3_result_error_toobig(ctx),
+                    _ => sqlite3_result_error_code(ctx, code),
+                }
+            }
+        }
+    }
+}
+
+impl Drop for StrBuilder {
+    fn drop(&mut self) {
+        unsafe { sqlite3_str_free(self.raw) };
+    }
+}
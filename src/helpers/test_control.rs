@@ -0,0 +1,38 @@
+use core::ffi::c_int;
+
+use super::{Result, SqliteError};
+use crate::{
+    sqlite3_test_control, SQLITE_MISUSE, SQLITE_TESTCTRL_ALWAYS, SQLITE_TESTCTRL_ASSERT,
+    SQLITE_TESTCTRL_EXTRA_SCHEMA_CHECKS, SQLITE_TESTCTRL_LOCALTIME_FAULT,
+    SQLITE_TESTCTRL_NEVER_CORRUPT, SQLITE_TESTCTRL_ONCE_RESET_THRESHOLD,
+};
+
+/// Call `sqlite3_test_control(op, arg)` for an `op` that takes a single `int`.
+///
+/// The supported ops are `SQLITE_TESTCTRL_ALWAYS`, `SQLITE_TESTCTRL_ASSERT`,
+/// `SQLITE_TESTCTRL_EXTRA_SCHEMA_CHECKS`, `SQLITE_TESTCTRL_LOCALTIME_FAULT`
+/// (with `arg` 0 or 1), `SQLITE_TESTCTRL_NEVER_CORRUPT` and
+/// `SQLITE_TESTCTRL_ONCE_RESET_THRESHOLD`. Any other op fails with
+/// `SQLITE_MISUSE` instead of reading arguments that were never passed.
+///
+/// Returns the value returned by `sqlite3_test_control`, whose meaning
+/// depends on `op`. The settings are global to the process, so tests that
+/// inject faults should restore them when done.
+pub fn test_control(op: c_int, arg: c_int) -> Result<c_int> {
+    match op {
+        SQLITE_TESTCTRL_ALWAYS
+        | SQLITE_TESTCTRL_ASSERT
+        | SQLITE_TESTCTRL_EXTRA_SCHEMA_CHECKS
+        | SQLITE_TESTCTRL_NEVER_CORRUPT
+        | SQLITE_TESTCTRL_ONCE_RESET_THRESHOLD => {}
+        // 2 additionally expects a localtime replacement function.
+        SQLITE_TESTCTRL_LOCALTIME_FAULT if arg == 0 || arg == 1 => {}
+        _ => {
+            return Err(SqliteError::new(
+                SQLITE_MISUSE,
+                alloc::format!("unsupported test control op {op} with argument {arg}"),
+            ))
+        }
+    }
+    Ok(unsafe { sqlite3_test_control(op, arg) })
+}
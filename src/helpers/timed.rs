@@ -0,0 +1,42 @@
+use alloc::vec::Vec;
+
+use js_sys::Date;
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::JsValue;
+
+use super::{execute_returning, IntoParams, Result, Row};
+use crate::sqlite3;
+
+#[wasm_bindgen]
+extern "C" {
+    // performance.now()
+    #[wasm_bindgen(js_namespace = ["globalThis", "performance"], js_name = now, catch)]
+    fn performance_now() -> core::result::Result<f64, JsValue>;
+}
+
+/// Milliseconds from a monotonic clock, or the wall clock without one.
+fn now() -> f64 {
+    performance_now().unwrap_or_else(|_| Date::now())
+}
+
+/// Run the first statement of `sql` like [`execute_returning`], and also
+/// return how long it took in milliseconds.
+///
+/// The time is measured with `performance.now()`, which is monotonic and
+/// has sub-millisecond resolution, and covers preparing, binding and
+/// stepping through all rows. Browsers coarsen the resolution to reduce
+/// timing attacks, so treat very short times as approximate. Falls back to
+/// `Date.now()` where `performance` is unavailable.
+///
+/// # Safety
+///
+/// `db` must be a valid database connection.
+pub unsafe fn timed_query(
+    db: *mut sqlite3,
+    sql: &str,
+    params: impl IntoParams,
+) -> Result<(Vec<Row>, f64)> {
+    let start = now();
+    let rows = execute_returning(db, sql, params)?;
+    Ok((rows, now() - start))
+}
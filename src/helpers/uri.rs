@@ -0,0 +1,31 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ffi::{c_int, CStr};
+
+use crate::{sqlite3_filename, sqlite3_uri_key};
+
+/// The key of the `n`th URI parameter of `filename`, counting from 0.
+///
+/// Wraps `sqlite3_uri_key`, returns `None` once `n` is past the last
+/// parameter.
+///
+/// # Safety
+///
+/// `filename` must be a filename passed by SQLite to `xOpen` of a VFS, or
+/// one returned by `sqlite3_db_filename`.
+pub unsafe fn uri_key(filename: sqlite3_filename, n: c_int) -> Option<String> {
+    let key = sqlite3_uri_key(filename, n);
+    (!key.is_null()).then(|| CStr::from_ptr(key).to_string_lossy().into_owned())
+}
+
+/// The keys of all URI parameters of `filename`, in the order of the URI.
+///
+/// Pass each key to `sqlite3_uri_parameter` to read its value.
+///
+/// # Safety
+///
+/// `filename` must be a filename passed by SQLite to `xOpen` of a VFS, or
+/// one returned by `sqlite3_db_filename`.
+pub unsafe fn uri_keys(filename: sqlite3_filename) -> Vec<String> {
+    (0..).map_while(|n| uri_key(filename, n)).collect()
+}
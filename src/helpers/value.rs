@@ -0,0 +1,262 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::{Result, SqliteError};
+use crate::{
+    sqlite3_value, sqlite3_value_blob, sqlite3_value_bytes, sqlite3_value_int64,
+    sqlite3_value_numeric_type, sqlite3_value_text, sqlite3_value_type, SQLITE_INTEGER,
+    SQLITE_MISMATCH, SQLITE_NULL,
+};
+
+/// An owned SQLite value, one variant per fundamental datatype.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// `NULL`.
+    Null,
+    /// A 64-bit signed integer.
+    Integer(i64),
+    /// A 64-bit IEEE floating point number.
+    Real(f64),
+    /// UTF-8 text.
+    Text(String),
+    /// A blob of bytes.
+    Blob(Vec<u8>),
+}
+
+/// The values of a result row, in column order.
+pub type Row = Vec<Value>;
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Integer(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Real(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::Text(value.into())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::Text(value)
+    }
+}
+
+impl From<Vec<u8>> for Value {
+    fn from(value: Vec<u8>) -> Self {
+        Value::Blob(value)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Null, Into::into)
+    }
+}
+
+/// Parameters that can be bound to a statement, in order, to `?1`, `?2`, ...
+///
+/// Implemented for slices, arrays and vectors of [`Value`], for `()` and for
+/// tuples of up to 8 elements that convert into a [`Value`], so that
+/// `(1, "a")` binds an integer and a text.
+pub trait IntoParams {
+    /// Convert into the values to bind.
+    fn into_params(self) -> Vec<Value>;
+}
+
+impl IntoParams for &[Value] {
+    fn into_params(self) -> Vec<Value> {
+        self.to_vec()
+    }
+}
+
+impl<const N: usize> IntoParams for &[Value; N] {
+    fn into_params(self) -> Vec<Value> {
+        self.to_vec()
+    }
+}
+
+impl<const N: usize> IntoParams for [Value; N] {
+    fn into_params(self) -> Vec<Value> {
+        self.into()
+    }
+}
+
+impl IntoParams for Vec<Value> {
+    fn into_params(self) -> Vec<Value> {
+        self
+    }
+}
+
+impl IntoParams for () {
+    fn into_params(self) -> Vec<Value> {
+        Vec::new()
+    }
+}
+
+macro_rules! impl_into_params_for_tuple {
+    ($($name:ident $idx:tt),+) => {
+        impl<$($name: Into<Value>),+> IntoParams for ($($name,)+) {
+            fn into_params(self) -> Vec<Value> {
+                alloc::vec![$(self.$idx.into()),+]
+            }
+        }
+    };
+}
+
+impl_into_params_for_tuple!(A 0);
+impl_into_params_for_tuple!(A 0, B 1);
+impl_into_params_for_tuple!(A 0, B 1, C 2);
+impl_into_params_for_tuple!(A 0, B 1, C 2, D 3);
+impl_into_params_for_tuple!(A 0, B 1, C 2, D 3, E 4);
+impl_into_params_for_tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_into_params_for_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_into_params_for_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+/// Types that can be read from a [`Value`].
+///
+/// Conversions follow the type of the value, not SQLite's type affinity:
+/// only `Integer` converts to `i64`, while `f64` accepts `Integer` and
+/// `Real`. Other combinations, including `NULL` into a non-`Option` type,
+/// fail with `SQLITE_MISMATCH`.
+pub trait FromValue: Sized {
+    /// Convert `value`, or fail with `SQLITE_MISMATCH`.
+    fn from_value(value: Value) -> Result<Self>;
+}
+
+fn mismatch(expected: &str, value: &Value) -> SqliteError {
+    SqliteError::new(
+        SQLITE_MISMATCH,
+        alloc::format!("expected {expected}, found {value:?}"),
+    )
+}
+
+impl FromValue for Value {
+    fn from_value(value: Value) -> Result<Self> {
+        Ok(value)
+    }
+}
+
+impl FromValue for i64 {
+    fn from_value(value: Value) -> Result<Self> {
+        match value {
+            Value::Integer(int) => Ok(int),
+            value => Err(mismatch("an integer", &value)),
+        }
+    }
+}
+
+impl FromValue for f64 {
+    fn from_value(value: Value) -> Result<Self> {
+        match value {
+            Value::Integer(int) => Ok(int as f64),
+            Value::Real(real) => Ok(real),
+            value => Err(mismatch("a number", &value)),
+        }
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: Value) -> Result<Self> {
+        match value {
+            Value::Text(text) => Ok(text),
+            value => Err(mismatch("text", &value)),
+        }
+    }
+}
+
+impl FromValue for Vec<u8> {
+    fn from_value(value: Value) -> Result<Self> {
+        match value {
+            Value::Blob(blob) => Ok(blob),
+            value => Err(mismatch("a blob", &value)),
+        }
+    }
+}
+
+impl<T: FromValue> FromValue for Option<T> {
+    fn from_value(value: Value) -> Result<Self> {
+        match value {
+            Value::Null => Ok(None),
+            value => T::from_value(value).map(Some),
+        }
+    }
+}
+
+/// Read an argument of an SQL function as an integer if it looks like one,
+/// and as text otherwise.
+///
+/// Text such as `'42'` or `' 9223372036854775807 '` is converted with
+/// numeric affinity, like a column of `INTEGER` type would, and returned as
+/// `Value::Integer`. Anything else, including numbers outside the `i64`
+/// range or with a fraction, is returned as `Value::Text` as given, reals
+/// and blobs in their `sqlite3_value_text` form. `NULL` stays `Value::Null`.
+///
+/// # Safety
+///
+/// `value` must be a valid value, e.g. an argument of the function.
+pub unsafe fn value_as_i64_or_text(value: *mut sqlite3_value) -> Value {
+    match sqlite3_value_type(value) {
+        SQLITE_NULL => return Value::Null,
+        SQLITE_INTEGER => return Value::Integer(sqlite3_value_int64(value)),
+        _ => (),
+    }
+
+    // Copy the text first, numeric affinity replaces it with the number.
+    let ptr = sqlite3_value_text(value);
+    let text = if ptr.is_null() {
+        String::new()
+    } else {
+        let len = sqlite3_value_bytes(value) as usize;
+        String::from_utf8_lossy(core::slice::from_raw_parts(ptr, len)).into_owned()
+    };
+    if sqlite3_value_numeric_type(value) == SQLITE_INTEGER {
+        Value::Integer(sqlite3_value_int64(value))
+    } else {
+        Value::Text(text)
+    }
+}
+
+/// Length of `value` in bytes, as UTF-8 for text.
+///
+/// Wraps `sqlite3_value_bytes`, so text is measured in UTF-8 bytes, not
+/// characters, even in a `UTF-16` database, e.g. `'héllo'` is 6. This is
+/// the length that goes with `sqlite3_value_text` and `sqlite3_value_blob`.
+/// Numbers are measured by their text form, which SQLite converts them to
+/// as a side effect, and `NULL` is 0.
+///
+/// # Safety
+///
+/// `value` must be a valid value, e.g. an argument of the function.
+pub unsafe fn value_len(value: *mut sqlite3_value) -> usize {
+    sqlite3_value_bytes(value) as usize
+}
+
+/// Bytes of `value`, as UTF-8 for text and as given for blobs.
+///
+/// Other types are converted to their text representation first, `NULL`
+/// and empty values are an empty slice. Calls `sqlite3_value_blob` before
+/// `sqlite3_value_bytes`, so that the length matches the representation
+/// that was returned.
+///
+/// # Safety
+///
+/// `value` must be a valid value, e.g. an argument of the function, and the
+/// slice must not be used after `value` is changed or freed.
+pub unsafe fn value_bytes<'a>(value: *mut sqlite3_value) -> &'a [u8] {
+    let ptr = sqlite3_value_blob(value);
+    let len = sqlite3_value_bytes(value);
+    if ptr.is_null() || len <= 0 {
+        &[]
+    } else {
+        core::slice::from_raw_parts(ptr.cast(), len as usize)
+    }
+}
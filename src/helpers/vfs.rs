@@ -0,0 +1,116 @@
+use alloc::string::String;
+use alloc::vec;
+use core::ffi::{c_int, CStr};
+use core::ptr;
+
+use super::{Result, SqliteError};
+use crate::{
+    sqlite3_file, sqlite3_vfs_find, SQLITE_ACCESS_EXISTS, SQLITE_CANTOPEN, SQLITE_ERROR,
+    SQLITE_MISUSE, SQLITE_OK, SQLITE_OPEN_MAIN_DB, SQLITE_OPEN_READWRITE, SQLITE_SYNC_NORMAL,
+};
+
+/// The name of the default VFS, used when a database is opened without one.
+///
+/// Installing a VFS with `default_vfs` set replaces the default, so after
+/// installing several this tells which one new connections end up on.
+/// Returns `None` if no VFS is registered.
+pub fn default_vfs_name() -> Option<String> {
+    unsafe {
+        let vfs = sqlite3_vfs_find(ptr::null());
+        if vfs.is_null() || (*vfs).zName.is_null() {
+            return None;
+        }
+        Some(CStr::from_ptr((*vfs).zName).to_string_lossy().into_owned())
+    }
+}
+
+/// Zero the database file `name` of the VFS `vfs`, so the next connection
+/// opening it finds an empty database.
+///
+/// Goes straight through the VFS: the file is opened with `xOpen`, cut to
+/// zero bytes with `xTruncate` and synced, and a leftover `-journal` or
+/// `-wal` file is deleted, so no table has to be dropped first. This works
+/// for any VFS, e.g. sahpool keeps the slot of the file but empties it and
+/// the memory VFS drops its chunks. Fails with `SQLITE_CANTOPEN` if the file
+/// does not exist.
+///
+/// # Safety
+///
+/// No connection may have the database open.
+pub unsafe fn truncate_database(vfs: &CStr, name: &CStr) -> Result<()> {
+    let vfs = sqlite3_vfs_find(vfs.as_ptr());
+    if vfs.is_null() {
+        return Err(SqliteError::new(SQLITE_ERROR, "no such vfs"));
+    }
+    let (Some(open), Some(full_pathname), Some(access), Some(delete)) = (
+        (*vfs).xOpen,
+        (*vfs).xFullPathname,
+        (*vfs).xAccess,
+        (*vfs).xDelete,
+    ) else {
+        return Err(SqliteError::from_code(SQLITE_MISUSE));
+    };
+
+    let mut path = vec![0u8; (*vfs).mxPathname as usize + 1];
+    let ret = full_pathname(
+        vfs,
+        name.as_ptr(),
+        path.len() as c_int,
+        path.as_mut_ptr().cast(),
+    );
+    if ret != SQLITE_OK {
+        return Err(SqliteError::from_code(ret));
+    }
+    let len = path
+        .iter()
+        .position(|&b| b == 0)
+        .ok_or_else(|| SqliteError::from_code(SQLITE_CANTOPEN))?;
+    path.truncate(len + 1);
+
+    // `u64` keeps the file object 8-byte aligned.
+    let mut buf = vec![0u64; ((*vfs).szOsFile as usize).div_ceil(8)];
+    let file = buf.as_mut_ptr().cast::<sqlite3_file>();
+    let mut out_flags = 0;
+    let mut ret = open(
+        vfs,
+        path.as_ptr().cast(),
+        file,
+        SQLITE_OPEN_READWRITE | SQLITE_OPEN_MAIN_DB,
+        &mut out_flags,
+    );
+    let methods = (*file).pMethods;
+    if ret == SQLITE_OK {
+        if let Some(truncate) = (*methods).xTruncate {
+            ret = truncate(file, 0);
+        }
+        if let (SQLITE_OK, Some(sync)) = (ret, (*methods).xSync) {
+            ret = sync(file, SQLITE_SYNC_NORMAL);
+        }
+    }
+    // `xClose` has to be called whenever `pMethods` is set, even on failure.
+    if let Some(close) = methods.as_ref().and_then(|methods| methods.xClose) {
+        close(file);
+    }
+    if ret != SQLITE_OK {
+        return Err(SqliteError::from_code(ret));
+    }
+
+    for suffix in [c"-journal", c"-wal"] {
+        let mut sibling = path[..len].to_vec();
+        sibling.extend_from_slice(suffix.to_bytes_with_nul());
+        let mut exists = 0;
+        let ret = access(
+            vfs,
+            sibling.as_ptr().cast(),
+            SQLITE_ACCESS_EXISTS,
+            &mut exists,
+        );
+        if ret == SQLITE_OK && exists != 0 {
+            let ret = delete(vfs, sibling.as_ptr().cast(), 0);
+            if ret != SQLITE_OK {
+                return Err(SqliteError::from_code(ret));
+            }
+        }
+    }
+    Ok(())
+}
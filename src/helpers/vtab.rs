@@ -0,0 +1,53 @@
+use core::ffi::c_int;
+
+use super::{Result, SqliteError};
+use crate::{
+    sqlite3, sqlite3_vtab_config, SQLITE_OK, SQLITE_VTAB_CONSTRAINT_SUPPORT,
+    SQLITE_VTAB_DIRECTONLY, SQLITE_VTAB_INNOCUOUS, SQLITE_VTAB_USES_ALL_SCHEMAS,
+};
+
+/// A property of a virtual table, declared with [`vtab_config`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VtabConfig {
+    /// `SQLITE_VTAB_CONSTRAINT_SUPPORT`: whether xUpdate honors the
+    /// `ON CONFLICT` mode of the statement, so SQLite doesn't have to abort
+    /// the whole statement when a constraint fails.
+    ConstraintSupport(bool),
+    /// `SQLITE_VTAB_INNOCUOUS`: the table has no side effects and exposes
+    /// nothing the schema shouldn't see, so it may be used from triggers
+    /// and views even with trusted schema off.
+    Innocuous,
+    /// `SQLITE_VTAB_DIRECTONLY`: the table may only be used from top-level
+    /// SQL, never from triggers or views.
+    DirectOnly,
+    /// `SQLITE_VTAB_USES_ALL_SCHEMAS`: the table reads every attached
+    /// schema, so a query on it takes a read transaction on all of them.
+    UsesAllSchemas,
+}
+
+/// Declare a safety or behavior property of the virtual table being
+/// connected.
+///
+/// A statement using a table marked [`VtabConfig::DirectOnly`] from a
+/// trigger or view fails with `SQLITE_ERROR` and an "unsafe use of virtual
+/// table" message.
+///
+/// # Safety
+///
+/// `db` must be the connection passed to the `xCreate` or `xConnect` method
+/// currently running; SQLite rejects the call with `SQLITE_MISUSE` anywhere
+/// else.
+pub unsafe fn vtab_config(db: *mut sqlite3, config: VtabConfig) -> Result<()> {
+    let ret = match config {
+        VtabConfig::ConstraintSupport(enabled) => {
+            sqlite3_vtab_config(db, SQLITE_VTAB_CONSTRAINT_SUPPORT, c_int::from(enabled))
+        }
+        VtabConfig::Innocuous => sqlite3_vtab_config(db, SQLITE_VTAB_INNOCUOUS),
+        VtabConfig::DirectOnly => sqlite3_vtab_config(db, SQLITE_VTAB_DIRECTONLY),
+        VtabConfig::UsesAllSchemas => sqlite3_vtab_config(db, SQLITE_VTAB_USES_ALL_SCHEMAS),
+    };
+    if ret != SQLITE_OK {
+        return Err(SqliteError::from_code(ret));
+    }
+    Ok(())
+}
@@ -18,9 +18,9 @@ pub mod utils {
     #[doc(inline)]
     pub use rsqlite_vfs::{
         bail, check_db_and_page_size, check_import_db, check_option, check_result, random_name,
-        register_vfs, registered_vfs, ImportDbError, MemChunksFile, OsCallback, RegisterVfsError,
-        SQLiteIoMethods, SQLiteVfs, SQLiteVfsFile, VfsAppData, VfsError, VfsFile, VfsResult,
-        VfsStore, SQLITE3_HEADER,
+        register_vfs, registered_vfs, try_sqlite, ImportDbError, MemChunksFile, OsCallback,
+        RegisterVfsError, SQLiteIoMethods, SQLiteVfs, SQLiteVfsFile, ScopedVfs, VfsAppData,
+        VfsError, VfsFile, VfsResult, VfsStore, SQLITE3_HEADER,
     };
 
     pub use rsqlite_vfs::ffi;
@@ -29,8 +29,11 @@ pub mod utils {
     pub use rsqlite_vfs::test_suite;
 }
 
+/// Helpers built on top of the raw bindings.
+pub mod helpers;
+
 #[doc(inline)]
-pub use self::utils::{bail, check_option, check_result};
+pub use self::utils::{bail, check_option, check_result, try_sqlite};
 
 /// Raw C-style bindings to the underlying `libsqlite3` library.
 pub use bindings::*;
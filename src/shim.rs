@@ -2,6 +2,7 @@
 
 use core::ffi::{c_char, c_int, c_long, c_longlong, c_void};
 use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
 use core::time::Duration;
 
 use js_sys::{Date, Math, Number};
@@ -9,8 +10,36 @@ use rsqlite_vfs::OsCallback;
 use wasm_bindgen::prelude::wasm_bindgen;
 use wasm_bindgen::JsValue;
 
+#[cfg(bundled_sqlite)]
+use crate::bindings::sqlite3_mutex;
+
 pub struct WasmOsCallback;
 
+/// Entropy source set with [`WasmOsCallback::set_random_source`], null for the default.
+static RANDOM_SOURCE: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+impl WasmOsCallback {
+    /// Fill random buffers with `source` instead of `crypto.getRandomValues`.
+    ///
+    /// Applies to `xRandomness` of every vfs installed with `WasmOsCallback`,
+    /// which seeds the PRNG of SQLite behind `random()`, `randomblob()` and
+    /// temporary file names, to the random file names of the vfs crates and
+    /// to `getentropy` of the C code. SQLite seeds its PRNG only once, so set
+    /// the source before the first connection is opened. `None` restores the
+    /// default.
+    pub fn set_random_source(source: Option<fn(&mut [u8])>) {
+        let source = source.map_or(ptr::null_mut(), |source| source as *mut ());
+        RANDOM_SOURCE.store(source, Ordering::Release);
+    }
+
+    fn random_source() -> Option<fn(&mut [u8])> {
+        let source = RANDOM_SOURCE.load(Ordering::Acquire);
+        // Only ever set from a `fn(&mut [u8])` above.
+        (!source.is_null())
+            .then(|| unsafe { core::mem::transmute::<*mut (), fn(&mut [u8])>(source) })
+    }
+}
+
 impl OsCallback for WasmOsCallback {
     /// thread::sleep is available when atomics is enabled
     #[cfg(target_feature = "atomics")]
@@ -30,6 +59,11 @@ impl OsCallback for WasmOsCallback {
     fn sleep(_dur: Duration) {}
 
     fn random(buf: &mut [u8]) {
+        if let Some(source) = Self::random_source() {
+            source(buf);
+            return;
+        }
+
         fn fallback(buf: &mut [u8]) {
             // Non-cryptographic fallback when crypto.getRandomValues is unavailable.
             for b in buf {
@@ -143,6 +177,11 @@ pub unsafe extern "C" fn rust_sqlite_wasm_getentropy(
     // https://github.com/WebAssembly/wasi-libc/blob/e9524a0980b9bb6bb92e87a41ed1055bdda5bb86/libc-bottom-half/headers/public/wasi/api.h#L373
     const FUNCTION_NOT_SUPPORT: core::ffi::c_ushort = 52;
 
+    if let Some(source) = WasmOsCallback::random_source() {
+        source(core::slice::from_raw_parts_mut(buf, buf_len));
+        return 0;
+    }
+
     #[cfg(target_feature = "atomics")]
     {
         let array = js_sys::Uint8Array::new_with_length(buf_len as u32);
@@ -256,6 +295,49 @@ pub unsafe extern "C" fn rust_sqlite_wasm_calloc(num: c_size_t, size: c_size_t)
     ptr.cast()
 }
 
+// SQLite is built with `SQLITE_THREADSAFE=0`, which turns the mutex API into
+// macros that never reach the library, so the symbols are missing. They are
+// provided here as no-ops for code ported from native SQLite. Every mutex is
+// a null pointer, which the real implementation also accepts and ignores.
+// A prebuilt `libsqlite3.a` overrides the build script, so `bundled_sqlite`
+// is not set and the library's own mutex functions are used instead.
+
+#[cfg(bundled_sqlite)]
+#[no_mangle]
+pub unsafe extern "C" fn sqlite3_mutex_alloc(_id: c_int) -> *mut sqlite3_mutex {
+    ptr::null_mut()
+}
+
+#[cfg(bundled_sqlite)]
+#[no_mangle]
+pub unsafe extern "C" fn sqlite3_mutex_free(_mutex: *mut sqlite3_mutex) {}
+
+#[cfg(bundled_sqlite)]
+#[no_mangle]
+pub unsafe extern "C" fn sqlite3_mutex_enter(_mutex: *mut sqlite3_mutex) {}
+
+#[cfg(bundled_sqlite)]
+#[no_mangle]
+pub unsafe extern "C" fn sqlite3_mutex_try(_mutex: *mut sqlite3_mutex) -> c_int {
+    crate::bindings::SQLITE_OK
+}
+
+#[cfg(bundled_sqlite)]
+#[no_mangle]
+pub unsafe extern "C" fn sqlite3_mutex_leave(_mutex: *mut sqlite3_mutex) {}
+
+#[cfg(bundled_sqlite)]
+#[no_mangle]
+pub unsafe extern "C" fn sqlite3_mutex_held(_mutex: *mut sqlite3_mutex) -> c_int {
+    1
+}
+
+#[cfg(bundled_sqlite)]
+#[no_mangle]
+pub unsafe extern "C" fn sqlite3_mutex_notheld(_mutex: *mut sqlite3_mutex) -> c_int {
+    1
+}
+
 /// SQLite OS initialization entry point.
 ///
 /// This function is called by SQLite when it is initialized. It sets up the
@@ -284,7 +366,7 @@ mod tests {
     use crate::{
         sqlite3_column_count, sqlite3_column_name, sqlite3_column_text, sqlite3_column_type,
         sqlite3_initialize, sqlite3_open, sqlite3_prepare_v3, sqlite3_shutdown, sqlite3_step,
-        SQLITE_OK, SQLITE_ROW, SQLITE_TEXT,
+        sqlite3_vfs_find, SQLITE_OK, SQLITE_ROW, SQLITE_TEXT,
     };
 
     use wasm_bindgen_test::{console_log, wasm_bindgen_test};
@@ -304,6 +386,35 @@ mod tests {
         console_log!("test_random_get: {buf:?}");
     }
 
+    #[wasm_bindgen_test]
+    fn test_random_source() {
+        fn counter(buf: &mut [u8]) {
+            static NEXT: core::sync::atomic::AtomicU8 = core::sync::atomic::AtomicU8::new(0);
+            for b in buf {
+                *b = NEXT.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        unsafe {
+            assert_eq!(sqlite3_initialize(), SQLITE_OK, "failed to initialize");
+            let vfs = sqlite3_vfs_find(c"memvfs".as_ptr());
+            assert!(!vfs.is_null());
+            let randomness = (*vfs).xRandomness.unwrap();
+
+            WasmOsCallback::set_random_source(Some(counter));
+            let (mut first, mut second) = ([0u8; 16], [0u8; 16]);
+            randomness(vfs, 16, first.as_mut_ptr().cast());
+            randomness(vfs, 16, second.as_mut_ptr().cast());
+            let mut entropy = [0u8; 4];
+            assert_eq!(0, rust_sqlite_wasm_getentropy(entropy.as_mut_ptr(), 4));
+            WasmOsCallback::set_random_source(None);
+
+            assert_ne!(first, second);
+            assert_eq!(first[0].wrapping_add(16), second[0]);
+            assert_eq!(second[0].wrapping_add(16), entropy[0]);
+        }
+    }
+
     #[wasm_bindgen_test]
     fn test_memory() {
         unsafe {
@@ -380,4 +491,36 @@ mod tests {
         assert_eq!(tm.tm_wday, 4);
         assert_eq!(tm.tm_yday, 346);
     }
+
+    #[cfg(bundled_sqlite)]
+    #[wasm_bindgen_test]
+    fn test_mutex_noop() {
+        use crate::{sqlite3_close, sqlite3_db_mutex, sqlite3_exec, SQLITE_MUTEX_FAST};
+
+        unsafe {
+            let mut db = core::ptr::null_mut();
+            let ret = sqlite3_open(c":memory:".as_ptr().cast(), &mut db as *mut _);
+            assert_eq!(ret, SQLITE_OK);
+
+            let mutex = sqlite3_db_mutex(db);
+            assert!(mutex.is_null());
+            assert!(sqlite3_mutex_alloc(SQLITE_MUTEX_FAST).is_null());
+
+            sqlite3_mutex_enter(mutex);
+            assert_eq!(sqlite3_mutex_try(mutex), SQLITE_OK);
+            let ret = sqlite3_exec(
+                db,
+                c"CREATE TABLE t (x)".as_ptr().cast(),
+                None,
+                core::ptr::null_mut(),
+                core::ptr::null_mut(),
+            );
+            assert_eq!(ret, SQLITE_OK);
+            sqlite3_mutex_leave(mutex);
+            sqlite3_mutex_leave(mutex);
+            sqlite3_mutex_free(mutex);
+
+            assert_eq!(sqlite3_close(db), SQLITE_OK);
+        }
+    }
 }
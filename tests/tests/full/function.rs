@@ -0,0 +1,77 @@
+use sqlite_wasm_rs::helpers::{execute_returning, Value};
+use sqlite_wasm_rs::*;
+use std::ffi::{c_int, CStr};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+/// `join_ws(sep, ...)` joins the non-NULL arguments after `sep` with it.
+unsafe extern "C" fn join_ws(
+    ctx: *mut sqlite3_context,
+    argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    if argc < 1 {
+        sqlite3_result_error(ctx, c"join_ws: missing separator".as_ptr(), -1);
+        return;
+    }
+    let args = std::slice::from_raw_parts(argv, argc as usize);
+    let text = |value| {
+        let text = sqlite3_value_text(value);
+        (!text.is_null()).then(|| CStr::from_ptr(text.cast()).to_str().unwrap().to_string())
+    };
+    let Some(sep) = text(args[0]) else {
+        sqlite3_result_null(ctx);
+        return;
+    };
+    let joined = args[1..]
+        .iter()
+        .filter_map(|&value| text(value))
+        .collect::<Vec<_>>()
+        .join(&sep);
+    sqlite3_result_text(
+        ctx,
+        joined.as_ptr().cast(),
+        joined.len() as c_int,
+        SQLITE_TRANSIENT(),
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_variadic_function() {
+    let mut db = std::ptr::null_mut();
+    unsafe {
+        let ret = sqlite3_open_v2(
+            c":memory:".as_ptr(),
+            &mut db as *mut _,
+            SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+            std::ptr::null(),
+        );
+        assert_eq!(SQLITE_OK, ret);
+        let ret = sqlite3_create_function_v2(
+            db,
+            c"join_ws".as_ptr(),
+            -1,
+            SQLITE_UTF8 | SQLITE_DETERMINISTIC,
+            std::ptr::null_mut(),
+            Some(join_ws),
+            None,
+            None,
+            None,
+        );
+        assert_eq!(SQLITE_OK, ret);
+    }
+
+    let query = |sql| unsafe { execute_returning(db, sql, &[]) };
+    let text = |value: &str| Ok(vec![vec![Value::Text(value.into())]]);
+    assert_eq!(text("a"), query("SELECT join_ws('-', 'a')"));
+    assert_eq!(
+        text("a-b-3"),
+        query("SELECT join_ws('-', 'a', 'b', NULL, 3)")
+    );
+    assert_eq!(text(""), query("SELECT join_ws(', ')"));
+    assert_eq!(
+        "join_ws: missing separator",
+        query("SELECT join_ws()").unwrap_err().message
+    );
+
+    unsafe { sqlite3_close(db) };
+}
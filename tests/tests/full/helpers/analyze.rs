@@ -0,0 +1,34 @@
+use sqlite_wasm_rs::helpers::{analyze, analyze_stats};
+use sqlite_wasm_rs::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::{exec, memory_db};
+
+#[wasm_bindgen_test]
+fn test_analyze() {
+    let db = memory_db();
+    assert!(unsafe { analyze_stats(db) }.unwrap().is_empty());
+
+    exec(
+        db,
+        c"CREATE TABLE events (id INTEGER PRIMARY KEY, kind TEXT);
+        CREATE INDEX events_kind ON events (kind);
+        CREATE TABLE plain (v);
+        WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n WHERE x < 100)
+        INSERT INTO events (kind) SELECT 'kind' || (x % 4) FROM n;
+        INSERT INTO plain VALUES (1), (2);",
+    );
+    unsafe { analyze(db) }.unwrap();
+
+    let mut stats = unsafe { analyze_stats(db) }.unwrap();
+    stats.sort();
+    assert_eq!(
+        stats,
+        [
+            ("events".into(), "events_kind".into(), "100 25".into()),
+            ("plain".into(), String::new(), "2".into()),
+        ]
+    );
+
+    unsafe { sqlite3_close(db) };
+}
@@ -0,0 +1,78 @@
+use sqlite_wasm_rs::helpers::{attach_memory, db_name, execute_returning, Attached};
+use sqlite_wasm_rs::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::{exec, memory_db, table_rows};
+
+#[wasm_bindgen_test]
+fn test_attach_memory() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE items (id INTEGER PRIMARY KEY, price INT);
+        INSERT INTO items (price) VALUES (10), (25), (40);",
+    );
+
+    unsafe { attach_memory(db, "scratch") }.unwrap();
+    assert_eq!(Some("scratch".to_string()), unsafe { db_name(db, 2) });
+
+    exec(
+        db,
+        c"CREATE TABLE scratch.expensive (id INTEGER);
+        INSERT INTO scratch.expensive SELECT id FROM main.items WHERE price > 20;
+        CREATE TABLE main.result AS
+            SELECT items.id, items.price FROM main.items
+            JOIN scratch.expensive USING (id);",
+    );
+    assert_eq!(table_rows(db, "result"), ["1:2,1:25", "1:3,1:40"]);
+    assert_eq!(table_rows(db, "scratch.expensive"), ["1:2", "1:3"]);
+
+    // The alias is already in use.
+    assert!(unsafe { attach_memory(db, "scratch") }.is_err());
+
+    unsafe { sqlite3_close(db) };
+}
+
+#[wasm_bindgen_test]
+fn test_attached() {
+    let db = memory_db();
+    {
+        let scratch = unsafe { Attached::new(db, ":memory:", "scratch") }.unwrap();
+        assert_eq!("scratch", scratch.schema_alias());
+        exec(
+            db,
+            c"CREATE TABLE scratch.t (v); INSERT INTO scratch.t VALUES (1), (2);",
+        );
+        assert_eq!(table_rows(db, "scratch.t"), ["1:1", "1:2"]);
+    }
+    assert_eq!(None, unsafe { db_name(db, 2) });
+    let err = unsafe { execute_returning(db, "SELECT * FROM scratch.t", &[]) }.unwrap_err();
+    assert_eq!("no such table: scratch.t", err.message);
+
+    // Detaching fails while a statement still reads from the schema.
+    let scratch = unsafe { Attached::new(db, ":memory:", "scratch") }.unwrap();
+    exec(
+        db,
+        c"CREATE TABLE scratch.t (v); INSERT INTO scratch.t VALUES (1);",
+    );
+    let mut stmt = std::ptr::null_mut();
+    unsafe {
+        let ret = sqlite3_prepare_v3(
+            db,
+            c"SELECT * FROM scratch.t".as_ptr(),
+            -1,
+            0,
+            &mut stmt as *mut _,
+            std::ptr::null_mut(),
+        );
+        assert_eq!(SQLITE_OK, ret);
+        assert_eq!(SQLITE_ROW, sqlite3_step(stmt));
+    }
+    assert!(scratch.detach().is_err());
+    assert_eq!(Some("scratch".to_string()), unsafe { db_name(db, 2) });
+
+    unsafe {
+        sqlite3_finalize(stmt);
+        sqlite3_close(db);
+    }
+}
@@ -0,0 +1,80 @@
+use sqlite_wasm_rs::helpers::{cancel_auto_extension, get_table, register_auto_extension};
+use sqlite_wasm_rs::*;
+use std::ffi::{c_char, c_int};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::memory_db;
+
+/// `uuid()`, a random version 4 UUID.
+unsafe extern "C" fn x_uuid(
+    ctx: *mut sqlite3_context,
+    _argc: c_int,
+    _argv: *mut *mut sqlite3_value,
+) {
+    let mut bytes = [0u8; 16];
+    sqlite3_randomness(bytes.len() as c_int, bytes.as_mut_ptr().cast());
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let mut text = String::new();
+    for (idx, byte) in bytes.iter().enumerate() {
+        if matches!(idx, 4 | 6 | 8 | 10) {
+            text.push('-');
+        }
+        text.push_str(&format!("{byte:02x}"));
+    }
+    sqlite3_result_text64(
+        ctx,
+        text.as_ptr().cast(),
+        text.len() as u64,
+        SQLITE_TRANSIENT(),
+        SQLITE_UTF8 as u8,
+    );
+}
+
+unsafe extern "C" fn uuid_init(
+    db: *mut sqlite3,
+    _pz_err_msg: *mut *mut c_char,
+    _p_api: *const sqlite3_api_routines,
+) -> c_int {
+    sqlite3_create_function_v2(
+        db,
+        c"uuid".as_ptr(),
+        0,
+        SQLITE_UTF8,
+        std::ptr::null_mut(),
+        Some(x_uuid),
+        None,
+        None,
+        None,
+    )
+}
+
+#[wasm_bindgen_test]
+fn test_auto_extension() {
+    unsafe {
+        register_auto_extension(uuid_init).unwrap();
+
+        let db = memory_db();
+        let (_, rows) = get_table(db, "SELECT uuid(), uuid()").unwrap();
+        let first = rows[0][0].clone().unwrap();
+        assert_eq!(36, first.len());
+        assert_eq!(Some('4'), first.chars().nth(14));
+        assert_ne!(rows[0][0], rows[0][1]);
+
+        assert!(cancel_auto_extension(uuid_init));
+        assert!(!cancel_auto_extension(uuid_init));
+
+        // Connections opened before the cancel keep the function.
+        assert!(get_table(db, "SELECT uuid()").is_ok());
+        let new_db = memory_db();
+        let err = get_table(new_db, "SELECT uuid()").unwrap_err();
+        assert!(
+            err.message.contains("no such function: uuid"),
+            "{}",
+            err.message
+        );
+
+        sqlite3_close(db);
+        sqlite3_close(new_db);
+    }
+}
@@ -0,0 +1,44 @@
+use sqlite_wasm_rs::helpers::{execute_returning, key, rekey};
+use sqlite_wasm_rs::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::{exec, table_rows};
+
+fn open_keyed(secret: &[u8]) -> *mut sqlite3 {
+    let mut db = std::ptr::null_mut();
+    let ret = unsafe {
+        sqlite3_open_v2(
+            c"test_helpers_key.db".as_ptr(),
+            &mut db as *mut _,
+            SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+            c"multipleciphers-memvfs".as_ptr(),
+        )
+    };
+    assert_eq!(SQLITE_OK, ret);
+    unsafe { key(db, c"main", secret) }.unwrap();
+    db
+}
+
+#[wasm_bindgen_test]
+fn test_key_rekey() {
+    let db = open_keyed(b"first secret");
+    exec(db, c"CREATE TABLE t (v); INSERT INTO t VALUES ('hidden');");
+    unsafe { sqlite3_close(db) };
+
+    // The wrong key is noticed on the first read.
+    let db = open_keyed(b"wrong secret");
+    let err = unsafe { execute_returning(db, "SELECT * FROM t", &[]) }.unwrap_err();
+    assert_eq!(SQLITE_NOTADB, err.extended_code);
+    unsafe { sqlite3_close(db) };
+
+    let db = open_keyed(b"first secret");
+    assert_eq!(table_rows(db, "t"), ["3:hidden"]);
+    unsafe { rekey(db, c"main", b"second secret") }.unwrap();
+    unsafe { sqlite3_close(db) };
+
+    let db = open_keyed(b"second secret");
+    assert_eq!(table_rows(db, "t"), ["3:hidden"]);
+    unsafe { sqlite3_close(db) };
+
+    MemVfsUtil::<WasmOsCallback>::new().delete_db("test_helpers_key.db");
+}
@@ -0,0 +1,76 @@
+use sqlite_wasm_rs::helpers::{config_lookaside, config_memstatus, default_vfs_name};
+use sqlite_wasm_rs::*;
+use std::ffi::CString;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::{exec, memory_db};
+
+/// Run `f` with SQLite shut down and initialize it again, restoring the
+/// default VFS that `sqlite3_os_init` replaces with the memory VFS.
+unsafe fn reconfigure(f: impl FnOnce()) {
+    let default = CString::new(default_vfs_name().unwrap()).unwrap();
+    assert_eq!(SQLITE_OK, sqlite3_shutdown());
+    f();
+    assert_eq!(SQLITE_OK, sqlite3_initialize());
+    let vfs = sqlite3_vfs_find(default.as_ptr());
+    assert!(!vfs.is_null());
+    assert_eq!(SQLITE_OK, sqlite3_vfs_register(vfs, 1));
+}
+
+unsafe fn lookaside_highwater(db: *mut sqlite3) -> i32 {
+    let (mut current, mut highwater) = (0, 0);
+    let ret = sqlite3_db_status(
+        db,
+        SQLITE_DBSTATUS_LOOKASIDE_USED,
+        &mut current,
+        &mut highwater,
+        0,
+    );
+    assert_eq!(SQLITE_OK, ret);
+    highwater
+}
+
+#[wasm_bindgen_test]
+fn test_config_memstatus() {
+    unsafe {
+        // Options can't change while SQLite is initialized.
+        assert_eq!(SQLITE_OK, sqlite3_initialize());
+        assert_eq!(
+            SQLITE_MISUSE,
+            config_memstatus(false).unwrap_err().extended_code
+        );
+
+        // Without accounting the usage is no longer updated.
+        reconfigure(|| config_memstatus(false).unwrap());
+        let used = sqlite3_memory_used();
+        let buf = sqlite3_malloc64(1 << 20);
+        assert!(!buf.is_null());
+        assert_eq!(used, sqlite3_memory_used());
+        sqlite3_free(buf);
+
+        reconfigure(|| config_memstatus(true).unwrap());
+        let used = sqlite3_memory_used();
+        let buf = sqlite3_malloc64(1 << 20);
+        assert!(!buf.is_null());
+        assert!(sqlite3_memory_used() >= used + (1 << 20));
+        sqlite3_free(buf);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_config_lookaside() {
+    unsafe {
+        reconfigure(|| config_lookaside(0, 0).unwrap());
+        let db = memory_db();
+        exec(db, c"CREATE TABLE t (x); SELECT * FROM t;");
+        assert_eq!(0, lookaside_highwater(db));
+        sqlite3_close(db);
+
+        // Back to the SQLite defaults.
+        reconfigure(|| config_lookaside(1200, 40).unwrap());
+        let db = memory_db();
+        exec(db, c"CREATE TABLE t (x); SELECT * FROM t;");
+        assert!(lookaside_highwater(db) > 0);
+        sqlite3_close(db);
+    }
+}
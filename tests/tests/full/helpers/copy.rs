@@ -0,0 +1,85 @@
+use sqlite_wasm_rs::helpers::{copy_table, execute_returning, export_tables, Value};
+use sqlite_wasm_rs::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::{exec, load_db, memory_db, table_rows};
+
+pub fn prepare_copy_source() -> *mut sqlite3 {
+    let src = memory_db();
+    exec(
+        src,
+        cr#"CREATE TABLE "my table" (id INTEGER PRIMARY KEY, name TEXT, score REAL, data BLOB, twice INT AS (id * 2));
+        INSERT INTO "my table" (name, score, data) VALUES ('Alice', 1.5, x'0001'), (NULL, 2, NULL), ('Bob', NULL, x'');"#,
+    );
+    src
+}
+
+#[wasm_bindgen_test]
+fn test_copy_table() {
+    let src = prepare_copy_source();
+    let dst = memory_db();
+
+    let rows = unsafe { copy_table(src, dst, "my table") }.unwrap();
+    assert_eq!(3, rows);
+    assert_eq!(
+        table_rows(src, "\"my table\""),
+        table_rows(dst, "\"my table\"")
+    );
+
+    // The table now exists, copying again fails on the primary key and rolls back.
+    let err = unsafe { copy_table(src, dst, "my table") }.unwrap_err();
+    assert_eq!(SQLITE_CONSTRAINT_PRIMARYKEY, err.extended_code);
+    assert_eq!(3, table_rows(dst, "\"my table\"").len());
+
+    let err = unsafe { copy_table(src, dst, "missing") }.unwrap_err();
+    assert_eq!("no such table: missing", err.message);
+
+    unsafe {
+        sqlite3_close(src);
+        sqlite3_close(dst);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_export_tables() {
+    let src = prepare_copy_source();
+    exec(
+        src,
+        c"CREATE TABLE a (id INTEGER PRIMARY KEY, v TEXT);
+        CREATE INDEX a_v ON a (v);
+        CREATE TABLE b (x);
+        CREATE VIEW a_view AS SELECT v FROM a;
+        INSERT INTO a (v) VALUES ('one'), ('two');
+        INSERT INTO b VALUES (1);",
+    );
+
+    let bytes = unsafe { export_tables(src, &["my table", "a"]) }.unwrap();
+    let copy = load_db(&bytes);
+    for table in ["\"my table\"", "a"] {
+        assert_eq!(table_rows(src, table), table_rows(copy, table), "{table}");
+    }
+    let schema = unsafe {
+        execute_returning(
+            copy,
+            "SELECT name FROM sqlite_schema ORDER BY type, name",
+            &[],
+        )
+    }
+    .unwrap();
+    assert_eq!(
+        schema.concat(),
+        [
+            Value::Text("a_v".into()),
+            Value::Text("a".into()),
+            Value::Text("my table".into())
+        ]
+    );
+
+    let err = unsafe { export_tables(src, &["a", "missing"]) }.unwrap_err();
+    assert_eq!("no such table: missing", err.message);
+
+    unsafe {
+        sqlite3_close(src);
+        sqlite3_close(copy);
+    }
+}
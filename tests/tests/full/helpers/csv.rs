@@ -0,0 +1,35 @@
+use sqlite_wasm_rs::helpers::export_csv;
+use sqlite_wasm_rs::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::{exec, memory_db};
+
+#[wasm_bindgen_test]
+fn test_export_csv() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE \"my table\" (id INTEGER PRIMARY KEY, \"name, full\" TEXT, score REAL, data BLOB);
+        INSERT INTO \"my table\" VALUES
+            (1, 'plain', 1.5, x'00ff'),
+            (2, 'comma, inside', NULL, NULL),
+            (3, 'say \"hi\"', -2.0, x''),
+            (4, 'line
+break', 0.0, NULL),
+            (5, '', NULL, NULL);",
+    );
+
+    let csv = unsafe { export_csv(db, "my table") }.unwrap();
+    assert_eq!(
+        csv,
+        "id,\"name, full\",score,data\r\n\
+         1,plain,1.5,00FF\r\n\
+         2,\"comma, inside\",,\r\n\
+         3,\"say \"\"hi\"\"\",-2.0,\r\n\
+         4,\"line\nbreak\",0.0,\r\n\
+         5,,,\r\n"
+    );
+
+    assert!(unsafe { export_csv(db, "missing") }.is_err());
+    unsafe { sqlite3_close(db) };
+}
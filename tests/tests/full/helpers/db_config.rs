@@ -0,0 +1,61 @@
+use sqlite_wasm_rs::helpers::{get_table, set_trusted_schema};
+use sqlite_wasm_rs::*;
+use std::ffi::{c_int, CStr};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::{exec, memory_db};
+
+unsafe extern "C" fn x_one(
+    ctx: *mut sqlite3_context,
+    _argc: c_int,
+    _argv: *mut *mut sqlite3_value,
+) {
+    sqlite3_result_int64(ctx, 1);
+}
+
+unsafe fn create_one(db: *mut sqlite3, name: &CStr, flags: c_int) {
+    let ret = sqlite3_create_function_v2(
+        db,
+        name.as_ptr(),
+        0,
+        SQLITE_UTF8 | flags,
+        std::ptr::null_mut(),
+        Some(x_one),
+        None,
+        None,
+        None,
+    );
+    assert_eq!(SQLITE_OK, ret);
+}
+
+#[wasm_bindgen_test]
+fn test_set_trusted_schema() {
+    let db = memory_db();
+    unsafe {
+        create_one(db, c"app_one", 0);
+        create_one(db, c"safe_one", SQLITE_INNOCUOUS);
+        exec(
+            db,
+            c"CREATE VIEW app_v AS SELECT app_one();
+            CREATE VIEW safe_v AS SELECT safe_one();",
+        );
+
+        assert!(set_trusted_schema(db, true).unwrap());
+        assert!(get_table(db, "SELECT * FROM app_v").is_ok());
+
+        assert!(!set_trusted_schema(db, false).unwrap());
+        let err = get_table(db, "SELECT * FROM app_v").unwrap_err();
+        assert_eq!(SQLITE_ERROR, err.extended_code);
+        assert!(
+            err.message.contains("unsafe use of app_one()"),
+            "{}",
+            err.message
+        );
+
+        // Innocuous functions and direct calls are still allowed.
+        assert!(get_table(db, "SELECT * FROM safe_v").is_ok());
+        assert!(get_table(db, "SELECT app_one()").is_ok());
+
+        sqlite3_close(db);
+    }
+}
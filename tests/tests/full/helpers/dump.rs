@@ -0,0 +1,80 @@
+use sqlite_wasm_rs::helpers::{dump_all, dump_schema};
+use sqlite_wasm_rs::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::{exec, memory_db, table_rows};
+
+#[wasm_bindgen_test]
+fn test_dump_schema() {
+    let db = memory_db();
+    assert_eq!("", unsafe { dump_schema(db) }.unwrap());
+
+    exec(
+        db,
+        c"CREATE TABLE users (id INTEGER PRIMARY KEY, email TEXT UNIQUE);
+        CREATE TABLE posts (id INTEGER PRIMARY KEY, user_id INT, body TEXT);
+        CREATE INDEX posts_user ON posts (user_id);
+        CREATE VIEW post_count AS SELECT user_id, count(*) FROM posts GROUP BY user_id;",
+    );
+
+    let schema = unsafe { dump_schema(db) }.unwrap();
+    assert_eq!(
+        schema,
+        "CREATE TABLE users (id INTEGER PRIMARY KEY, email TEXT UNIQUE);\n\
+         CREATE TABLE posts (id INTEGER PRIMARY KEY, user_id INT, body TEXT);\n\
+         CREATE INDEX posts_user ON posts (user_id);\n\
+         CREATE VIEW post_count AS SELECT user_id, count(*) FROM posts GROUP BY user_id;\n"
+    );
+    // The automatic index of the UNIQUE constraint has no SQL.
+    assert!(!schema.contains("sqlite_autoindex"));
+
+    unsafe { sqlite3_close(db) };
+}
+
+#[wasm_bindgen_test]
+fn test_dump_all() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE \"odd \"\"name\"\"\" (id INTEGER PRIMARY KEY AUTOINCREMENT, v);
+        CREATE TABLE t (a INT, b REAL, c TEXT, d BLOB);
+        CREATE INDEX t_c ON t (c);
+        CREATE VIEW v AS SELECT c FROM t;
+        CREATE TRIGGER t_log AFTER INSERT ON t BEGIN SELECT 1; END;
+        CREATE VIRTUAL TABLE docs USING fts5(body);
+        INSERT INTO \"odd \"\"name\"\"\" (v) VALUES ('x'), (2.0), (NULL), (x'00');
+        DELETE FROM \"odd \"\"name\"\"\" WHERE v IS NULL;
+        INSERT INTO t VALUES (1, 1.0, 'it''s', x'00ff'), (NULL, -2.5e-10, '', x''),
+            (9223372036854775807, NULL, 'line
+break', NULL);
+        INSERT INTO docs VALUES ('hello world');",
+    );
+
+    let dump = unsafe { dump_all(db) }.unwrap();
+    assert!(dump.starts_with("PRAGMA foreign_keys=OFF;\nBEGIN TRANSACTION;\n"));
+    assert!(dump.ends_with("COMMIT;\nPRAGMA writable_schema=RESET;\n"));
+
+    let copy = memory_db();
+    exec(copy, &std::ffi::CString::new(dump).unwrap());
+
+    for table in ["\"odd \"\"name\"\"\"", "t", "sqlite_sequence"] {
+        assert_eq!(table_rows(db, table), table_rows(copy, table), "{table}");
+    }
+    // Indexes, triggers and views come after the tables in the dump.
+    let sorted_schema = |db| {
+        let schema = unsafe { dump_schema(db) }.unwrap();
+        let mut lines = schema.lines().map(String::from).collect::<Vec<_>>();
+        lines.sort();
+        lines
+    };
+    assert_eq!(sorted_schema(db), sorted_schema(copy));
+    // The full-text index keeps its content.
+    assert_eq!(table_rows(copy, "docs"), ["3:hello world"]);
+    exec(copy, c"SELECT * FROM docs WHERE docs MATCH 'world'");
+    assert_eq!(table_rows(db, "docs_data"), table_rows(copy, "docs_data"));
+
+    unsafe {
+        sqlite3_close(db);
+        sqlite3_close(copy);
+    }
+}
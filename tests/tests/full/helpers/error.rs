@@ -0,0 +1,97 @@
+use sqlite_wasm_rs::helpers::last_error;
+use sqlite_wasm_rs::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::memory_db;
+
+#[wasm_bindgen_test]
+fn test_last_error() {
+    let db = memory_db();
+    unsafe {
+        let ret = sqlite3_exec(
+            db,
+            c"CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT UNIQUE);
+            INSERT INTO t (name) VALUES ('Alice');"
+                .as_ptr(),
+            None,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        );
+        assert_eq!(SQLITE_OK, ret);
+
+        let ret = sqlite3_exec(
+            db,
+            c"INSERT INTO t (name) VALUES ('Alice')".as_ptr(),
+            None,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        );
+        assert_eq!(SQLITE_CONSTRAINT, ret);
+
+        let err = last_error(db);
+        assert_eq!(ErrorCode::ConstraintViolation, err.code);
+        assert_eq!(SQLITE_CONSTRAINT_UNIQUE, err.extended_code);
+        assert_eq!(SQLITE_CONSTRAINT, err.primary_code());
+        assert_eq!("UNIQUE constraint failed: t.name", err.message);
+        assert_eq!(None, err.offset);
+
+        let ret = sqlite3_exec(
+            db,
+            c"SELECT nope FROM t".as_ptr(),
+            None,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        );
+        assert_eq!(SQLITE_ERROR, ret);
+
+        let err = last_error(db);
+        assert_eq!(SQLITE_ERROR, err.extended_code);
+        assert_eq!("no such column: nope", err.message);
+        assert_eq!(Some(7), err.offset);
+
+        sqlite3_close(db);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_errcode() {
+    let db = memory_db();
+    unsafe {
+        let ret = sqlite3_exec(
+            db,
+            c"CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT NOT NULL);".as_ptr(),
+            None,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        );
+        assert_eq!(SQLITE_OK, ret);
+
+        let ret = sqlite3_exec(
+            db,
+            c"INSERT INTO t (name) VALUES (NULL)".as_ptr(),
+            None,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        );
+        assert_eq!(SQLITE_CONSTRAINT, ret);
+        // The primary code drops the extended information in the upper bits.
+        assert_eq!(SQLITE_CONSTRAINT, sqlite3_errcode(db));
+        assert_eq!(SQLITE_CONSTRAINT_NOTNULL, sqlite3_extended_errcode(db));
+
+        // With extended result codes enabled, the API calls and `sqlite3_errcode`
+        // report the extended code as well.
+        assert_eq!(SQLITE_OK, sqlite3_extended_result_codes(db, 1));
+        let ret = sqlite3_exec(
+            db,
+            c"INSERT INTO t (id, name) VALUES (1, 'a'), (1, 'b')".as_ptr(),
+            None,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        );
+        assert_eq!(SQLITE_CONSTRAINT_PRIMARYKEY, ret);
+        assert_eq!(SQLITE_CONSTRAINT_PRIMARYKEY, sqlite3_errcode(db));
+        assert_eq!(SQLITE_CONSTRAINT_PRIMARYKEY, sqlite3_extended_errcode(db));
+
+        sqlite3_close(db);
+    }
+}
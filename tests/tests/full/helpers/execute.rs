@@ -0,0 +1,364 @@
+use js_sys::{Function, Reflect};
+use sqlite_wasm_rs::helpers::{
+    count, delete_in_batches, execute, execute_returning, get_table, query_column, upsert,
+    Execution, UpsertResult, Value,
+};
+use sqlite_wasm_rs::*;
+use std::cell::Cell;
+use std::rc::Rc;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::{exec, memory_db, table_rows};
+
+#[wasm_bindgen_test]
+fn test_execute_returning() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT, data BLOB, score REAL)",
+    );
+
+    let mut ids = vec![];
+    for name in ["a", "b"] {
+        let rows = unsafe {
+            execute_returning(
+                db,
+                "INSERT INTO t (name, data, score) VALUES (?1, ?2, ?3) RETURNING rowid",
+                &[name.into(), vec![1u8, 2].into(), Value::Null],
+            )
+        }
+        .unwrap();
+        let last = unsafe { sqlite3_last_insert_rowid(db) };
+        assert_eq!(rows, [vec![Value::Integer(last)]]);
+        ids.push(last);
+    }
+    assert_ne!(ids[0], ids[1]);
+    assert_eq!(
+        table_rows(db, "t"),
+        [
+            format!("1:{},3:a,blob(2),NULL", ids[0]),
+            format!("1:{},3:b,blob(2),NULL", ids[1]),
+        ]
+    );
+
+    let rows = unsafe {
+        execute_returning(
+            db,
+            "UPDATE t SET score = ?1 WHERE id = ?2 RETURNING id, name, data, score",
+            &[1.5.into(), ids[1].into()],
+        )
+    }
+    .unwrap();
+    assert_eq!(
+        rows,
+        [vec![
+            Value::Integer(ids[1]),
+            Value::Text("b".into()),
+            Value::Blob(vec![1, 2]),
+            Value::Real(1.5),
+        ]]
+    );
+
+    // Without RETURNING, the statement runs but no rows come back.
+    let rows = unsafe { execute_returning(db, "DELETE FROM t", &[]) }.unwrap();
+    assert!(rows.is_empty());
+    assert!(table_rows(db, "t").is_empty());
+
+    unsafe { sqlite3_close(db) };
+}
+
+#[wasm_bindgen_test]
+fn test_execute() {
+    let db = memory_db();
+    let run = |sql, params: &[Value]| unsafe { execute(db, sql, params) }.unwrap();
+
+    assert_eq!(
+        Execution::Changes(0),
+        run("CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT)", &[])
+    );
+    assert_eq!(
+        Execution::Changes(3),
+        run(
+            "INSERT INTO t (name) VALUES ('a'), ('b'), (?1)",
+            &["c".into()]
+        )
+    );
+    assert_eq!(
+        Execution::Rows(vec![
+            vec![Value::Integer(2), Value::Text("b".into())],
+            vec![Value::Integer(3), Value::Text("c".into())],
+        ]),
+        run("SELECT id, name FROM t WHERE id > ?1", &[1.into()])
+    );
+    assert_eq!(
+        Execution::Changes(2),
+        run("UPDATE t SET name = upper(name) WHERE id < 3", &[])
+    );
+    assert_eq!(
+        Execution::Rows(vec![]),
+        run("SELECT * FROM t WHERE name = 'b'", &[])
+    );
+    // The count of the previous UPDATE is not reported again.
+    assert_eq!(
+        Execution::Changes(0),
+        run("UPDATE t SET name = 'x' WHERE 0", &[])
+    );
+    assert_eq!(
+        Execution::Changes(0),
+        run("CREATE INDEX t_name ON t (name)", &[])
+    );
+    assert_eq!(table_rows(db, "t"), ["1:1,3:A", "1:2,3:B", "1:3,3:c"]);
+
+    unsafe { sqlite3_close(db) };
+}
+
+#[wasm_bindgen_test]
+fn test_execute_tuple_params() {
+    let db = memory_db();
+    unsafe {
+        execute(db, "CREATE TABLE t (id INTEGER, name TEXT, score REAL)", ()).unwrap();
+        assert_eq!(
+            Execution::Changes(1),
+            execute(db, "INSERT INTO t VALUES (?,?,NULL)", (1, "a")).unwrap()
+        );
+        assert_eq!(
+            Execution::Changes(1),
+            execute(
+                db,
+                "INSERT INTO t VALUES (?,?,?)",
+                (2, String::from("b"), Some(0.5))
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            Execution::Rows(vec![vec![Value::Text("b".into())]]),
+            execute(db, "SELECT name FROM t WHERE id = ?", (2,)).unwrap()
+        );
+    }
+    assert_eq!(table_rows(db, "t"), ["1:1,3:a,NULL", "1:2,3:b,2:0.5"]);
+
+    unsafe { sqlite3_close(db) };
+}
+
+#[wasm_bindgen_test]
+fn test_query_column() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE employees (name TEXT, salary REAL, manager TEXT);
+        INSERT INTO employees VALUES
+            ('ann', 5200.5, NULL), ('bob', 4100, 'ann'), ('cid', 3900.25, 'ann');",
+    );
+    unsafe {
+        let salaries: Vec<f64> = query_column(db, "SELECT salary FROM employees", ()).unwrap();
+        assert_eq!(salaries, [5200.5, 4100.0, 3900.25]);
+
+        let names: Vec<String> = query_column(
+            db,
+            "SELECT name FROM employees WHERE salary < ?1 ORDER BY name",
+            (5000.0,),
+        )
+        .unwrap();
+        assert_eq!(names, ["bob", "cid"]);
+
+        let managers: Vec<Option<String>> =
+            query_column(db, "SELECT manager FROM employees", ()).unwrap();
+        assert_eq!(managers, [None, Some("ann".into()), Some("ann".into())]);
+
+        let err = query_column::<String>(db, "SELECT manager FROM employees", ()).unwrap_err();
+        assert_eq!(SQLITE_MISMATCH, err.extended_code);
+        let err = query_column::<i64>(db, "SELECT salary FROM employees", ()).unwrap_err();
+        assert_eq!(SQLITE_MISMATCH, err.extended_code);
+
+        assert!(query_column::<i64>(db, "SELECT 1 WHERE 0", ())
+            .unwrap()
+            .is_empty());
+        let err = query_column::<i64>(db, "DELETE FROM employees WHERE 0", ()).unwrap_err();
+        assert_eq!(SQLITE_RANGE, err.extended_code);
+
+        sqlite3_close(db);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_get_table() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE employees (name TEXT, salary REAL, manager TEXT);
+        INSERT INTO employees VALUES
+            ('ann', 5200.5, NULL), ('bob', 4100, 'ann'), ('cid', 3900.25, 'ann');",
+    );
+    unsafe {
+        let (header, rows) = get_table(
+            db,
+            "SELECT name, salary AS pay, manager FROM employees ORDER BY name",
+        )
+        .unwrap();
+        assert_eq!(header, ["name", "pay", "manager"]);
+        assert_eq!(
+            rows,
+            [
+                [Some("ann".into()), Some("5200.5".into()), None],
+                [
+                    Some("bob".into()),
+                    Some("4100.0".into()),
+                    Some("ann".into())
+                ],
+                [
+                    Some("cid".into()),
+                    Some("3900.25".into()),
+                    Some("ann".into())
+                ],
+            ]
+        );
+
+        let (header, rows) = get_table(db, "SELECT count(*) FROM employees WHERE 0").unwrap();
+        assert_eq!(header, ["count(*)"]);
+        assert_eq!(rows, [[Some("0".to_string())]]);
+
+        assert!(get_table(db, "SELECT * FROM missing").is_err());
+        sqlite3_close(db);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_upsert() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE kv (key TEXT PRIMARY KEY, value INTEGER);
+        INSERT INTO kv VALUES ('other', 0);",
+    );
+    let last = unsafe { sqlite3_last_insert_rowid(db) };
+    let sql = "INSERT INTO kv VALUES (?1, ?2)
+        ON CONFLICT (key) DO UPDATE SET value = excluded.value";
+
+    let result = unsafe { upsert(db, sql, ("a", 1)) }.unwrap();
+    assert_eq!(
+        result,
+        UpsertResult {
+            changes: 1,
+            inserted: true
+        }
+    );
+    let inserted = unsafe { sqlite3_last_insert_rowid(db) };
+    assert_ne!(last, inserted);
+
+    let result = unsafe { upsert(db, sql, ("a", 2)) }.unwrap();
+    assert_eq!(
+        result,
+        UpsertResult {
+            changes: 1,
+            inserted: false
+        }
+    );
+    // An update keeps the rowid of the last insert.
+    assert_eq!(inserted, unsafe { sqlite3_last_insert_rowid(db) });
+    assert_eq!(table_rows(db, "kv"), ["3:other,1:0", "3:a,1:2"]);
+
+    // `DO NOTHING` neither inserts nor updates.
+    let result = unsafe {
+        upsert(
+            db,
+            "INSERT INTO kv VALUES ('a', 3) ON CONFLICT DO NOTHING",
+            (),
+        )
+    }
+    .unwrap();
+    assert_eq!(
+        result,
+        UpsertResult {
+            changes: 0,
+            inserted: false
+        }
+    );
+
+    unsafe { sqlite3_close(db) };
+}
+
+#[wasm_bindgen_test]
+fn test_count() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE posts (id INTEGER PRIMARY KEY, author TEXT, likes INTEGER);
+        WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n WHERE x < 100)
+        INSERT INTO posts SELECT x, CASE x % 3 WHEN 0 THEN 'ann' ELSE 'bob' END, x % 7 FROM n;",
+    );
+
+    unsafe {
+        let authors: Vec<String> = query_column(db, "SELECT author FROM posts", ()).unwrap();
+        let likes: Vec<i64> = query_column(db, "SELECT likes FROM posts", ()).unwrap();
+        let manual = authors
+            .iter()
+            .zip(&likes)
+            .filter(|(author, &likes)| *author == "ann" && likes > 2)
+            .count() as i64;
+
+        assert_eq!(
+            manual,
+            count(db, "posts WHERE author = ?1 AND likes > ?2", ("ann", 2)).unwrap()
+        );
+        assert_eq!(100, count(db, "posts", ()).unwrap());
+        assert_eq!(0, count(db, "posts WHERE 0", ()).unwrap());
+        assert!(count(db, "missing", ()).is_err());
+
+        sqlite3_close(db);
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_delete_in_batches() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE events (id INTEGER PRIMARY KEY, kind TEXT);
+        WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n WHERE x < 10000)
+        INSERT INTO events SELECT x, CASE x % 4 WHEN 0 THEN 'keep' ELSE 'drop' END FROM n;",
+    );
+
+    // A timer queued before the delete runs between its batches.
+    let deleted_before_timer = Rc::new(Cell::new(None));
+    let callback = Closure::once_into_js({
+        let deleted_before_timer = deleted_before_timer.clone();
+        move || {
+            let remaining = unsafe { count(db, "events WHERE kind = 'drop'", ()) }.unwrap();
+            deleted_before_timer.set(Some(7500 - remaining));
+        }
+    });
+    let global = js_sys::global();
+    Reflect::get(&global, &JsValue::from("setTimeout"))
+        .unwrap()
+        .dyn_into::<Function>()
+        .unwrap()
+        .call2(&global, &callback, &JsValue::from(0))
+        .unwrap();
+
+    unsafe {
+        assert_eq!(
+            7500,
+            delete_in_batches(db, "events", "kind = 'drop'", 1000)
+                .await
+                .unwrap()
+        );
+        assert_eq!(Some(1000), deleted_before_timer.get());
+        assert_eq!(0, count(db, "events WHERE kind = 'drop'", ()).unwrap());
+        assert_eq!(2500, count(db, "events", ()).unwrap());
+
+        // The last batch is partial.
+        assert_eq!(
+            2500,
+            delete_in_batches(db, "events", "", 999).await.unwrap()
+        );
+        assert_eq!(0, count(db, "events", ()).unwrap());
+        assert_eq!(0, delete_in_batches(db, "events", "", 999).await.unwrap());
+
+        let err = delete_in_batches(db, "events", "", 0).await.unwrap_err();
+        assert_eq!(SQLITE_RANGE, err.extended_code);
+        assert!(delete_in_batches(db, "missing", "", 10).await.is_err());
+
+        sqlite3_close(db);
+    }
+}
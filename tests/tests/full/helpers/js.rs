@@ -0,0 +1,54 @@
+use sqlite_wasm_rs::helpers::query_js;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::{exec, memory_db};
+
+fn get(row: &JsValue, key: &str) -> JsValue {
+    js_sys::Reflect::get(row, &JsValue::from_str(key)).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn test_query_js() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE t(i INTEGER, r REAL, s TEXT, b BLOB, n);
+        INSERT INTO t VALUES (42, 1.5, 'héllo', x'00ff', NULL);
+        INSERT INTO t VALUES (9007199254740993, -0.25, '', x'', NULL);",
+    );
+
+    let rows = unsafe { query_js(db, "SELECT * FROM t ORDER BY rowid") }.unwrap();
+    assert_eq!(2, rows.length());
+
+    let first = rows.get(0);
+    assert_eq!(Some(42.0), get(&first, "i").as_f64());
+    assert_eq!(Some(1.5), get(&first, "r").as_f64());
+    assert_eq!(Some("héllo".to_string()), get(&first, "s").as_string());
+    assert_eq!(
+        vec![0x00, 0xff],
+        js_sys::Uint8Array::new(&get(&first, "b")).to_vec()
+    );
+    assert!(get(&first, "n").is_null());
+    assert_eq!(
+        5,
+        js_sys::Object::keys(first.unchecked_ref::<js_sys::Object>()).length()
+    );
+
+    // Integers beyond Number.MAX_SAFE_INTEGER are returned as BigInt.
+    let second = rows.get(1);
+    let big = get(&second, "i");
+    assert!(big.is_bigint());
+    assert_eq!(big, JsValue::bigint_from_str("9007199254740993"));
+    assert_eq!(Some(String::new()), get(&second, "s").as_string());
+    assert!(get(&second, "b").is_instance_of::<js_sys::Uint8Array>());
+
+    let rows = unsafe { query_js(db, "SELECT 1 AS one, 'x' AS two") }.unwrap();
+    assert_eq!(Some(1.0), get(&rows.get(0), "one").as_f64());
+    assert_eq!(Some("x".to_string()), get(&rows.get(0), "two").as_string());
+
+    let err = unsafe { query_js(db, "SELECT * FROM missing") }.unwrap_err();
+    assert_eq!("Error code 1: no such table: missing", err.to_string());
+
+    unsafe { sqlite_wasm_rs::sqlite3_close(db) };
+}
@@ -0,0 +1,46 @@
+use sqlite_wasm_rs::helpers::{create_function, get_table};
+use sqlite_wasm_rs::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::memory_db;
+
+#[wasm_bindgen_test]
+fn test_create_function() {
+    let db = memory_db();
+    unsafe {
+        create_function(
+            db,
+            "double_it",
+            1,
+            js_sys::Function::new_with_args("x", "return x * 2"),
+        )
+        .unwrap();
+        create_function(
+            db,
+            "fail",
+            0,
+            js_sys::Function::new_no_args("throw new Error('boom')"),
+        )
+        .unwrap();
+
+        let (_, rows) = get_table(
+            db,
+            "SELECT double_it(21), typeof(double_it(21)), double_it(1.25), double_it(NULL)",
+        )
+        .unwrap();
+        assert_eq!(
+            vec![vec![
+                Some("42".to_string()),
+                Some("integer".to_string()),
+                Some("2.5".to_string()),
+                // `null * 2` is `0` in JS.
+                Some("0".to_string()),
+            ]],
+            rows
+        );
+
+        let err = get_table(db, "SELECT fail()").unwrap_err();
+        assert_eq!(SQLITE_ERROR, err.extended_code);
+        assert_eq!("boom", err.message);
+    }
+}
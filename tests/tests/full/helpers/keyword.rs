@@ -0,0 +1,76 @@
+use sqlite_wasm_rs::helpers::{keyword_suggestions, validate_function_name, Result};
+use sqlite_wasm_rs::*;
+use std::ffi::{c_int, CString};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::memory_db;
+
+#[wasm_bindgen_test]
+fn test_keyword_suggestions() {
+    let suggestions = keyword_suggestions("sel");
+    assert!(suggestions.contains(&"SELECT".to_string()));
+    assert!(suggestions.iter().all(|keyword| keyword.starts_with("SEL")));
+    assert_eq!(suggestions, keyword_suggestions("SeL"));
+
+    assert_eq!(keyword_suggestions("TRANSACTIO"), ["TRANSACTION"]);
+    assert!(keyword_suggestions("xyz").is_empty());
+    assert_eq!(
+        unsafe { sqlite3_keyword_count() } as usize,
+        keyword_suggestions("").len()
+    );
+}
+
+unsafe extern "C" fn x_one(
+    ctx: *mut sqlite3_context,
+    _argc: c_int,
+    _argv: *mut *mut sqlite3_value,
+) {
+    sqlite3_result_int64(ctx, 1);
+}
+
+unsafe fn register(db: *mut sqlite3, name: &str) -> Result<()> {
+    validate_function_name(name)?;
+    let name = CString::new(name).unwrap();
+    let ret = sqlite3_create_function_v2(
+        db,
+        name.as_ptr(),
+        0,
+        SQLITE_UTF8,
+        std::ptr::null_mut(),
+        Some(x_one),
+        None,
+        None,
+        None,
+    );
+    assert_eq!(SQLITE_OK, ret);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_validate_function_name() {
+    let db = memory_db();
+    unsafe {
+        let err = register(db, "select").unwrap_err();
+        assert_eq!(SQLITE_MISUSE, err.extended_code);
+        assert_eq!(
+            "invalid function name `select`: the name is an SQL keyword",
+            err.message
+        );
+
+        register(db, "my_func$2").unwrap();
+        register(db, "_größe").unwrap();
+
+        for name in ["", "2nd", "$x", "my-func", "a b", "x".repeat(256).as_str()] {
+            let err = register(db, name).unwrap_err();
+            assert_eq!(SQLITE_MISUSE, err.extended_code, "{name}");
+        }
+        let err = validate_function_name("my-func").unwrap_err();
+        assert!(
+            err.message.contains("`-` is not allowed"),
+            "{}",
+            err.message
+        );
+
+        sqlite3_close(db);
+    }
+}
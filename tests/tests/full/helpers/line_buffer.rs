@@ -0,0 +1,45 @@
+use sqlite_wasm_rs::helpers::SqlLineBuffer;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+fn test_sql_line_buffer() {
+    let mut buffer = SqlLineBuffer::new();
+    assert!(buffer.is_empty());
+    assert!(!buffer.is_complete());
+    assert_eq!(None, buffer.take_complete_statements());
+
+    buffer.push_line("CREATE TABLE t (");
+    buffer.push_line("  v TEXT -- a comment;");
+    assert!(!buffer.is_empty());
+    assert!(!buffer.is_complete());
+    assert_eq!(None, buffer.take_complete_statements());
+
+    buffer.push_line(");");
+    assert!(buffer.is_complete());
+    assert_eq!(
+        Some("CREATE TABLE t (\n  v TEXT -- a comment;\n);".to_string()),
+        buffer.take_complete_statements()
+    );
+    assert!(buffer.is_empty());
+
+    // Complete statements are taken, the incomplete tail is kept.
+    buffer.push_line("INSERT INTO t VALUES ('a;b'); SELECT 1;");
+    buffer.push_line("SELECT");
+    assert!(!buffer.is_complete());
+    assert_eq!(
+        Some("INSERT INTO t VALUES ('a;b'); SELECT 1;".to_string()),
+        buffer.take_complete_statements()
+    );
+    buffer.push_line("2;");
+    assert_eq!(
+        Some("SELECT\n2;".to_string()),
+        buffer.take_complete_statements()
+    );
+
+    buffer.push_line("CREATE TRIGGER tr AFTER INSERT ON t BEGIN SELECT 1;");
+    assert!(!buffer.is_complete());
+    buffer.push_line("END;");
+    assert!(buffer.is_complete());
+    buffer.clear();
+    assert!(buffer.is_empty());
+}
@@ -0,0 +1,53 @@
+use sqlite_wasm_rs::helpers::configure_lookaside;
+use sqlite_wasm_rs::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::{exec, memory_db, table_rows};
+
+unsafe fn lookaside_status(db: *mut sqlite3, op: i32) -> (i32, i32) {
+    let (mut current, mut highwater) = (0, 0);
+    assert_eq!(
+        SQLITE_OK,
+        sqlite3_db_status(db, op, &mut current, &mut highwater, 0)
+    );
+    (current, highwater)
+}
+
+#[wasm_bindgen_test]
+fn test_configure_lookaside() {
+    let db = memory_db();
+    let lookaside = unsafe { configure_lookaside(db, 128, 32) }.unwrap();
+
+    exec(
+        db,
+        c"CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT);
+        INSERT INTO t (name) VALUES ('a'), ('b');
+        CREATE INDEX t_name ON t (name);",
+    );
+    assert_eq!(table_rows(db, "t"), ["1:1,3:a", "1:2,3:b"]);
+
+    unsafe {
+        // The connection allocated from the new slots, and never more of
+        // them than were configured.
+        let (_, used) = lookaside_status(db, SQLITE_DBSTATUS_LOOKASIDE_USED);
+        assert!(used > 0 && used <= 32, "{used}");
+        let (_, hits) = lookaside_status(db, SQLITE_DBSTATUS_LOOKASIDE_HIT);
+        assert!(hits > 0);
+
+        // Lookaside can't be reconfigured while a statement holds slots.
+        let mut stmt = std::ptr::null_mut();
+        sqlite3_prepare_v2(
+            db,
+            c"SELECT * FROM t".as_ptr(),
+            -1,
+            &mut stmt,
+            std::ptr::null_mut(),
+        );
+        let err = configure_lookaside(db, 64, 8).unwrap_err();
+        assert_eq!(SQLITE_BUSY, err.extended_code);
+        sqlite3_finalize(stmt);
+
+        sqlite3_close(db);
+    }
+    drop(lookaside);
+}
@@ -0,0 +1,126 @@
+mod analyze;
+mod attach;
+mod auto_extension;
+#[cfg(feature = "sqlite3mc")]
+mod cipher;
+mod config;
+mod copy;
+mod csv;
+mod db_config;
+mod dump;
+mod error;
+mod execute;
+#[cfg(feature = "js-api")]
+mod js;
+#[cfg(feature = "js-functions")]
+mod js_function;
+mod keyword;
+mod line_buffer;
+mod lookaside;
+mod open;
+mod pragma;
+mod progress;
+mod recover;
+mod result;
+mod savepoint;
+mod scanstatus;
+mod schema;
+mod seeded_random;
+mod serialize;
+mod stmt;
+mod str_builder;
+#[cfg(feature = "test-control")]
+mod test_control;
+mod timed;
+mod uri;
+mod value;
+mod vtab;
+
+use sqlite_wasm_rs::*;
+
+pub fn memory_db() -> *mut sqlite3 {
+    let mut db = std::ptr::null_mut();
+    let ret = unsafe {
+        sqlite3_open_v2(
+            c":memory:".as_ptr().cast(),
+            &mut db as *mut _,
+            SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+            std::ptr::null(),
+        )
+    };
+    assert_eq!(SQLITE_OK, ret);
+    db
+}
+
+/// A new connection with `bytes` deserialized as its main database.
+pub fn load_db(bytes: &[u8]) -> *mut sqlite3 {
+    let db = memory_db();
+    unsafe {
+        let buf = sqlite3_malloc64(bytes.len() as u64).cast::<u8>();
+        assert!(!buf.is_null());
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), buf, bytes.len());
+        let ret = sqlite3_deserialize(
+            db,
+            c"main".as_ptr(),
+            buf,
+            bytes.len() as i64,
+            bytes.len() as i64,
+            SQLITE_DESERIALIZE_FREEONCLOSE | SQLITE_DESERIALIZE_RESIZEABLE,
+        );
+        assert_eq!(SQLITE_OK, ret);
+    }
+    db
+}
+
+/// All rows of `table` ordered by rowid, each value rendered with `quote()`.
+pub fn table_rows(db: *mut sqlite3, table: &str) -> Vec<String> {
+    let sql = format!("SELECT * FROM {table} ORDER BY rowid");
+    let mut stmt = std::ptr::null_mut();
+    let ret = unsafe {
+        sqlite3_prepare_v3(
+            db,
+            sql.as_ptr().cast(),
+            sql.len() as i32,
+            0,
+            &mut stmt as *mut _,
+            std::ptr::null_mut(),
+        )
+    };
+    assert_eq!(SQLITE_OK, ret);
+
+    let mut rows = vec![];
+    unsafe {
+        while sqlite3_step(stmt) == SQLITE_ROW {
+            let row = (0..sqlite3_column_count(stmt))
+                .map(|col| {
+                    let ty = sqlite3_column_type(stmt, col);
+                    let text = sqlite3_column_text(stmt, col);
+                    match ty {
+                        SQLITE_NULL => "NULL".to_string(),
+                        SQLITE_BLOB => format!("blob({})", sqlite3_column_bytes(stmt, col)),
+                        _ => format!(
+                            "{ty}:{}",
+                            std::ffi::CStr::from_ptr(text.cast()).to_str().unwrap()
+                        ),
+                    }
+                })
+                .collect::<Vec<_>>();
+            rows.push(row.join(","));
+        }
+        sqlite3_finalize(stmt);
+    }
+    rows
+}
+
+pub fn exec(db: *mut sqlite3, sql: &std::ffi::CStr) {
+    let ret = unsafe {
+        sqlite3_exec(
+            db,
+            sql.as_ptr(),
+            None,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    assert_eq!(SQLITE_OK, ret);
+}
@@ -0,0 +1,175 @@
+use sqlite_wasm_rs::helpers::{
+    close_checked, open_shared_memory, open_temp, open_with_busy_timeout, open_with_foreign_keys,
+};
+use sqlite_wasm_rs::*;
+use std::ffi::CStr;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::{exec, memory_db, table_rows};
+
+fn busy_timeout(db: *mut sqlite3) -> i32 {
+    let mut stmt = std::ptr::null_mut();
+    unsafe {
+        let ret = sqlite3_prepare_v3(
+            db,
+            c"PRAGMA busy_timeout".as_ptr(),
+            -1,
+            0,
+            &mut stmt as *mut _,
+            std::ptr::null_mut(),
+        );
+        assert_eq!(SQLITE_OK, ret);
+        assert_eq!(SQLITE_ROW, sqlite3_step(stmt));
+        let timeout = sqlite3_column_int(stmt, 0);
+        sqlite3_finalize(stmt);
+        timeout
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_open_with_busy_timeout_sets_setting() {
+    let db = unsafe {
+        open_with_busy_timeout(
+            c"test_open_with_busy_timeout.db",
+            SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+            None,
+            5000,
+        )
+    }
+    .unwrap();
+    assert_eq!(5000, busy_timeout(db));
+    unsafe { sqlite3_close(db) };
+
+    let err = unsafe {
+        open_with_busy_timeout(
+            c"test_open_with_busy_timeout_missing.db",
+            SQLITE_OPEN_READWRITE,
+            None,
+            5000,
+        )
+    }
+    .unwrap_err();
+    assert_eq!(SQLITE_CANTOPEN, err.extended_code & 0xff);
+
+    let err = unsafe {
+        open_with_busy_timeout(
+            c"test_open_with_busy_timeout.db",
+            SQLITE_OPEN_READWRITE,
+            Some(c"no-such-vfs"),
+            5000,
+        )
+    }
+    .unwrap_err();
+    assert_eq!("no such vfs: no-such-vfs", err.message);
+}
+
+#[wasm_bindgen_test]
+fn test_open_shared_memory() {
+    let a = unsafe { open_shared_memory(c"test_open_shared_memory.db") }.unwrap();
+    let b = unsafe { open_shared_memory(c"test_open_shared_memory.db") }.unwrap();
+    exec(a, c"CREATE TABLE t (v); INSERT INTO t VALUES (1), ('two');");
+    assert_eq!(table_rows(b, "t"), ["1:1", "3:two"]);
+
+    exec(b, c"DELETE FROM t WHERE v = 1");
+    assert_eq!(table_rows(a, "t"), ["3:two"]);
+    unsafe {
+        sqlite3_close(a);
+        sqlite3_close(b);
+    }
+
+    // The data is kept after every connection is closed.
+    let c = unsafe { open_shared_memory(c"test_open_shared_memory.db") }.unwrap();
+    assert_eq!(table_rows(c, "t"), ["3:two"]);
+    unsafe { sqlite3_close(c) };
+    MemVfsUtil::<sqlite_wasm_rs::WasmOsCallback>::new().delete_db("test_open_shared_memory.db");
+}
+
+#[wasm_bindgen_test]
+fn test_open_temp() {
+    let util = MemVfsUtil::<sqlite_wasm_rs::WasmOsCallback>::new();
+    let files = util.count();
+
+    let a = unsafe { open_temp() }.unwrap();
+    let b = unsafe { open_temp() }.unwrap();
+    exec(a, c"CREATE TABLE t (v); INSERT INTO t VALUES (1);");
+    assert_eq!(table_rows(a, "t"), ["1:1"]);
+    // Every temporary database is private to its connection.
+    exec(b, c"CREATE TABLE t (v);");
+    assert!(table_rows(b, "t").is_empty());
+
+    let filename = unsafe { CStr::from_ptr(sqlite3_db_filename(a, c"main".as_ptr())) };
+    assert!(filename.is_empty());
+    unsafe {
+        sqlite3_close(a);
+        sqlite3_close(b);
+    }
+    assert_eq!(files, util.count());
+}
+
+#[wasm_bindgen_test]
+fn test_close_checked() {
+    let db = memory_db();
+    let mut stmt = std::ptr::null_mut();
+    let ret = unsafe {
+        sqlite3_prepare_v3(
+            db,
+            c"SELECT 1".as_ptr(),
+            -1,
+            0,
+            &mut stmt as *mut _,
+            std::ptr::null_mut(),
+        )
+    };
+    assert_eq!(SQLITE_OK, ret);
+
+    // The connection stays usable until the statement is finalized.
+    assert_eq!(Err(1), unsafe { close_checked(db) });
+    assert_eq!(SQLITE_ROW, unsafe { sqlite3_step(stmt) });
+    unsafe { sqlite3_finalize(stmt) };
+    assert_eq!(Ok(()), unsafe { close_checked(db) });
+}
+
+#[wasm_bindgen_test]
+fn test_open_with_foreign_keys() {
+    let db = unsafe {
+        open_with_foreign_keys(
+            c":memory:",
+            SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+            None,
+        )
+    }
+    .unwrap();
+    exec(
+        db,
+        c"CREATE TABLE parent (id INTEGER PRIMARY KEY);
+        CREATE TABLE child (id INTEGER PRIMARY KEY, parent_id INT REFERENCES parent (id));
+        INSERT INTO parent VALUES (1);
+        INSERT INTO child VALUES (1, 1);",
+    );
+
+    let ret = unsafe {
+        sqlite3_exec(
+            db,
+            c"INSERT INTO child VALUES (2, 2)".as_ptr(),
+            None,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    assert_eq!(SQLITE_CONSTRAINT_FOREIGNKEY, unsafe {
+        sqlite3_extended_errcode(db)
+    });
+    assert_eq!(SQLITE_CONSTRAINT, ret);
+    assert_eq!(table_rows(db, "child"), ["1:1,1:1"]);
+    unsafe { sqlite3_close(db) };
+
+    let err = unsafe {
+        open_with_foreign_keys(
+            c"test_open_with_foreign_keys_missing.db",
+            SQLITE_OPEN_READWRITE,
+            None,
+        )
+    }
+    .unwrap_err();
+    assert_eq!(SQLITE_CANTOPEN, err.extended_code & 0xff);
+}
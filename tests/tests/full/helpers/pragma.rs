@@ -0,0 +1,117 @@
+use sqlite_wasm_rs::helpers::{
+    cache_size, db_tags, execute_returning, integrity_check_limited, journal_mode,
+    open_shared_memory, serialize_to_vec, set_application_id, set_cache_size, set_journal_mode,
+    set_user_version, wal_checkpoint, CheckpointMode, Value, WalCheckpoint,
+};
+use sqlite_wasm_rs::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::{exec, load_db, memory_db};
+
+#[wasm_bindgen_test]
+fn test_journal_mode() {
+    let db = memory_db();
+    unsafe {
+        assert_eq!("memory", journal_mode(db).unwrap());
+        assert_eq!("off", set_journal_mode(db, "OFF").unwrap());
+        assert_eq!("off", journal_mode(db).unwrap());
+        assert_eq!("memory", set_journal_mode(db, "MEMORY").unwrap());
+        // In-memory databases keep their mode instead of switching to WAL.
+        assert_eq!("memory", set_journal_mode(db, "wal").unwrap());
+
+        let err = set_journal_mode(db, "memory; DROP TABLE t").unwrap_err();
+        assert_eq!(SQLITE_MISUSE, err.extended_code);
+
+        assert_eq!(
+            WalCheckpoint {
+                busy: false,
+                log_frames: -1,
+                checkpointed_frames: -1,
+            },
+            wal_checkpoint(db, CheckpointMode::Passive).unwrap()
+        );
+
+        sqlite3_close(db);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_cache_size() {
+    let db = memory_db();
+    unsafe {
+        // Built with SQLITE_DEFAULT_CACHE_SIZE=-16384, i.e. 16 MiB.
+        assert_eq!(-16384, cache_size(db).unwrap());
+
+        set_cache_size(db, -2048).unwrap();
+        assert_eq!(-2048, cache_size(db).unwrap());
+
+        set_cache_size(db, 500).unwrap();
+        assert_eq!(500, cache_size(db).unwrap());
+
+        sqlite3_close(db);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_integrity_check_limited() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE t (id INTEGER PRIMARY KEY, payload TEXT);
+        CREATE INDEX t_payload ON t (payload);
+        WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n WHERE x < 1000)
+        INSERT INTO t SELECT x, printf('%d%.200c', x, 'x') FROM n;",
+    );
+    unsafe {
+        assert!(integrity_check_limited(db, 10).unwrap().is_empty());
+        assert_eq!(
+            SQLITE_RANGE,
+            integrity_check_limited(db, 0).unwrap_err().extended_code
+        );
+
+        let mut bytes = serialize_to_vec(db, c"main").unwrap();
+        let rows = execute_returning(
+            db,
+            "SELECT pageno, (SELECT page_size FROM pragma_page_size) FROM dbstat \
+             WHERE name = 't_payload' AND pagetype = 'leaf' ORDER BY pageno LIMIT 3 OFFSET 2",
+            &[],
+        )
+        .unwrap();
+        assert_eq!(3, rows.len());
+        // Zero three leaf pages of the index.
+        for row in rows {
+            let [Value::Integer(page), Value::Integer(page_size)] = row[..] else {
+                panic!("unexpected row {row:?}");
+            };
+            let (page, page_size) = (page as usize, page_size as usize);
+            bytes[(page - 1) * page_size..page * page_size].fill(0);
+        }
+
+        let broken = load_db(&bytes);
+        assert!(integrity_check_limited(broken, 100).unwrap().len() > 3);
+        assert_eq!(3, integrity_check_limited(broken, 3).unwrap().len());
+
+        sqlite3_close(broken);
+        sqlite3_close(db);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_db_tags() {
+    unsafe {
+        let db = open_shared_memory(c"test_db_tags.db").unwrap();
+        assert_eq!((0, 0), db_tags(db).unwrap());
+        set_application_id(db, 0x5153_4c57).unwrap();
+        set_user_version(db, -7).unwrap();
+        assert_eq!((0x5153_4c57, -7), db_tags(db).unwrap());
+        sqlite3_close(db);
+
+        // Both are stored in the database header.
+        let db = open_shared_memory(c"test_db_tags.db").unwrap();
+        assert_eq!((0x5153_4c57, -7), db_tags(db).unwrap());
+        set_user_version(db, 8).unwrap();
+        assert_eq!((0x5153_4c57, 8), db_tags(db).unwrap());
+        sqlite3_close(db);
+    }
+    MemVfsUtil::<WasmOsCallback>::new().delete_db("test_db_tags.db");
+}
@@ -0,0 +1,36 @@
+use sqlite_wasm_rs::helpers::{query_column, with_progress};
+use sqlite_wasm_rs::*;
+use std::cell::Cell;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::memory_db;
+
+const LONG_QUERY: &str =
+    "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n WHERE x < 100000) \
+     SELECT sum(x) FROM n";
+
+#[wasm_bindgen_test]
+fn test_with_progress() {
+    let db = memory_db();
+    let calls = Cell::new(0);
+    unsafe {
+        let sum: Vec<i64> = with_progress(
+            db,
+            1000,
+            |count| {
+                assert_eq!(calls.get() + 1, count);
+                calls.set(count);
+            },
+            || query_column(db, LONG_QUERY, ()).unwrap(),
+        );
+        assert_eq!(vec![5000050000], sum);
+        assert!(calls.get() > 0);
+
+        // The handler is removed afterwards.
+        let before = calls.get();
+        query_column::<i64>(db, LONG_QUERY, ()).unwrap();
+        assert_eq!(before, calls.get());
+
+        sqlite3_close(db);
+    }
+}
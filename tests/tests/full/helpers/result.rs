@@ -0,0 +1,250 @@
+use sqlite_wasm_rs::helpers::{result_blob, result_static_text, result_text, result_value};
+use sqlite_wasm_rs::*;
+use std::ffi::{c_int, CStr};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::{memory_db, table_rows};
+
+unsafe extern "C" fn x_repeat(
+    ctx: *mut sqlite3_context,
+    _argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    let n = sqlite3_value_int64(*argv) as usize;
+    result_text(ctx, &"αβ".repeat(n));
+}
+
+unsafe extern "C" fn x_identity(
+    ctx: *mut sqlite3_context,
+    _argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    result_value(ctx, *argv);
+}
+
+#[wasm_bindgen_test]
+fn test_result_text() {
+    let db = memory_db();
+    unsafe {
+        let ret = sqlite3_create_function_v2(
+            db,
+            c"repeat_text".as_ptr(),
+            1,
+            SQLITE_UTF8,
+            std::ptr::null_mut(),
+            Some(x_repeat),
+            None,
+            None,
+            None,
+        );
+        assert_eq!(SQLITE_OK, ret);
+
+        let n = 1 << 20;
+        let mut stmt = std::ptr::null_mut();
+        let ret = sqlite3_prepare_v3(
+            db,
+            c"SELECT repeat_text(?1), length(repeat_text(?1))".as_ptr(),
+            -1,
+            0,
+            &mut stmt as *mut _,
+            std::ptr::null_mut(),
+        );
+        assert_eq!(SQLITE_OK, ret);
+        sqlite3_bind_int64(stmt, 1, n as i64);
+        assert_eq!(SQLITE_ROW, sqlite3_step(stmt));
+
+        // Two 2-byte characters per repetition.
+        assert_eq!(4 * n as c_int, sqlite3_column_bytes(stmt, 0));
+        let text = CStr::from_ptr(sqlite3_column_text(stmt, 0).cast());
+        assert_eq!("αβ".repeat(n), text.to_str().unwrap());
+        assert_eq!(2 * n as i64, sqlite3_column_int64(stmt, 1));
+        sqlite3_finalize(stmt);
+
+        sqlite3_close(db);
+    }
+}
+
+unsafe extern "C" fn x_filled_blob(
+    ctx: *mut sqlite3_context,
+    _argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    let n = sqlite3_value_int64(*argv) as usize;
+    result_blob(ctx, &vec![0xab; n]);
+}
+
+#[wasm_bindgen_test]
+fn test_result_blob() {
+    let db = memory_db();
+    unsafe {
+        let ret = sqlite3_create_function_v2(
+            db,
+            c"filled_blob".as_ptr(),
+            1,
+            SQLITE_UTF8,
+            std::ptr::null_mut(),
+            Some(x_filled_blob),
+            None,
+            None,
+            None,
+        );
+        assert_eq!(SQLITE_OK, ret);
+
+        // Lower the length limit, the default of 1e9 bytes doesn't fit
+        // comfortably in wasm32 memory.
+        let limit = 16 << 20;
+        sqlite3_limit(db, SQLITE_LIMIT_LENGTH, limit);
+
+        let mut stmt = std::ptr::null_mut();
+        let ret = sqlite3_prepare_v3(
+            db,
+            c"SELECT filled_blob(?1)".as_ptr(),
+            -1,
+            0,
+            &mut stmt as *mut _,
+            std::ptr::null_mut(),
+        );
+        assert_eq!(SQLITE_OK, ret);
+
+        sqlite3_bind_int64(stmt, 1, limit as i64);
+        assert_eq!(SQLITE_ROW, sqlite3_step(stmt));
+        let blob = sqlite3_column_blob(stmt, 0);
+        assert_eq!(limit, sqlite3_column_bytes(stmt, 0));
+        let blob = std::slice::from_raw_parts(blob.cast::<u8>(), limit as usize);
+        assert!(blob.iter().all(|&byte| byte == 0xab));
+        sqlite3_reset(stmt);
+
+        sqlite3_bind_int64(stmt, 1, limit as i64 + 1);
+        assert_eq!(SQLITE_TOOBIG, sqlite3_step(stmt));
+        sqlite3_finalize(stmt);
+
+        sqlite3_close(db);
+    }
+}
+
+type XFunc = unsafe extern "C" fn(*mut sqlite3_context, c_int, *mut *mut sqlite3_value);
+
+/// A large text, so a copy of it shows up in the memory highwater.
+fn big_text() -> &'static str {
+    static TEXT: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+    TEXT.get_or_init(|| "x".repeat(1 << 16))
+}
+
+unsafe extern "C" fn x_static_big(
+    ctx: *mut sqlite3_context,
+    _argc: c_int,
+    _argv: *mut *mut sqlite3_value,
+) {
+    result_static_text(ctx, big_text());
+}
+
+unsafe extern "C" fn x_copied_big(
+    ctx: *mut sqlite3_context,
+    _argc: c_int,
+    _argv: *mut *mut sqlite3_value,
+) {
+    result_text(ctx, big_text());
+}
+
+#[wasm_bindgen_test]
+fn test_result_static_text() {
+    let db = memory_db();
+    unsafe {
+        let register = |name: &CStr, func: XFunc| {
+            let ret = sqlite3_create_function_v2(
+                db,
+                name.as_ptr(),
+                0,
+                SQLITE_UTF8,
+                std::ptr::null_mut(),
+                Some(func),
+                None,
+                None,
+                None,
+            );
+            assert_eq!(SQLITE_OK, ret);
+        };
+        register(c"static_big", x_static_big);
+        register(c"copied_big", x_copied_big);
+
+        // Peak memory of running a function 1000 times.
+        let peak = |sql: &CStr| {
+            let mut stmt = std::ptr::null_mut();
+            let ret = sqlite3_prepare_v3(
+                db,
+                sql.as_ptr(),
+                -1,
+                0,
+                &mut stmt as *mut _,
+                std::ptr::null_mut(),
+            );
+            assert_eq!(SQLITE_OK, ret);
+            let used = sqlite3_memory_used();
+            sqlite3_memory_highwater(1);
+            assert_eq!(SQLITE_ROW, sqlite3_step(stmt));
+            assert_eq!(1000, sqlite3_column_int64(stmt, 0));
+            let peak = sqlite3_memory_highwater(0) - used;
+            sqlite3_finalize(stmt);
+            peak
+        };
+        let static_peak = peak(
+            c"WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n WHERE x < 1000)
+            SELECT count(*) FROM n WHERE static_big() IS NOT NULL",
+        );
+        let copied_peak = peak(
+            c"WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n WHERE x < 1000)
+            SELECT count(*) FROM n WHERE copied_big() IS NOT NULL",
+        );
+        let len = big_text().len() as i64;
+        assert!(static_peak < len);
+        assert!(copied_peak >= len);
+
+        let mut stmt = std::ptr::null_mut();
+        let ret = sqlite3_prepare_v3(
+            db,
+            c"SELECT static_big() = copied_big()".as_ptr(),
+            -1,
+            0,
+            &mut stmt as *mut _,
+            std::ptr::null_mut(),
+        );
+        assert_eq!(SQLITE_OK, ret);
+        assert_eq!(SQLITE_ROW, sqlite3_step(stmt));
+        assert_eq!(1, sqlite3_column_int(stmt, 0));
+        sqlite3_finalize(stmt);
+
+        sqlite3_close(db);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_result_value() {
+    let db = memory_db();
+    unsafe {
+        let ret = sqlite3_create_function_v2(
+            db,
+            c"identity".as_ptr(),
+            1,
+            SQLITE_UTF8 | SQLITE_DETERMINISTIC,
+            std::ptr::null_mut(),
+            Some(x_identity),
+            None,
+            None,
+            None,
+        );
+        assert_eq!(SQLITE_OK, ret);
+    }
+
+    super::exec(
+        db,
+        c"CREATE TABLE t AS SELECT identity(42) AS i, identity(1.5) AS r,
+            identity('text') AS s, identity(x'00ff01') AS b, identity(NULL) AS n,
+            hex(identity(x'00ff01')) AS h",
+    );
+    assert_eq!(
+        table_rows(db, "t"),
+        ["1:42,2:1.5,3:text,blob(3),NULL,3:00FF01"]
+    );
+
+    unsafe { sqlite3_close(db) };
+}
@@ -0,0 +1,45 @@
+use sqlite_wasm_rs::helpers::{query_column, Savepoint};
+use sqlite_wasm_rs::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::{exec, memory_db};
+
+fn values(db: *mut sqlite3) -> Vec<i64> {
+    unsafe { query_column(db, "SELECT x FROM t ORDER BY x", ()) }.unwrap()
+}
+
+#[wasm_bindgen_test]
+fn test_savepoint() {
+    let db = memory_db();
+    exec(db, c"CREATE TABLE t (x INTEGER)");
+
+    unsafe {
+        let outer = Savepoint::new(db).unwrap();
+        assert!(outer.is_outermost());
+        assert_eq!(0, sqlite3_get_autocommit(db));
+        exec(db, c"INSERT INTO t VALUES (1)");
+
+        let inner = Savepoint::new(db).unwrap();
+        assert!(!inner.is_outermost());
+        assert_ne!(outer.name(), inner.name());
+        exec(db, c"INSERT INTO t VALUES (2)");
+        assert_eq!(vec![1, 2], values(db));
+        inner.rollback().unwrap();
+        assert_eq!(vec![1], values(db));
+
+        exec(db, c"INSERT INTO t VALUES (3)");
+        outer.release().unwrap();
+        assert_ne!(0, sqlite3_get_autocommit(db));
+        assert_eq!(vec![1, 3], values(db));
+
+        // Dropping the guard rolls back.
+        {
+            let _savepoint = Savepoint::new(db).unwrap();
+            exec(db, c"INSERT INTO t VALUES (4)");
+        }
+        assert_ne!(0, sqlite3_get_autocommit(db));
+        assert_eq!(vec![1, 3], values(db));
+
+        sqlite3_close(db);
+    }
+}
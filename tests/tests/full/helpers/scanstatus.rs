@@ -0,0 +1,58 @@
+use sqlite_wasm_rs::helpers::scan_status;
+use sqlite_wasm_rs::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::{exec, memory_db};
+
+#[wasm_bindgen_test]
+fn test_scan_status() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE a (id INTEGER PRIMARY KEY, b_id INT);
+        CREATE TABLE b (id INTEGER PRIMARY KEY, name TEXT);
+        WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n WHERE x < 50)
+        INSERT INTO a SELECT x, x % 5 FROM n;
+        INSERT INTO b VALUES (0, 'zero'), (1, 'one'), (2, 'two'), (3, 'three'), (4, 'four');",
+    );
+
+    let mut stmt = std::ptr::null_mut();
+    unsafe {
+        let ret = sqlite3_prepare_v3(
+            db,
+            c"SELECT a.id, b.name FROM a JOIN b ON b.id = a.b_id".as_ptr(),
+            -1,
+            0,
+            &mut stmt as *mut _,
+            std::ptr::null_mut(),
+        );
+        assert_eq!(SQLITE_OK, ret);
+        let mut rows = 0;
+        while sqlite3_step(stmt) == SQLITE_ROW {
+            rows += 1;
+        }
+        assert_eq!(50, rows);
+
+        let stats = scan_status(stmt);
+        assert_eq!(2, stats.len());
+        for stat in &stats {
+            assert!(stat.loops > 0 && stat.visits > 0);
+            assert!(stat.estimated_rows > 0.0);
+            assert!(!stat.explain.as_deref().unwrap_or_default().is_empty());
+        }
+        let mut names = stats
+            .iter()
+            .map(|stat| stat.name.clone().unwrap())
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, ["a", "b"]);
+        // Every row of the outer loop visits one row of the inner loop.
+        assert_eq!(50, stats.iter().map(|stat| stat.visits).min().unwrap());
+
+        sqlite3_stmt_scanstatus_reset(stmt);
+        assert!(scan_status(stmt).iter().all(|stat| stat.visits == 0));
+
+        sqlite3_finalize(stmt);
+        sqlite3_close(db);
+    }
+}
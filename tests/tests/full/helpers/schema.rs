@@ -0,0 +1,155 @@
+use sqlite_wasm_rs::helpers::{
+    db_name, ensure_index, list_tables, list_triggers, list_views, table_indexes, IndexInfo,
+    TriggerInfo,
+};
+use sqlite_wasm_rs::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::{exec, memory_db};
+
+#[wasm_bindgen_test]
+fn test_table_indexes() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE t (id INTEGER PRIMARY KEY, a TEXT, b INT, c TEXT UNIQUE);
+        CREATE UNIQUE INDEX t_a_b ON t (a, b DESC);
+        CREATE INDEX t_lower ON t (lower(a), b);",
+    );
+
+    let mut indexes = unsafe { table_indexes(db, "t") }.unwrap();
+    indexes.sort_by(|x, y| x.name.cmp(&y.name));
+    assert_eq!(
+        indexes,
+        [
+            IndexInfo {
+                name: "sqlite_autoindex_t_1".into(),
+                unique: true,
+                columns: vec!["c".into()],
+            },
+            IndexInfo {
+                name: "t_a_b".into(),
+                unique: true,
+                columns: vec!["a".into(), "b".into()],
+            },
+            IndexInfo {
+                name: "t_lower".into(),
+                unique: false,
+                columns: vec!["".into(), "b".into()],
+            },
+        ]
+    );
+
+    assert!(unsafe { table_indexes(db, "missing") }.unwrap().is_empty());
+
+    unsafe { sqlite3_close(db) };
+}
+
+#[wasm_bindgen_test]
+fn test_db_name() {
+    let db = memory_db();
+    exec(db, c"ATTACH DATABASE ':memory:' AS scratch;");
+    unsafe {
+        assert_eq!(Some("main".into()), db_name(db, 0));
+        assert_eq!(Some("temp".into()), db_name(db, 1));
+        assert_eq!(Some("scratch".into()), db_name(db, 2));
+        assert_eq!(None, db_name(db, 3));
+        assert_eq!(None, db_name(db, -1));
+        sqlite3_close(db);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_list_tables_and_views() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE users (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT);
+        CREATE TABLE orders (id INTEGER PRIMARY KEY, user_id INT);
+        CREATE VIEW active_users AS SELECT * FROM users;
+        CREATE INDEX orders_user ON orders (user_id);
+        INSERT INTO users (name) VALUES ('a');
+        CREATE TEMP TABLE scratch (x);",
+    );
+    unsafe {
+        assert_eq!(list_tables(db).unwrap(), ["orders", "users"]);
+        assert_eq!(list_views(db).unwrap(), ["active_users"]);
+        sqlite3_close(db);
+    }
+
+    let db = memory_db();
+    unsafe {
+        assert!(list_tables(db).unwrap().is_empty());
+        assert!(list_views(db).unwrap().is_empty());
+        sqlite3_close(db);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_list_triggers() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);
+        CREATE TABLE audit (user_id INT, action TEXT);
+        CREATE VIEW names AS SELECT name FROM users;
+        CREATE TRIGGER users_insert AFTER INSERT ON users BEGIN
+            INSERT INTO audit VALUES (new.id, 'insert');
+        END;
+        CREATE TRIGGER names_insert INSTEAD OF INSERT ON names BEGIN
+            INSERT INTO users (name) VALUES (new.name);
+        END;",
+    );
+    unsafe {
+        let users_insert = TriggerInfo {
+            name: "users_insert".into(),
+            table: "users".into(),
+            sql: "CREATE TRIGGER users_insert AFTER INSERT ON users BEGIN
+            INSERT INTO audit VALUES (new.id, 'insert');
+        END"
+            .into(),
+        };
+        assert_eq!(
+            list_triggers(db, Some("Users")).unwrap(),
+            [users_insert.clone()]
+        );
+
+        let all = list_triggers(db, None).unwrap();
+        assert_eq!(
+            all.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+            ["names_insert", "users_insert"]
+        );
+        assert_eq!("names", all[0].table);
+        assert_eq!(users_insert, all[1]);
+
+        assert!(list_triggers(db, Some("audit")).unwrap().is_empty());
+        sqlite3_close(db);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_ensure_index() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE users (id INTEGER PRIMARY KEY, email TEXT);",
+    );
+
+    unsafe {
+        let ddl = "CREATE INDEX IF NOT EXISTS users_email ON users (email)";
+        assert!(ensure_index(db, ddl).unwrap());
+        assert!(!ensure_index(db, ddl).unwrap());
+        assert_eq!(
+            vec!["users_email".to_string()],
+            table_indexes(db, "users")
+                .unwrap()
+                .into_iter()
+                .map(|index| index.name)
+                .collect::<Vec<_>>()
+        );
+
+        assert!(ensure_index(db, "CREATE INDEX IF NOT EXISTS missing_x ON missing (x)").is_err());
+
+        sqlite3_close(db);
+    }
+}
@@ -0,0 +1,43 @@
+use sqlite_wasm_rs::helpers::{create_seeded_random, query_column};
+use sqlite_wasm_rs::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::memory_db;
+
+fn sample(db: *mut sqlite3) -> Vec<i64> {
+    unsafe {
+        query_column(
+            db,
+            "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n WHERE x < 8) \
+             SELECT seeded_random() FROM n",
+            (),
+        )
+    }
+    .unwrap()
+}
+
+#[wasm_bindgen_test]
+fn test_create_seeded_random() {
+    let db = memory_db();
+    let other = memory_db();
+    unsafe {
+        create_seeded_random(db, 42).unwrap();
+        let first = sample(db);
+        assert_eq!(8, first.len());
+        assert!(first.windows(2).all(|pair| pair[0] != pair[1]));
+        assert_ne!(first, sample(db));
+
+        // Reseeding repeats the sequence.
+        create_seeded_random(db, 42).unwrap();
+        assert_eq!(first, sample(db));
+
+        // Every connection has its own state.
+        create_seeded_random(other, 42).unwrap();
+        assert_eq!(first, sample(other));
+        create_seeded_random(other, 43).unwrap();
+        assert_ne!(first, sample(other));
+
+        sqlite3_close(db);
+        sqlite3_close(other);
+    }
+}
@@ -0,0 +1,183 @@
+use sqlite_wasm_rs::helpers::{
+    clone_db, is_sqlite_database, serialize_to_vec, serialized_size, vacuum_into_vec,
+};
+use sqlite_wasm_rs::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::{exec, load_db, memory_db, table_rows};
+
+#[wasm_bindgen_test]
+fn test_serialize_to_vec() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT, data BLOB);
+        INSERT INTO t VALUES (1, 'a', x'0102'), (2, 'b', NULL);",
+    );
+    let bytes = unsafe { serialize_to_vec(db, c"main") }.unwrap();
+    assert!(bytes.starts_with(b"SQLite format 3\0"));
+
+    // Load the bytes into a fresh connection and serialize it again.
+    let copy = memory_db();
+    unsafe {
+        let buf = sqlite3_malloc64(bytes.len() as u64).cast::<u8>();
+        assert!(!buf.is_null());
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), buf, bytes.len());
+        let ret = sqlite3_deserialize(
+            copy,
+            c"main".as_ptr(),
+            buf,
+            bytes.len() as i64,
+            bytes.len() as i64,
+            SQLITE_DESERIALIZE_FREEONCLOSE | SQLITE_DESERIALIZE_RESIZEABLE,
+        );
+        assert_eq!(SQLITE_OK, ret);
+    }
+    assert_eq!(bytes, unsafe { serialize_to_vec(copy, c"main") }.unwrap());
+    assert_eq!(table_rows(db, "t"), table_rows(copy, "t"));
+
+    let err = unsafe { serialize_to_vec(db, c"missing") }.unwrap_err();
+    assert_eq!(SQLITE_ERROR, err.extended_code);
+
+    unsafe {
+        sqlite3_close(db);
+        sqlite3_close(copy);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_is_sqlite_database() {
+    let db = memory_db();
+    exec(db, c"CREATE TABLE t (x); INSERT INTO t VALUES (1);");
+    let bytes = unsafe { serialize_to_vec(db, c"main") }.unwrap();
+    assert!(is_sqlite_database(&bytes));
+
+    // Pseudo-random bytes of a plausible size.
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let random = (0..bytes.len())
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect::<Vec<_>>();
+    assert!(!is_sqlite_database(&random));
+
+    // Valid magic but broken page size or length.
+    let mut bad_page_size = bytes.clone();
+    bad_page_size[16..18].copy_from_slice(&1000u16.to_be_bytes());
+    assert!(!is_sqlite_database(&bad_page_size));
+    assert!(!is_sqlite_database(&bytes[..bytes.len() - 512]));
+    assert!(!is_sqlite_database(&bytes[..100]));
+    assert!(!is_sqlite_database(&[]));
+
+    unsafe { sqlite3_close(db) };
+}
+
+#[wasm_bindgen_test]
+fn test_vacuum_into_vec() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE t (id INTEGER PRIMARY KEY, payload TEXT);
+        WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n WHERE x < 2000)
+        INSERT INTO t SELECT x, printf('%.500c', 'x') FROM n;
+        DELETE FROM t WHERE id > 10;",
+    );
+    let plain = unsafe { serialize_to_vec(db, c"main") }.unwrap();
+    let vacuumed = unsafe { vacuum_into_vec(db) }.unwrap();
+    assert!(is_sqlite_database(&vacuumed));
+    assert!(vacuumed.len() < plain.len());
+    // The source database keeps its free pages.
+    assert_eq!(plain, unsafe { serialize_to_vec(db, c"main") }.unwrap());
+
+    let copy = load_db(&vacuumed);
+    assert_eq!(table_rows(db, "t"), table_rows(copy, "t"));
+
+    // A second call starts from an empty target again.
+    assert_eq!(vacuumed, unsafe { vacuum_into_vec(db) }.unwrap());
+
+    unsafe {
+        sqlite3_close(db);
+        sqlite3_close(copy);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_clone_db() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT);
+        INSERT INTO t VALUES (1, 'a'), (2, 'b');",
+    );
+
+    let clone = unsafe { clone_db(db) }.unwrap();
+    assert_eq!(table_rows(db, "t"), table_rows(clone, "t"));
+
+    // Writes to the clone don't reach the original, and the other way round.
+    exec(
+        clone,
+        c"INSERT INTO t VALUES (3, 'c'); DELETE FROM t WHERE id = 1;",
+    );
+    exec(db, c"UPDATE t SET name = 'x' WHERE id = 2;");
+    assert_eq!(table_rows(db, "t"), ["1:1,3:a", "1:2,3:x"]);
+    assert_eq!(table_rows(clone, "t"), ["1:2,3:b", "1:3,3:c"]);
+
+    // A database marked as WAL is cloned with a rollback journal header.
+    let mut wal = unsafe { serialize_to_vec(db, c"main") }.unwrap();
+    wal[18] = 2;
+    wal[19] = 2;
+    let wal_db = load_db(&wal);
+    let wal_clone = unsafe { clone_db(wal_db) }.unwrap();
+    let bytes = unsafe { serialize_to_vec(wal_clone, c"main") }.unwrap();
+    assert_eq!([1, 1], bytes[18..20]);
+    assert_eq!(table_rows(db, "t"), table_rows(wal_clone, "t"));
+
+    // An empty database clones to an empty one.
+    let empty = memory_db();
+    let empty_clone = unsafe { clone_db(empty) }.unwrap();
+    assert!(unsafe { serialize_to_vec(empty_clone, c"main") }
+        .unwrap()
+        .is_empty());
+
+    unsafe {
+        for db in [db, clone, wal_db, wal_clone, empty, empty_clone] {
+            sqlite3_close(db);
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_serialized_size() {
+    let db = memory_db();
+    assert_eq!(0, unsafe { serialized_size(db, c"main") }.unwrap());
+    exec(
+        db,
+        c"CREATE TABLE t (id INTEGER PRIMARY KEY, payload TEXT);
+        WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n WHERE x < 100)
+        INSERT INTO t SELECT x, printf('%.500c', 'x') FROM n;",
+    );
+
+    let bytes = unsafe { serialize_to_vec(db, c"main") }.unwrap();
+    assert_eq!(
+        bytes.len() as i64,
+        unsafe { serialized_size(db, c"main") }.unwrap()
+    );
+
+    // A deserialized database reports the size of its buffer.
+    let copy = load_db(&bytes);
+    assert_eq!(
+        bytes.len() as i64,
+        unsafe { serialized_size(copy, c"main") }.unwrap()
+    );
+
+    let err = unsafe { serialized_size(db, c"missing") }.unwrap_err();
+    assert_eq!(SQLITE_ERROR, err.extended_code);
+
+    unsafe {
+        sqlite3_close(db);
+        sqlite3_close(copy);
+    }
+}
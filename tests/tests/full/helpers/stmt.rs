@@ -0,0 +1,485 @@
+use sqlite_wasm_rs::helpers::{
+    bind_option, bind_row, column_as_bool, column_as_datetime, prepared_statements, rebind,
+    reset_all_statements, statement_columns, statement_returns_rows, step_row, ColumnSpec,
+    StatementIter, Value,
+};
+use sqlite_wasm_rs::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::{exec, memory_db, table_rows};
+
+#[wasm_bindgen_test]
+fn test_statement_iter() {
+    let db = memory_db();
+    let sql = "
+CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT);
+-- comments and empty statements are skipped
+INSERT INTO t (name) VALUES ('Alice');;
+INSERT INTO t (name) VALUES ('Bob');
+SELECT count(*) FROM t;
+    ";
+
+    let mut count = 0;
+    let mut rows = 0;
+    unsafe {
+        for stmt in StatementIter::new(db, sql) {
+            let stmt = stmt.unwrap();
+            while sqlite3_step(stmt) == SQLITE_ROW {
+                rows = sqlite3_column_int(stmt, 0);
+            }
+            assert_eq!(SQLITE_OK, sqlite3_finalize(stmt));
+            count += 1;
+        }
+    }
+    assert_eq!(4, count);
+    assert_eq!(2, rows);
+
+    let mut iter = unsafe { StatementIter::new(db, "SELECT 1; SELEC 2; SELECT 3;") };
+    unsafe { sqlite3_finalize(iter.next().unwrap().unwrap()) };
+    let err = iter.next().unwrap().unwrap_err();
+    assert_eq!(ErrorCode::Unknown, err.code);
+    assert_eq!(Some(10), err.offset);
+    assert!(iter.next().is_none());
+
+    unsafe { sqlite3_close(db) };
+}
+
+#[wasm_bindgen_test]
+fn test_step_row() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT, score REAL, data BLOB);
+        INSERT INTO t VALUES (1, 'a', 0.5, x'01'), (2, NULL, -1.0, x''), (3, 'c', NULL, NULL);",
+    );
+
+    let mut stmt = std::ptr::null_mut();
+    unsafe {
+        let ret = sqlite3_prepare_v3(
+            db,
+            c"SELECT * FROM t ORDER BY id".as_ptr(),
+            -1,
+            0,
+            &mut stmt as *mut _,
+            std::ptr::null_mut(),
+        );
+        assert_eq!(SQLITE_OK, ret);
+
+        let mut rows = vec![];
+        while let Some(row) = step_row(stmt).unwrap() {
+            rows.push(row);
+        }
+        assert_eq!(
+            rows,
+            [
+                vec![
+                    Value::Integer(1),
+                    Value::Text("a".into()),
+                    Value::Real(0.5),
+                    Value::Blob(vec![1]),
+                ],
+                vec![
+                    Value::Integer(2),
+                    Value::Null,
+                    Value::Real(-1.0),
+                    Value::Blob(vec![]),
+                ],
+                vec![
+                    Value::Integer(3),
+                    Value::Text("c".into()),
+                    Value::Null,
+                    Value::Null,
+                ],
+            ]
+        );
+        // The statement is left to the caller and can be reused.
+        assert_eq!(SQLITE_OK, sqlite3_reset(stmt));
+        assert_eq!(3, std::iter::from_fn(|| step_row(stmt).unwrap()).count());
+        assert_eq!(SQLITE_OK, sqlite3_finalize(stmt));
+
+        sqlite3_close(db);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_bind_row_blob_outlives_values() {
+    let db = memory_db();
+    let mut stmt = std::ptr::null_mut();
+    unsafe {
+        let ret = sqlite3_prepare_v3(
+            db,
+            c"SELECT ?1, length(?1), ?2".as_ptr(),
+            -1,
+            0,
+            &mut stmt as *mut _,
+            std::ptr::null_mut(),
+        );
+        assert_eq!(SQLITE_OK, ret);
+        let blob = (0..=255).collect::<Vec<u8>>();
+        // SQLite keeps its own copy, the values can be dropped right away.
+        bind_row(stmt, &[Value::Blob(blob.clone()), Value::Blob(vec![])]).unwrap();
+
+        // The blob stays bound across resets.
+        for _ in 0..3 {
+            let row = step_row(stmt).unwrap().unwrap();
+            assert_eq!(
+                row,
+                [
+                    Value::Blob(blob.clone()),
+                    Value::Integer(256),
+                    Value::Blob(vec![])
+                ]
+            );
+            assert_eq!(SQLITE_OK, sqlite3_reset(stmt));
+        }
+
+        sqlite3_finalize(stmt);
+        sqlite3_close(db);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_bind_option() {
+    let db = memory_db();
+    exec(db, c"CREATE TABLE t (a INTEGER, b INTEGER)");
+    let mut stmt = std::ptr::null_mut();
+    unsafe {
+        let ret = sqlite3_prepare_v3(
+            db,
+            c"INSERT INTO t VALUES (?1, ?2)".as_ptr(),
+            -1,
+            0,
+            &mut stmt as *mut _,
+            std::ptr::null_mut(),
+        );
+        assert_eq!(SQLITE_OK, ret);
+        bind_option(stmt, 1, None::<i64>).unwrap();
+        bind_option(stmt, 2, Some(5i64)).unwrap();
+        assert_eq!(SQLITE_DONE, sqlite3_step(stmt));
+
+        let err = bind_option(stmt, 3, Some("x")).unwrap_err();
+        assert_eq!(SQLITE_RANGE, err.extended_code);
+        sqlite3_finalize(stmt);
+    }
+    assert_eq!(table_rows(db, "t"), ["NULL,1:5"]);
+    unsafe { sqlite3_close(db) };
+}
+
+#[wasm_bindgen_test]
+fn test_bind_row() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT, score REAL)",
+    );
+    let mut stmt = std::ptr::null_mut();
+    unsafe {
+        let ret = sqlite3_prepare_v3(
+            db,
+            c"INSERT INTO t VALUES (?, ?, ?)".as_ptr(),
+            -1,
+            0,
+            &mut stmt as *mut _,
+            std::ptr::null_mut(),
+        );
+        assert_eq!(SQLITE_OK, ret);
+
+        bind_row(stmt, &[7.into(), "seven".into(), 0.5.into()]).unwrap();
+        assert_eq!(SQLITE_DONE, sqlite3_step(stmt));
+        assert_eq!(SQLITE_OK, sqlite3_reset(stmt));
+
+        for values in [vec![Value::Integer(8)], vec![Value::Null; 4]] {
+            let err = bind_row(stmt, &values).unwrap_err();
+            assert_eq!(SQLITE_RANGE, err.extended_code);
+        }
+        sqlite3_finalize(stmt);
+    }
+    assert_eq!(table_rows(db, "t"), ["1:7,3:seven,2:0.5"]);
+
+    unsafe { sqlite3_close(db) };
+}
+
+#[wasm_bindgen_test]
+fn test_prepared_statements() {
+    let db = memory_db();
+    assert!(unsafe { prepared_statements(db) }.is_empty());
+
+    let mut stmts = vec![];
+    for sql in [c"SELECT 1", c"SELECT 2", c"SELECT 3"] {
+        let mut stmt = std::ptr::null_mut();
+        let ret = unsafe {
+            sqlite3_prepare_v3(
+                db,
+                sql.as_ptr(),
+                -1,
+                0,
+                &mut stmt as *mut _,
+                std::ptr::null_mut(),
+            )
+        };
+        assert_eq!(SQLITE_OK, ret);
+        stmts.push(stmt);
+    }
+
+    // The most recently prepared statement comes first.
+    let open = unsafe { prepared_statements(db) };
+    stmts.reverse();
+    assert_eq!(stmts, open);
+
+    for stmt in open {
+        unsafe { sqlite3_finalize(stmt) };
+    }
+    assert!(unsafe { prepared_statements(db) }.is_empty());
+    assert_eq!(SQLITE_OK, unsafe { sqlite3_close(db) });
+}
+
+#[wasm_bindgen_test]
+fn test_reset_all_statements() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE t (id INTEGER PRIMARY KEY);
+        INSERT INTO t VALUES (1), (2), (3);",
+    );
+
+    let mut stmts = vec![];
+    for sql in [
+        c"SELECT id FROM t ORDER BY id",
+        c"SELECT id * 10 FROM t ORDER BY id",
+        c"SELECT id FROM t ORDER BY id DESC",
+    ] {
+        let mut stmt = std::ptr::null_mut();
+        let ret = unsafe {
+            sqlite3_prepare_v3(
+                db,
+                sql.as_ptr(),
+                -1,
+                0,
+                &mut stmt as *mut _,
+                std::ptr::null_mut(),
+            )
+        };
+        assert_eq!(SQLITE_OK, ret);
+        stmts.push(stmt);
+    }
+
+    let first_rows = |stmts: &[*mut sqlite3_stmt]| -> Vec<i64> {
+        stmts
+            .iter()
+            .map(|&stmt| unsafe {
+                assert_eq!(SQLITE_ROW, sqlite3_step(stmt));
+                sqlite3_column_int64(stmt, 0)
+            })
+            .collect()
+    };
+
+    // Leave every statement in the middle of its rows.
+    assert_eq!(first_rows(&stmts), [1, 10, 3]);
+    assert_eq!(first_rows(&stmts), [2, 20, 2]);
+    assert!(stmts
+        .iter()
+        .all(|&stmt| unsafe { sqlite3_stmt_busy(stmt) } != 0));
+
+    unsafe { reset_all_statements(db) };
+    assert!(stmts
+        .iter()
+        .all(|&stmt| unsafe { sqlite3_stmt_busy(stmt) } == 0));
+    assert_eq!(first_rows(&stmts), [1, 10, 3]);
+
+    for stmt in stmts {
+        unsafe { sqlite3_finalize(stmt) };
+    }
+    unsafe { sqlite3_close(db) };
+}
+
+#[wasm_bindgen_test]
+fn test_statement_columns() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);
+        CREATE TABLE posts (id INTEGER PRIMARY KEY, user_id INTEGER, title VARCHAR(80));",
+    );
+
+    let mut stmt = std::ptr::null_mut();
+    unsafe {
+        let ret = sqlite3_prepare_v3(
+            db,
+            c"SELECT u.name AS author, p.title, p.id, count(*) AS n
+            FROM posts p JOIN users u ON u.id = p.user_id GROUP BY p.id"
+                .as_ptr(),
+            -1,
+            0,
+            &mut stmt as *mut _,
+            std::ptr::null_mut(),
+        );
+        assert_eq!(SQLITE_OK, ret);
+    }
+
+    let spec = |name: &str, declared_type: &str, table: &str, origin: &str| ColumnSpec {
+        name: name.into(),
+        declared_type: Some(declared_type.into()),
+        table: Some(table.into()),
+        origin: Some(origin.into()),
+    };
+    let columns = unsafe { statement_columns(stmt) };
+    assert_eq!(
+        columns,
+        [
+            spec("author", "TEXT", "users", "name"),
+            spec("title", "VARCHAR(80)", "posts", "title"),
+            spec("id", "INTEGER", "posts", "id"),
+            ColumnSpec {
+                name: "n".into(),
+                declared_type: None,
+                table: None,
+                origin: None,
+            },
+        ]
+    );
+
+    unsafe {
+        sqlite3_finalize(stmt);
+        sqlite3_close(db);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_rebind() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT);
+        INSERT INTO t VALUES (1, 'a'), (2, 'b'), (3, 'c');",
+    );
+
+    let mut stmt = std::ptr::null_mut();
+    unsafe {
+        let ret = sqlite3_prepare_v3(
+            db,
+            c"SELECT id, ?2 FROM t WHERE name = ?1".as_ptr(),
+            -1,
+            0,
+            &mut stmt as *mut _,
+            std::ptr::null_mut(),
+        );
+        assert_eq!(SQLITE_OK, ret);
+
+        rebind(stmt, &["a".into(), 10i64.into()]).unwrap();
+        assert_eq!(
+            step_row(stmt).unwrap(),
+            Some(vec![Value::Integer(1), Value::Integer(10)])
+        );
+
+        // Rebinding in the middle of the rows resets the statement, and the
+        // parameter without a new value is cleared.
+        rebind(stmt, &["c".into()]).unwrap();
+        assert_eq!(
+            step_row(stmt).unwrap(),
+            Some(vec![Value::Integer(3), Value::Null])
+        );
+        assert_eq!(step_row(stmt).unwrap(), None);
+
+        let err = rebind(stmt, &["a".into(), 1i64.into(), 2i64.into()]).unwrap_err();
+        assert_eq!(SQLITE_RANGE, err.extended_code);
+
+        sqlite3_finalize(stmt);
+        sqlite3_close(db);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_statement_returns_rows() {
+    let db = memory_db();
+    exec(db, c"CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT);");
+
+    unsafe {
+        for (sql, expected) in [
+            (c"SELECT id, name FROM t", true),
+            (c"INSERT INTO t (name) VALUES ('a')", false),
+            (c"INSERT INTO t (name) VALUES ('b') RETURNING id", true),
+        ] {
+            let mut stmt = std::ptr::null_mut();
+            let ret = sqlite3_prepare_v3(
+                db,
+                sql.as_ptr(),
+                -1,
+                0,
+                &mut stmt as *mut _,
+                std::ptr::null_mut(),
+            );
+            assert_eq!(SQLITE_OK, ret);
+            assert_eq!(expected, statement_returns_rows(stmt), "{sql:?}");
+            sqlite3_finalize(stmt);
+        }
+        sqlite3_close(db);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_column_as_bool_and_datetime() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE t (id INTEGER PRIMARY KEY, active BOOLEAN, at DATETIME, name TEXT);
+        INSERT INTO t (active, at, name) VALUES
+            (0, '2024-01-02 03:04:05.250', 'a'),
+            (1, 1704164645, 'b'),
+            ('TRUE', 2460311.5, 'c'),
+            (NULL, NULL, 'd'),
+            ('maybe', 'soon', 'e'),
+            (1, 1704164645.5, 'f'),
+            (1, 2460311, 'g'),
+            (1, '2024-01-02T05:04:05.5+02:00', 'h');",
+    );
+
+    let mut stmt = std::ptr::null_mut();
+    unsafe {
+        let ret = sqlite3_prepare_v3(
+            db,
+            c"SELECT active, at, name, at > 0 FROM t ORDER BY id".as_ptr(),
+            -1,
+            0,
+            &mut stmt as *mut _,
+            std::ptr::null_mut(),
+        );
+        assert_eq!(SQLITE_OK, ret);
+
+        let mut rows = Vec::new();
+        while sqlite3_step(stmt) == SQLITE_ROW {
+            rows.push((
+                column_as_bool(stmt, 0).map_err(|err| err.extended_code),
+                column_as_datetime(stmt, 1).map_err(|err| err.extended_code),
+            ));
+            // A TEXT column is neither, an expression has no declared type.
+            assert_eq!(
+                SQLITE_MISMATCH,
+                column_as_bool(stmt, 2).unwrap_err().extended_code
+            );
+            assert_eq!(
+                SQLITE_MISMATCH,
+                column_as_datetime(stmt, 2).unwrap_err().extended_code
+            );
+            assert!(column_as_bool(stmt, 3).is_ok());
+        }
+        assert_eq!(
+            rows,
+            [
+                (Ok(Some(false)), Ok(Some(1704164645.25))),
+                (Ok(Some(true)), Ok(Some(1704164645.0))),
+                (Ok(Some(true)), Ok(Some(1704153600.0))),
+                (Ok(None), Ok(None)),
+                (Err(SQLITE_MISMATCH), Err(SQLITE_MISMATCH)),
+                // Numbers out of the Julian day range are Unix time, integer or real.
+                (Ok(Some(true)), Ok(Some(1704164645.5))),
+                // Numbers in range are Julian days, integer or real.
+                (Ok(Some(true)), Ok(Some(1704110400.0))),
+                // An offset is subtracted to get UTC.
+                (Ok(Some(true)), Ok(Some(1704164645.5))),
+            ]
+        );
+
+        sqlite3_finalize(stmt);
+        sqlite3_close(db);
+    }
+}
@@ -0,0 +1,71 @@
+use sqlite_wasm_rs::helpers::{get_table, StrBuilder};
+use sqlite_wasm_rs::*;
+use std::ffi::{c_int, CStr};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::memory_db;
+
+unsafe extern "C" fn x_join(ctx: *mut sqlite3_context, argc: c_int, argv: *mut *mut sqlite3_value) {
+    let mut text = StrBuilder::new(sqlite3_context_db_handle(ctx));
+    for idx in 0..argc as usize {
+        if idx > 0 {
+            text.push_str(", ");
+        }
+        let value = sqlite3_value_text(*argv.add(idx));
+        if !value.is_null() {
+            text.push_str(CStr::from_ptr(value.cast()).to_str().unwrap());
+        }
+    }
+    text.result(ctx);
+}
+
+#[wasm_bindgen_test]
+fn test_str_builder() {
+    let mut text = unsafe { StrBuilder::new(std::ptr::null_mut()) };
+    assert!(text.is_empty());
+    text.push_str("ab");
+    text.push_repeated('-', 3);
+    text.push_repeated('é', 2);
+    assert_eq!(9, text.len());
+    assert_eq!("ab---éé", text.finish().unwrap());
+
+    let empty = unsafe { StrBuilder::new(std::ptr::null_mut()) };
+    assert_eq!("", empty.finish().unwrap());
+
+    let db = memory_db();
+    unsafe {
+        let ret = sqlite3_create_function_v2(
+            db,
+            c"join_all".as_ptr(),
+            -1,
+            SQLITE_UTF8,
+            std::ptr::null_mut(),
+            Some(x_join),
+            None,
+            None,
+            None,
+        );
+        assert_eq!(SQLITE_OK, ret);
+
+        let (_, rows) = get_table(
+            db,
+            "SELECT join_all('a', 'b', 3), join_all(), typeof(join_all())",
+        )
+        .unwrap();
+        assert_eq!(
+            vec![vec![
+                Some("a, b, 3".to_string()),
+                Some("".to_string()),
+                Some("text".to_string()),
+            ]],
+            rows
+        );
+
+        // Results longer than SQLITE_LIMIT_LENGTH fail the statement.
+        sqlite3_limit(db, SQLITE_LIMIT_LENGTH, 10);
+        let err = get_table(db, "SELECT join_all('abcdef', 'ghijkl')").unwrap_err();
+        assert_eq!(SQLITE_TOOBIG, err.extended_code);
+
+        sqlite3_close(db);
+    }
+}
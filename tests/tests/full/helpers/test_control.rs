@@ -0,0 +1,41 @@
+use sqlite_wasm_rs::helpers::test_control;
+use sqlite_wasm_rs::*;
+use std::ffi::CStr;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::{exec, memory_db};
+
+#[wasm_bindgen_test]
+fn test_test_control() {
+    let db = memory_db();
+    let sql = c"SELECT datetime(0, 'unixepoch', 'localtime')";
+    exec(db, sql);
+
+    test_control(SQLITE_TESTCTRL_LOCALTIME_FAULT, 1).unwrap();
+    let ret = unsafe {
+        sqlite3_exec(
+            db,
+            sql.as_ptr(),
+            None,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    test_control(SQLITE_TESTCTRL_LOCALTIME_FAULT, 0).unwrap();
+    assert_eq!(SQLITE_ERROR, ret);
+    assert_eq!(
+        "local time unavailable",
+        unsafe { CStr::from_ptr(sqlite3_errmsg(db)) }
+            .to_str()
+            .unwrap()
+    );
+    exec(db, sql);
+
+    // Ops expecting other arguments are rejected.
+    let err = test_control(SQLITE_TESTCTRL_PRNG_SEED, 1).unwrap_err();
+    assert_eq!(SQLITE_MISUSE, err.extended_code);
+    let err = test_control(SQLITE_TESTCTRL_LOCALTIME_FAULT, 2).unwrap_err();
+    assert_eq!(SQLITE_MISUSE, err.extended_code);
+
+    unsafe { sqlite3_close(db) };
+}
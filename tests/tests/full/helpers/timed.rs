@@ -0,0 +1,31 @@
+use sqlite_wasm_rs::helpers::{timed_query, Value};
+use sqlite_wasm_rs::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::{exec, memory_db};
+
+#[wasm_bindgen_test]
+fn test_timed_query() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE t (x INTEGER);
+        WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n WHERE x < 1000)
+        INSERT INTO t SELECT x FROM n;",
+    );
+
+    let (rows, elapsed) = unsafe {
+        timed_query(
+            db,
+            "SELECT count(*), sum(x) FROM t WHERE x > ?1",
+            &[500.into()],
+        )
+    }
+    .unwrap();
+    assert_eq!(rows, [[Value::Integer(500), Value::Integer(375250)]]);
+    assert!(elapsed >= 0.0 && elapsed.is_finite(), "{elapsed}");
+
+    assert!(unsafe { timed_query(db, "SELECT * FROM missing", &[]) }.is_err());
+
+    unsafe { sqlite3_close(db) };
+}
@@ -0,0 +1,28 @@
+use sqlite_wasm_rs::helpers::{uri_key, uri_keys};
+use sqlite_wasm_rs::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+fn test_uri_keys() {
+    let mut db = std::ptr::null_mut();
+    let ret = unsafe {
+        sqlite3_open_v2(
+            c"file:test_uri_keys.db?a=1&b=2".as_ptr(),
+            &mut db as *mut _,
+            SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE | SQLITE_OPEN_URI,
+            std::ptr::null(),
+        )
+    };
+    assert_eq!(SQLITE_OK, ret);
+
+    let filename = unsafe { sqlite3_db_filename(db, c"main".as_ptr()) };
+    assert_eq!(vec!["a", "b"], unsafe { uri_keys(filename) });
+    assert_eq!(Some("b".to_string()), unsafe { uri_key(filename, 1) });
+    assert_eq!(None, unsafe { uri_key(filename, 2) });
+    for (key, value) in [(c"a", c"1"), (c"b", c"2")] {
+        let param = unsafe { sqlite3_uri_parameter(filename, key.as_ptr()) };
+        assert_eq!(value, unsafe { std::ffi::CStr::from_ptr(param) });
+    }
+
+    unsafe { sqlite3_close(db) };
+}
@@ -0,0 +1,212 @@
+use sqlite_wasm_rs::helpers::{
+    execute_returning, result_text, value_as_i64_or_text, value_bytes, value_len, Value,
+};
+use sqlite_wasm_rs::*;
+use std::ffi::c_int;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::{exec, memory_db};
+
+#[wasm_bindgen_test]
+fn test_zero_length_blob() {
+    let db = memory_db();
+    exec(db, c"CREATE TABLE t (id INTEGER PRIMARY KEY, b BLOB)");
+
+    // An empty `Vec` is bound as a zero-length BLOB, not as NULL.
+    let rows = unsafe {
+        execute_returning(
+            db,
+            "INSERT INTO t (b) VALUES (?1), (x''), (zeroblob(0)) RETURNING typeof(b), length(b), b",
+            &[Value::Blob(vec![])],
+        )
+    }
+    .unwrap();
+    assert_eq!(3, rows.len());
+    for row in rows {
+        assert_eq!(
+            row,
+            [
+                Value::Text("blob".into()),
+                Value::Integer(0),
+                Value::Blob(vec![])
+            ]
+        );
+    }
+
+    // The raw API returns a NULL pointer with a length of 0 for these.
+    let mut stmt = std::ptr::null_mut();
+    unsafe {
+        let ret = sqlite3_prepare_v3(
+            db,
+            c"SELECT b FROM t".as_ptr(),
+            -1,
+            0,
+            &mut stmt as *mut _,
+            std::ptr::null_mut(),
+        );
+        assert_eq!(SQLITE_OK, ret);
+        while sqlite3_step(stmt) == SQLITE_ROW {
+            assert_eq!(SQLITE_BLOB, sqlite3_column_type(stmt, 0));
+            assert!(sqlite3_column_blob(stmt, 0).is_null());
+            assert_eq!(0, sqlite3_column_bytes(stmt, 0));
+        }
+        sqlite3_finalize(stmt);
+        sqlite3_close(db);
+    }
+}
+
+unsafe extern "C" fn x_as_int(
+    ctx: *mut sqlite3_context,
+    _argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    match value_as_i64_or_text(*argv) {
+        Value::Integer(value) => sqlite3_result_int64(ctx, value),
+        Value::Text(text) => result_text(ctx, &text),
+        _ => sqlite3_result_null(ctx),
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_value_as_i64_or_text() {
+    let db = memory_db();
+    unsafe {
+        let ret = sqlite3_create_function_v2(
+            db,
+            c"as_int".as_ptr(),
+            1,
+            SQLITE_UTF8 | SQLITE_DETERMINISTIC,
+            std::ptr::null_mut(),
+            Some(x_as_int),
+            None,
+            None,
+            None,
+        );
+        assert_eq!(SQLITE_OK, ret);
+    }
+
+    let rows = unsafe {
+        execute_returning(
+            db,
+            "SELECT as_int(?1), typeof(as_int(?1)) UNION ALL
+            SELECT as_int(' 42 '), typeof(as_int(' 42 ')) UNION ALL
+            SELECT as_int('9223372036854775808'), typeof(as_int('9223372036854775808')) UNION ALL
+            SELECT as_int('1.5'), typeof(as_int('1.5')) UNION ALL
+            SELECT as_int('12abc'), typeof(as_int('12abc')) UNION ALL
+            SELECT as_int(7), typeof(as_int(7)) UNION ALL
+            SELECT as_int(NULL), typeof(as_int(NULL))",
+            &["9223372036854775807".into()],
+        )
+    }
+    .unwrap();
+    assert_eq!(
+        rows,
+        [
+            vec![Value::Integer(i64::MAX), "integer".into()],
+            vec![Value::Integer(42), "integer".into()],
+            vec!["9223372036854775808".into(), "text".into()],
+            vec!["1.5".into(), "text".into()],
+            vec!["12abc".into(), "text".into()],
+            vec![Value::Integer(7), "integer".into()],
+            vec![Value::Null, "null".into()],
+        ]
+    );
+
+    unsafe { sqlite3_close(db) };
+}
+
+unsafe extern "C" fn x_value_len(
+    ctx: *mut sqlite3_context,
+    _argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    sqlite3_result_int64(ctx, value_len(*argv) as i64);
+}
+
+#[wasm_bindgen_test]
+fn test_value_len() {
+    for encoding in [
+        c"PRAGMA encoding = 'UTF-8'",
+        c"PRAGMA encoding = 'UTF-16le'",
+    ] {
+        let db = memory_db();
+        exec(db, encoding);
+        exec(
+            db,
+            c"CREATE TABLE t (id INTEGER PRIMARY KEY, v);
+            INSERT INTO t (v) VALUES ('héllo 世界'), (x'00ff00'), (12345), (-1.5), (''), (NULL);",
+        );
+        unsafe {
+            let ret = sqlite3_create_function_v2(
+                db,
+                c"value_len".as_ptr(),
+                1,
+                SQLITE_UTF8 | SQLITE_DETERMINISTIC,
+                std::ptr::null_mut(),
+                Some(x_value_len),
+                None,
+                None,
+                None,
+            );
+            assert_eq!(SQLITE_OK, ret);
+
+            let rows =
+                execute_returning(db, "SELECT value_len(v) FROM t ORDER BY id", &[]).unwrap();
+            // 8 characters, but `é` takes 2 bytes and `世` and `界` take 3 each.
+            assert_eq!(
+                rows,
+                [13, 3, 5, 4, 0, 0].map(|len: i64| vec![Value::Integer(len)]),
+                "{encoding:?}"
+            );
+
+            sqlite3_close(db);
+        }
+    }
+}
+
+unsafe extern "C" fn x_value_bytes(
+    ctx: *mut sqlite3_context,
+    _argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    let bytes = value_bytes(*argv);
+    sqlite3_result_blob(
+        ctx,
+        bytes.as_ptr().cast(),
+        bytes.len() as c_int,
+        SQLITE_TRANSIENT(),
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_value_bytes() {
+    let db = memory_db();
+    exec(
+        db,
+        c"CREATE TABLE t (id INTEGER PRIMARY KEY, v);
+        INSERT INTO t (v) VALUES ('héllo'), (x'00ff00'), (12345), (''), (NULL);",
+    );
+    unsafe {
+        let ret = sqlite3_create_function_v2(
+            db,
+            c"value_bytes".as_ptr(),
+            1,
+            SQLITE_UTF8 | SQLITE_DETERMINISTIC,
+            std::ptr::null_mut(),
+            Some(x_value_bytes),
+            None,
+            None,
+            None,
+        );
+        assert_eq!(SQLITE_OK, ret);
+
+        let rows = execute_returning(db, "SELECT value_bytes(v) FROM t ORDER BY id", &[]).unwrap();
+        assert_eq!(
+            rows,
+            ["héllo".as_bytes(), &[0, 0xff, 0], b"12345", &[], &[],]
+                .map(|bytes: &[u8]| vec![Value::Blob(bytes.to_vec())])
+        );
+
+        sqlite3_close(db);
+    }
+}
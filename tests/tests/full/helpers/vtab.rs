@@ -0,0 +1,196 @@
+use sqlite_wasm_rs::helpers::{get_table, set_trusted_schema, vtab_config, VtabConfig};
+use sqlite_wasm_rs::*;
+use std::ffi::{c_char, c_int, c_void};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use super::{exec, memory_db};
+
+/// A table with the single row `(42)`, declaring the `VtabConfig` passed as
+/// module client data.
+#[repr(C)]
+struct OneCursor {
+    base: sqlite3_vtab_cursor,
+    eof: bool,
+}
+
+unsafe extern "C" fn one_connect(
+    db: *mut sqlite3,
+    aux: *mut c_void,
+    _argc: c_int,
+    _argv: *const *const c_char,
+    pp_vtab: *mut *mut sqlite3_vtab,
+    _err: *mut *mut c_char,
+) -> c_int {
+    if let Err(err) = vtab_config(db, *aux.cast::<VtabConfig>()) {
+        return err.extended_code;
+    }
+    let ret = sqlite3_declare_vtab(db, c"CREATE TABLE x(value INTEGER)".as_ptr());
+    if ret == SQLITE_OK {
+        *pp_vtab = Box::into_raw(Box::<sqlite3_vtab>::default());
+    }
+    ret
+}
+
+unsafe extern "C" fn one_disconnect(vtab: *mut sqlite3_vtab) -> c_int {
+    drop(Box::from_raw(vtab));
+    SQLITE_OK
+}
+
+unsafe extern "C" fn one_best_index(
+    _vtab: *mut sqlite3_vtab,
+    _info: *mut sqlite3_index_info,
+) -> c_int {
+    SQLITE_OK
+}
+
+unsafe extern "C" fn one_open(
+    _vtab: *mut sqlite3_vtab,
+    pp_cursor: *mut *mut sqlite3_vtab_cursor,
+) -> c_int {
+    let cursor = Box::new(OneCursor {
+        base: sqlite3_vtab_cursor::default(),
+        eof: false,
+    });
+    *pp_cursor = Box::into_raw(cursor).cast();
+    SQLITE_OK
+}
+
+unsafe extern "C" fn one_close(cursor: *mut sqlite3_vtab_cursor) -> c_int {
+    drop(Box::from_raw(cursor.cast::<OneCursor>()));
+    SQLITE_OK
+}
+
+unsafe extern "C" fn one_filter(
+    cursor: *mut sqlite3_vtab_cursor,
+    _idx_num: c_int,
+    _idx_str: *const c_char,
+    _argc: c_int,
+    _argv: *mut *mut sqlite3_value,
+) -> c_int {
+    (*cursor.cast::<OneCursor>()).eof = false;
+    SQLITE_OK
+}
+
+unsafe extern "C" fn one_next(cursor: *mut sqlite3_vtab_cursor) -> c_int {
+    (*cursor.cast::<OneCursor>()).eof = true;
+    SQLITE_OK
+}
+
+unsafe extern "C" fn one_eof(cursor: *mut sqlite3_vtab_cursor) -> c_int {
+    c_int::from((*cursor.cast::<OneCursor>()).eof)
+}
+
+unsafe extern "C" fn one_column(
+    _cursor: *mut sqlite3_vtab_cursor,
+    ctx: *mut sqlite3_context,
+    _col: c_int,
+) -> c_int {
+    sqlite3_result_int64(ctx, 42);
+    SQLITE_OK
+}
+
+unsafe extern "C" fn one_rowid(
+    _cursor: *mut sqlite3_vtab_cursor,
+    rowid: *mut sqlite3_int64,
+) -> c_int {
+    *rowid = 1;
+    SQLITE_OK
+}
+
+fn one_module() -> &'static sqlite3_module {
+    let mut module: sqlite3_module = unsafe { std::mem::zeroed() };
+    module.xCreate = Some(one_connect);
+    module.xConnect = Some(one_connect);
+    module.xBestIndex = Some(one_best_index);
+    module.xDisconnect = Some(one_disconnect);
+    module.xDestroy = Some(one_disconnect);
+    module.xOpen = Some(one_open);
+    module.xClose = Some(one_close);
+    module.xFilter = Some(one_filter);
+    module.xNext = Some(one_next);
+    module.xEof = Some(one_eof);
+    module.xColumn = Some(one_column);
+    module.xRowid = Some(one_rowid);
+    Box::leak(Box::new(module))
+}
+
+unsafe fn create_one(db: *mut sqlite3, name: &std::ffi::CStr, config: VtabConfig) {
+    let ret = sqlite3_create_module_v2(
+        db,
+        name.as_ptr(),
+        one_module(),
+        Box::into_raw(Box::new(config)).cast(),
+        Some(drop_config),
+    );
+    assert_eq!(SQLITE_OK, ret);
+}
+
+unsafe extern "C" fn drop_config(config: *mut c_void) {
+    drop(Box::from_raw(config.cast::<VtabConfig>()));
+}
+
+#[wasm_bindgen_test]
+fn test_vtab_config_direct_only() {
+    let db = memory_db();
+    unsafe {
+        create_one(db, c"direct_one", VtabConfig::DirectOnly);
+        exec(
+            db,
+            c"CREATE VIRTUAL TABLE d USING direct_one;
+            CREATE TABLE t(value INTEGER);
+            CREATE TABLE log(value INTEGER);
+            CREATE TRIGGER t_insert AFTER INSERT ON t BEGIN
+                INSERT INTO log SELECT value FROM d;
+            END;",
+        );
+
+        let (_, rows) = get_table(db, "SELECT value FROM d").unwrap();
+        assert_eq!(vec![vec![Some("42".to_string())]], rows);
+
+        let err = get_table(db, "INSERT INTO t VALUES (1)").unwrap_err();
+        assert_eq!(SQLITE_ERROR, err.extended_code);
+        assert!(
+            err.message.contains("unsafe use of virtual table \"d\""),
+            "{}",
+            err.message
+        );
+        // Direct-only holds even though the schema is trusted.
+        assert!(set_trusted_schema(db, true).unwrap());
+        let (_, rows) = get_table(db, "SELECT * FROM log").unwrap();
+        assert!(rows.is_empty());
+
+        sqlite3_close(db);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_vtab_config_innocuous() {
+    let db = memory_db();
+    unsafe {
+        create_one(db, c"safe_one", VtabConfig::Innocuous);
+        create_one(db, c"app_one", VtabConfig::ConstraintSupport(false));
+        exec(
+            db,
+            c"CREATE VIRTUAL TABLE safe USING safe_one;
+            CREATE VIRTUAL TABLE app USING app_one;
+            CREATE VIEW safe_v AS SELECT value FROM safe;
+            CREATE VIEW app_v AS SELECT value FROM app;",
+        );
+
+        set_trusted_schema(db, false).unwrap();
+        let (_, rows) = get_table(db, "SELECT * FROM safe_v").unwrap();
+        assert_eq!(vec![vec![Some("42".to_string())]], rows);
+        let err = get_table(db, "SELECT * FROM app_v").unwrap_err();
+        assert!(
+            err.message.contains("unsafe use of virtual table \"app\""),
+            "{}",
+            err.message
+        );
+
+        // Outside of xCreate and xConnect the call is rejected.
+        let err = vtab_config(db, VtabConfig::Innocuous).unwrap_err();
+        assert_eq!(SQLITE_MISUSE, err.extended_code);
+
+        sqlite3_close(db);
+    }
+}
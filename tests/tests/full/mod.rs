@@ -1,6 +1,9 @@
+mod function;
+mod helpers;
 #[cfg(feature = "sqlite3mc")]
 mod sqlite3mc;
 mod vfs;
+mod vtab;
 
 use sqlite_wasm_rs::*;
 use std::ffi::CStr;
@@ -0,0 +1,110 @@
+use sqlite_wasm_rs::*;
+use sqlite_wasm_vfs::localstorage::{
+    install, LocalStorageCfg, LocalStorageCfgBuilder, LocalStorageError, LocalStorageUtil,
+};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use crate::full::{check_result, prepare_simple_db};
+
+/// Workers have no `localStorage`, install a `Map` backed one.
+fn install_localstorage_vfs(
+    options: &LocalStorageCfg,
+    default_vfs: bool,
+) -> Result<LocalStorageUtil, LocalStorageError> {
+    js_sys::Function::new_no_args(
+        "if (globalThis.localStorage === undefined) {
+            const map = new Map();
+            globalThis.localStorage = {
+                getItem: (key) => (map.has(key) ? map.get(key) : null),
+                setItem: (key, value) => void map.set(key, String(value)),
+                removeItem: (key) => void map.delete(key),
+                key: (idx) => [...map.keys()][idx] ?? null,
+                get length() { return map.size; },
+            };
+        }",
+    )
+    .call0(&wasm_bindgen::JsValue::UNDEFINED)
+    .unwrap();
+    install::<sqlite_wasm_rs::WasmOsCallback>(options, default_vfs)
+}
+
+fn open(filename: &std::ffi::CStr, vfs: &std::ffi::CStr) -> *mut sqlite3 {
+    let mut db = std::ptr::null_mut();
+    let ret = unsafe {
+        sqlite3_open_v2(
+            filename.as_ptr(),
+            &mut db as *mut _,
+            SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+            vfs.as_ptr(),
+        )
+    };
+    assert_eq!(SQLITE_OK, ret);
+    db
+}
+
+#[wasm_bindgen_test]
+fn test_localstorage_vfs_persistent() {
+    let util = install_localstorage_vfs(
+        &LocalStorageCfgBuilder::new()
+            .vfs_name("localstorage-persistent")
+            .build(),
+        false,
+    )
+    .unwrap();
+    util.clear_all().unwrap();
+
+    let db = open(c"settings.db", c"localstorage-persistent");
+    prepare_simple_db(db);
+    unsafe { sqlite3_close(db) };
+
+    assert!(util.exists("settings.db"));
+    assert!(util.usage() > 0);
+
+    let db = open(c"settings.db", c"localstorage-persistent");
+    check_result(db);
+    unsafe { sqlite3_close(db) };
+
+    let bytes = util.export_db("settings.db").unwrap();
+    assert_eq!(&bytes[..16], b"SQLite format 3\0");
+
+    util.delete_db("settings.db");
+    assert!(!util.exists("settings.db"));
+    assert_eq!(0, util.usage());
+}
+
+#[wasm_bindgen_test]
+fn test_localstorage_vfs_quota() {
+    let util = install_localstorage_vfs(
+        &LocalStorageCfgBuilder::new()
+            .vfs_name("localstorage-quota")
+            .quota(64 * 1024)
+            .build(),
+        false,
+    )
+    .unwrap();
+    util.clear_all().unwrap();
+
+    let db = open(c"quota.db", c"localstorage-quota");
+    let exec = |sql: &std::ffi::CStr| unsafe {
+        sqlite3_exec(
+            db,
+            sql.as_ptr(),
+            None,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    assert_eq!(SQLITE_OK, exec(c"CREATE TABLE t (data BLOB)"));
+    let usage = util.usage();
+
+    assert_eq!(
+        SQLITE_FULL,
+        exec(c"INSERT INTO t VALUES (randomblob(128 * 1024))")
+    );
+    // The failed commit was rolled back and nothing was stored.
+    assert_eq!(usage, util.usage());
+    assert_eq!(SQLITE_OK, exec(c"INSERT INTO t VALUES (x'00')"));
+
+    unsafe { sqlite3_close(db) };
+    util.delete_db("quota.db");
+}
@@ -1,3 +1,5 @@
+mod localstorage;
 mod memory;
 mod relaxed_idb;
 mod sahpool;
+mod scoped;
@@ -1,6 +1,7 @@
 use sqlite_wasm_rs::*;
 use sqlite_wasm_vfs::sahpool::{
-    install, OpfsSAHError, OpfsSAHPoolCfg, OpfsSAHPoolCfgBuilder, OpfsSAHPoolUtil,
+    install, opfs_available, sahpool_installed, OpfsSAHError, OpfsSAHPoolCfg,
+    OpfsSAHPoolCfgBuilder, OpfsSAHPoolUtil,
 };
 use wasm_bindgen_test::wasm_bindgen_test;
 
@@ -11,13 +12,17 @@ pub async fn install_opfs_sahpool(
     install::<sqlite_wasm_rs::WasmOsCallback>(options, default_vfs).await
 }
 
-use crate::full::{check_persistent, prepare_simple_db};
+use crate::full::{check_persistent, check_result, prepare_simple_db};
 
 #[wasm_bindgen_test]
 async fn test_opfs_sah_vfs_default() {
     install_opfs_sahpool(&OpfsSAHPoolCfg::default(), true)
         .await
         .unwrap();
+    assert_eq!(
+        Some(OpfsSAHPoolCfg::default().vfs_name),
+        sqlite_wasm_rs::helpers::default_vfs_name()
+    );
 
     let mut db = std::ptr::null_mut();
     let ret = unsafe {
@@ -34,6 +39,49 @@ async fn test_opfs_sah_vfs_default() {
     assert_eq!(!state, check_persistent(db));
 }
 
+#[wasm_bindgen_test]
+async fn test_opfs_sah_vfs_make_default() {
+    // The default vfs is process-wide, put it back for the other tests.
+    let previous = unsafe { sqlite3_vfs_find(std::ptr::null()) };
+    let cfg = OpfsSAHPoolCfgBuilder::new()
+        .vfs_name("test-vfs-make-default")
+        .directory("custom/make-default")
+        .clear_on_init(true)
+        .make_default(true)
+        .build();
+    let util = install_opfs_sahpool(&cfg, false).await.unwrap();
+    assert_eq!(
+        Some("test-vfs-make-default".to_string()),
+        sqlite_wasm_rs::helpers::default_vfs_name()
+    );
+
+    let mut db = std::ptr::null_mut();
+    let ret = unsafe {
+        sqlite3_open_v2(
+            c"foo.db".as_ptr().cast(),
+            &mut db as *mut _,
+            SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+            std::ptr::null_mut(),
+        )
+    };
+    assert_eq!(SQLITE_OK, ret);
+    prepare_simple_db(db);
+    check_result(db);
+    assert!(util.exists("foo.db").unwrap());
+
+    unsafe { sqlite3_close(db) };
+
+    // Installing the registered vfs again makes it the default again.
+    assert_eq!(SQLITE_OK, unsafe { sqlite3_vfs_register(previous, 1) });
+    install_opfs_sahpool(&cfg, false).await.unwrap();
+    assert_eq!(
+        Some("test-vfs-make-default".to_string()),
+        sqlite_wasm_rs::helpers::default_vfs_name()
+    );
+
+    assert_eq!(SQLITE_OK, unsafe { sqlite3_vfs_register(previous, 1) });
+}
+
 #[wasm_bindgen_test]
 async fn test_opfs_sah_vfs_default_error() {
     install_opfs_sahpool(&OpfsSAHPoolCfg::default(), true)
@@ -196,3 +244,218 @@ async fn test_opfs_sah_vfs_pause() {
 
     unsafe { sqlite3_close(db3) };
 }
+
+#[wasm_bindgen_test]
+async fn test_opfs_sah_vfs_migrate_db() {
+    let cfg = OpfsSAHPoolCfgBuilder::new()
+        .vfs_name("test-vfs-migrate")
+        .directory("custom/migrate")
+        .clear_on_init(true)
+        .build();
+    let util = install_opfs_sahpool(&cfg, false).await.unwrap();
+
+    let mut src = std::ptr::null_mut();
+    let ret = unsafe {
+        sqlite3_open_v2(
+            c"test_migrate_src.db".as_ptr().cast(),
+            &mut src as *mut _,
+            SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+            c"memvfs".as_ptr().cast(),
+        )
+    };
+    assert_eq!(SQLITE_OK, ret);
+    prepare_simple_db(src);
+    unsafe { sqlite3_close(src) };
+
+    unsafe {
+        sqlite_wasm_rs::helpers::migrate_db(
+            c"memvfs",
+            c"test_migrate_src.db",
+            c"test-vfs-migrate",
+            c"test_migrate_dst.db",
+        )
+    }
+    .unwrap();
+    assert!(util.exists("test_migrate_dst.db").unwrap());
+
+    let mut dst = std::ptr::null_mut();
+    let ret = unsafe {
+        sqlite3_open_v2(
+            c"test_migrate_dst.db".as_ptr().cast(),
+            &mut dst as *mut _,
+            SQLITE_OPEN_READWRITE,
+            c"test-vfs-migrate".as_ptr().cast(),
+        )
+    };
+    assert_eq!(SQLITE_OK, ret);
+    check_result(dst);
+    unsafe { sqlite3_close(dst) };
+
+    // The source must exist.
+    let err = unsafe {
+        sqlite_wasm_rs::helpers::migrate_db(
+            c"memvfs",
+            c"test_migrate_missing.db",
+            c"test-vfs-migrate",
+            c"test_migrate_dst.db",
+        )
+    }
+    .unwrap_err();
+    assert_eq!(SQLITE_CANTOPEN, err.extended_code);
+}
+
+#[wasm_bindgen_test]
+async fn test_opfs_sah_vfs_truncate_database() {
+    let cfg = OpfsSAHPoolCfgBuilder::new()
+        .vfs_name("test-vfs-truncate")
+        .directory("custom/truncate")
+        .clear_on_init(true)
+        .build();
+    let util = install_opfs_sahpool(&cfg, false).await.unwrap();
+
+    let open = || {
+        let mut db = std::ptr::null_mut();
+        let ret = unsafe {
+            sqlite3_open_v2(
+                c"test_truncate.db".as_ptr().cast(),
+                &mut db as *mut _,
+                SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+                c"test-vfs-truncate".as_ptr().cast(),
+            )
+        };
+        assert_eq!(SQLITE_OK, ret);
+        db
+    };
+
+    let db = open();
+    prepare_simple_db(db);
+    unsafe { sqlite3_close(db) };
+    assert!(!util.export_db("test_truncate.db").unwrap().is_empty());
+
+    unsafe {
+        sqlite_wasm_rs::helpers::truncate_database(c"test-vfs-truncate", c"test_truncate.db")
+    }
+    .unwrap();
+    // The slot stays associated, but the file is empty.
+    assert!(util.exists("test_truncate.db").unwrap());
+
+    let db = open();
+    assert!(unsafe { sqlite_wasm_rs::helpers::list_tables(db) }
+        .unwrap()
+        .is_empty());
+    unsafe { sqlite3_close(db) };
+
+    let err = unsafe {
+        sqlite_wasm_rs::helpers::truncate_database(c"test-vfs-truncate", c"test_missing.db")
+    }
+    .unwrap_err();
+    assert_eq!(SQLITE_CANTOPEN, err.extended_code);
+}
+
+#[wasm_bindgen_test]
+async fn test_opfs_sah_vfs_copy_table() {
+    let cfg = OpfsSAHPoolCfgBuilder::new()
+        .vfs_name("test-vfs-copy-table")
+        .directory("custom/copy-table")
+        .clear_on_init(true)
+        .build();
+    install_opfs_sahpool(&cfg, false).await.unwrap();
+
+    let src = crate::full::helpers::memory_db();
+    prepare_simple_db(src);
+
+    let mut dst = std::ptr::null_mut();
+    let ret = unsafe {
+        sqlite3_open_v2(
+            c"test_copy_table.db".as_ptr().cast(),
+            &mut dst as *mut _,
+            SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+            c"test-vfs-copy-table".as_ptr().cast(),
+        )
+    };
+    assert_eq!(SQLITE_OK, ret);
+
+    let rows = unsafe { sqlite_wasm_rs::helpers::copy_table(src, dst, "employees") }.unwrap();
+    assert_eq!(2, rows);
+    check_result(dst);
+    assert_eq!(
+        crate::full::helpers::table_rows(src, "employees"),
+        crate::full::helpers::table_rows(dst, "employees")
+    );
+
+    unsafe {
+        sqlite3_close(src);
+        sqlite3_close(dst);
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_opfs_sah_vfs_flush_batch() {
+    let cfg = OpfsSAHPoolCfgBuilder::new()
+        .vfs_name("test-vfs-flush-batch")
+        .directory("custom/flush-batch")
+        .clear_on_init(true)
+        .flush_batch(std::time::Duration::from_millis(100), 16)
+        .build();
+    let util = install_opfs_sahpool(&cfg, false).await.unwrap();
+
+    let open = || {
+        let mut db = std::ptr::null_mut();
+        let ret = unsafe {
+            sqlite3_open_v2(
+                c"test_flush_batch.db".as_ptr().cast(),
+                &mut db as *mut _,
+                SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+                c"test-vfs-flush-batch".as_ptr().cast(),
+            )
+        };
+        assert_eq!(SQLITE_OK, ret);
+        db
+    };
+
+    let db = open();
+    crate::full::helpers::exec(
+        db,
+        c"CREATE TABLE t (id INTEGER PRIMARY KEY, value INTEGER)",
+    );
+    // Every insert is its own transaction with its own sync.
+    for idx in 0..200 {
+        let sql =
+            std::ffi::CString::new(format!("INSERT INTO t VALUES ({idx}, {})", idx * 2)).unwrap();
+        crate::full::helpers::exec(db, &sql);
+    }
+    unsafe { sqlite3_close(db) };
+
+    let db = open();
+    let sums = unsafe {
+        sqlite_wasm_rs::helpers::query_column::<i64>(
+            db,
+            "SELECT count(*) FROM t UNION ALL SELECT sum(value) FROM t",
+            (),
+        )
+    }
+    .unwrap();
+    assert_eq!(sums, [200, 199 * 200]);
+    unsafe { sqlite3_close(db) };
+
+    util.delete_db("test_flush_batch.db").unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn test_opfs_sah_vfs_installed() {
+    assert!(opfs_available());
+
+    let name = "test-vfs-installed";
+    assert!(!sahpool_installed(Some(name.into())));
+    let cfg = OpfsSAHPoolCfgBuilder::new()
+        .vfs_name(name)
+        .directory("custom/installed")
+        .build();
+    let util = install_opfs_sahpool(&cfg, false).await.unwrap();
+    assert!(sahpool_installed(Some(name.into())));
+
+    util.pause_vfs().unwrap();
+    assert!(!sahpool_installed(Some(name.into())));
+    util.unpause_vfs().await.unwrap();
+    assert!(sahpool_installed(Some(name.into())));
+}
@@ -0,0 +1,123 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use sqlite_wasm_rs::utils::{
+    ffi::{sqlite3_vfs, SQLITE_IOERR},
+    registered_vfs, MemChunksFile, OsCallback, SQLiteIoMethods, SQLiteVfs, SQLiteVfsFile,
+    ScopedVfs, VfsError, VfsResult, VfsStore,
+};
+use sqlite_wasm_rs::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use crate::full::{check_result, prepare_simple_db};
+
+type Files = RefCell<HashMap<String, MemChunksFile>>;
+
+/// A minimal in-memory VFS, registered only for the test.
+struct ScopedStore;
+
+impl VfsStore<MemChunksFile, Files> for ScopedStore {
+    fn add_file(vfs: *mut sqlite3_vfs, file: &str, _flags: i32) -> VfsResult<()> {
+        let files = unsafe { Self::app_data(vfs) };
+        files
+            .borrow_mut()
+            .insert(file.into(), MemChunksFile::waiting_for_write());
+        Ok(())
+    }
+
+    fn contains_file(vfs: *mut sqlite3_vfs, file: &str) -> VfsResult<bool> {
+        let files = unsafe { Self::app_data(vfs) };
+        Ok(files.borrow().contains_key(file))
+    }
+
+    fn delete_file(vfs: *mut sqlite3_vfs, file: &str) -> VfsResult<()> {
+        let files = unsafe { Self::app_data(vfs) };
+        files.borrow_mut().remove(file);
+        Ok(())
+    }
+
+    fn with_file<F: Fn(&MemChunksFile) -> VfsResult<i32>>(
+        vfs_file: &SQLiteVfsFile,
+        f: F,
+    ) -> VfsResult<i32> {
+        let name = unsafe { vfs_file.name() };
+        let files = unsafe { Self::app_data(vfs_file.vfs) };
+        match files.borrow().get(name) {
+            Some(file) => f(file),
+            None => Err(VfsError::new(SQLITE_IOERR, format!("{name} not found"))),
+        }
+    }
+
+    fn with_file_mut<F: Fn(&mut MemChunksFile) -> VfsResult<i32>>(
+        vfs_file: &SQLiteVfsFile,
+        f: F,
+    ) -> VfsResult<i32> {
+        let name = unsafe { vfs_file.name() };
+        let files = unsafe { Self::app_data(vfs_file.vfs) };
+        match files.borrow_mut().get_mut(name) {
+            Some(file) => f(file),
+            None => Err(VfsError::new(SQLITE_IOERR, format!("{name} not found"))),
+        }
+    }
+}
+
+struct ScopedIoMethods;
+
+impl SQLiteIoMethods for ScopedIoMethods {
+    type File = MemChunksFile;
+    type AppData = Files;
+    type Store = ScopedStore;
+
+    const VERSION: ::std::os::raw::c_int = 1;
+}
+
+struct ScopedMemVfs<C>(PhantomData<C>);
+
+impl<C: OsCallback> SQLiteVfs<ScopedIoMethods> for ScopedMemVfs<C> {
+    const VERSION: ::std::os::raw::c_int = 1;
+
+    fn sleep(dur: Duration) {
+        C::sleep(dur);
+    }
+
+    fn random(buf: &mut [u8]) {
+        C::random(buf);
+    }
+
+    fn epoch_timestamp_in_ms() -> i64 {
+        C::epoch_timestamp_in_ms()
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_scoped_vfs() {
+    {
+        let vfs = ScopedVfs::<ScopedIoMethods>::register::<ScopedMemVfs<WasmOsCallback>>(
+            "scoped-vfs",
+            Files::default(),
+            false,
+        )
+        .unwrap();
+        assert_eq!(Some(vfs.as_ptr()), registered_vfs("scoped-vfs").unwrap());
+
+        let mut db = std::ptr::null_mut();
+        let ret = unsafe {
+            sqlite3_open_v2(
+                c"scoped.db".as_ptr().cast(),
+                &mut db as *mut _,
+                SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+                c"scoped-vfs".as_ptr().cast(),
+            )
+        };
+        assert_eq!(SQLITE_OK, ret);
+        prepare_simple_db(db);
+        check_result(db);
+        unsafe { sqlite3_close(db) };
+
+        assert!(vfs.app_data().borrow().contains_key("scoped.db"));
+    }
+
+    assert!(registered_vfs("scoped-vfs").unwrap().is_none());
+}
@@ -0,0 +1,223 @@
+use sqlite_wasm_rs::*;
+use std::ffi::{c_char, c_int, c_void};
+use std::sync::atomic::{AtomicI32, Ordering};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+/// Values returned by the `seq` table.
+const SEQ: std::ops::RangeInclusive<i64> = 1..=10;
+
+static FILTER_CALLS: AtomicI32 = AtomicI32::new(0);
+static LAST_DISTINCT: AtomicI32 = AtomicI32::new(-1);
+
+#[repr(C)]
+struct SeqCursor {
+    base: sqlite3_vtab_cursor,
+    values: Vec<i64>,
+    idx: usize,
+}
+
+unsafe extern "C" fn seq_connect(
+    db: *mut sqlite3,
+    _aux: *mut c_void,
+    _argc: c_int,
+    _argv: *const *const c_char,
+    pp_vtab: *mut *mut sqlite3_vtab,
+    _err: *mut *mut c_char,
+) -> c_int {
+    let ret = sqlite3_declare_vtab(db, c"CREATE TABLE x(value INTEGER)".as_ptr());
+    if ret == SQLITE_OK {
+        *pp_vtab = Box::into_raw(Box::<sqlite3_vtab>::default());
+    }
+    ret
+}
+
+unsafe extern "C" fn seq_disconnect(vtab: *mut sqlite3_vtab) -> c_int {
+    drop(Box::from_raw(vtab));
+    SQLITE_OK
+}
+
+unsafe extern "C" fn seq_best_index(
+    _vtab: *mut sqlite3_vtab,
+    info: *mut sqlite3_index_info,
+) -> c_int {
+    LAST_DISTINCT.store(sqlite3_vtab_distinct(info), Ordering::Relaxed);
+
+    let info = &mut *info;
+    info.estimatedCost = SEQ.count() as f64;
+    for i in 0..info.nConstraint {
+        let constraint = &*info.aConstraint.add(i as usize);
+        if constraint.usable == 0
+            || constraint.iColumn != 0
+            || c_int::from(constraint.op) != SQLITE_INDEX_CONSTRAINT_EQ
+        {
+            continue;
+        }
+        // Ask for the whole IN list at once, instead of one xFilter call per value.
+        if sqlite3_vtab_in(info, i, -1) != 0 {
+            sqlite3_vtab_in(info, i, 1);
+            info.idxNum = 1;
+        } else {
+            info.idxNum = 2;
+        }
+        let usage = &mut *info.aConstraintUsage.add(i as usize);
+        usage.argvIndex = 1;
+        usage.omit = 1;
+        info.estimatedCost = 1.0;
+        break;
+    }
+    SQLITE_OK
+}
+
+unsafe extern "C" fn seq_open(
+    _vtab: *mut sqlite3_vtab,
+    pp_cursor: *mut *mut sqlite3_vtab_cursor,
+) -> c_int {
+    let cursor = Box::new(SeqCursor {
+        base: sqlite3_vtab_cursor::default(),
+        values: vec![],
+        idx: 0,
+    });
+    *pp_cursor = Box::into_raw(cursor).cast();
+    SQLITE_OK
+}
+
+unsafe extern "C" fn seq_close(cursor: *mut sqlite3_vtab_cursor) -> c_int {
+    drop(Box::from_raw(cursor.cast::<SeqCursor>()));
+    SQLITE_OK
+}
+
+unsafe extern "C" fn seq_filter(
+    cursor: *mut sqlite3_vtab_cursor,
+    idx_num: c_int,
+    _idx_str: *const c_char,
+    _argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) -> c_int {
+    FILTER_CALLS.fetch_add(1, Ordering::Relaxed);
+    let cursor = &mut *cursor.cast::<SeqCursor>();
+
+    let mut wanted = vec![];
+    match idx_num {
+        1 => {
+            let mut value: *mut sqlite3_value = std::ptr::null_mut();
+            let mut ret = sqlite3_vtab_in_first(*argv, &mut value as *mut _);
+            while ret == SQLITE_OK && !value.is_null() {
+                wanted.push(sqlite3_value_int64(value));
+                ret = sqlite3_vtab_in_next(*argv, &mut value as *mut _);
+            }
+            if ret != SQLITE_OK && ret != SQLITE_DONE {
+                return ret;
+            }
+        }
+        2 => wanted.push(sqlite3_value_int64(*argv)),
+        _ => wanted.extend(SEQ),
+    }
+
+    cursor.values = SEQ.filter(|value| wanted.contains(value)).collect();
+    cursor.idx = 0;
+    SQLITE_OK
+}
+
+unsafe extern "C" fn seq_next(cursor: *mut sqlite3_vtab_cursor) -> c_int {
+    (*cursor.cast::<SeqCursor>()).idx += 1;
+    SQLITE_OK
+}
+
+unsafe extern "C" fn seq_eof(cursor: *mut sqlite3_vtab_cursor) -> c_int {
+    let cursor = &*cursor.cast::<SeqCursor>();
+    c_int::from(cursor.idx >= cursor.values.len())
+}
+
+unsafe extern "C" fn seq_column(
+    cursor: *mut sqlite3_vtab_cursor,
+    ctx: *mut sqlite3_context,
+    _col: c_int,
+) -> c_int {
+    let cursor = &*cursor.cast::<SeqCursor>();
+    sqlite3_result_int64(ctx, cursor.values[cursor.idx]);
+    SQLITE_OK
+}
+
+unsafe extern "C" fn seq_rowid(
+    cursor: *mut sqlite3_vtab_cursor,
+    rowid: *mut sqlite3_int64,
+) -> c_int {
+    let cursor = &*cursor.cast::<SeqCursor>();
+    *rowid = cursor.values[cursor.idx];
+    SQLITE_OK
+}
+
+fn seq_module() -> &'static sqlite3_module {
+    let mut module: sqlite3_module = unsafe { std::mem::zeroed() };
+    module.xConnect = Some(seq_connect);
+    module.xBestIndex = Some(seq_best_index);
+    module.xDisconnect = Some(seq_disconnect);
+    module.xOpen = Some(seq_open);
+    module.xClose = Some(seq_close);
+    module.xFilter = Some(seq_filter);
+    module.xNext = Some(seq_next);
+    module.xEof = Some(seq_eof);
+    module.xColumn = Some(seq_column);
+    module.xRowid = Some(seq_rowid);
+    Box::leak(Box::new(module))
+}
+
+fn query(db: *mut sqlite3, sql: &std::ffi::CStr) -> Vec<i64> {
+    let mut stmt = std::ptr::null_mut();
+    let mut values = vec![];
+    unsafe {
+        let ret = sqlite3_prepare_v3(
+            db,
+            sql.as_ptr(),
+            -1,
+            0,
+            &mut stmt as *mut _,
+            std::ptr::null_mut(),
+        );
+        assert_eq!(SQLITE_OK, ret);
+        while sqlite3_step(stmt) == SQLITE_ROW {
+            values.push(sqlite3_column_int64(stmt, 0));
+        }
+        assert_eq!(SQLITE_OK, sqlite3_finalize(stmt));
+    }
+    values
+}
+
+#[wasm_bindgen_test]
+fn test_vtab_in_and_distinct() {
+    let mut db = std::ptr::null_mut();
+    unsafe {
+        let ret = sqlite3_open_v2(
+            c":memory:".as_ptr(),
+            &mut db as *mut _,
+            SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+            std::ptr::null(),
+        );
+        assert_eq!(SQLITE_OK, ret);
+        let ret = sqlite3_create_module_v2(
+            db,
+            c"seq".as_ptr(),
+            seq_module(),
+            std::ptr::null_mut(),
+            None,
+        );
+        assert_eq!(SQLITE_OK, ret);
+    }
+
+    FILTER_CALLS.store(0, Ordering::Relaxed);
+    assert_eq!(
+        vec![3, 7],
+        query(db, c"SELECT value FROM seq WHERE value IN (7, 42, 3)")
+    );
+    // The IN list was handled by a single xFilter call.
+    assert_eq!(1, FILTER_CALLS.load(Ordering::Relaxed));
+
+    assert_eq!(vec![5], query(db, c"SELECT value FROM seq WHERE value = 5"));
+
+    assert_eq!(10, query(db, c"SELECT DISTINCT value FROM seq").len());
+    assert_eq!(2, LAST_DISTINCT.load(Ordering::Relaxed));
+    assert_eq!(10, query(db, c"SELECT value FROM seq").len());
+    assert_eq!(0, LAST_DISTINCT.load(Ordering::Relaxed));
+
+    unsafe { sqlite3_close(db) };
+}